@@ -1,4 +1,4 @@
-use std::error::Error;
+use std::{error::Error, path::Path};
 
 use alloy_core::primitives::keccak256;
 use alloy_dyn_abi::DynSolType;
@@ -6,7 +6,8 @@ use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 use syn::{
     parse::{Parse, ParseStream},
-    FnArg, Ident, ImplItemMethod, LitStr, PathArguments, ReturnType, TraitItemMethod, Type,
+    Attribute, Expr, FnArg, Ident, ImplItemMethod, ItemImpl, LitStr, PathArguments, ReturnType,
+    Token, TraitItemMethod, Type,
 };
 
 // Unified method info from `ImplItemMethod` and `TraitItemMethod`
@@ -15,6 +16,7 @@ pub struct MethodInfo<'a> {
     name: &'a Ident,
     args: Vec<syn::FnArg>,
     return_type: &'a ReturnType,
+    attrs: &'a [Attribute],
 }
 
 impl<'a> From<&'a ImplItemMethod> for MethodInfo<'a> {
@@ -23,6 +25,7 @@ impl<'a> From<&'a ImplItemMethod> for MethodInfo<'a> {
             name: &method.sig.ident,
             args: method.sig.inputs.iter().cloned().collect(),
             return_type: &method.sig.output,
+            attrs: &method.attrs,
         }
     }
 }
@@ -33,18 +36,33 @@ impl<'a> From<&'a TraitItemMethod> for MethodInfo<'a> {
             name: &method.sig.ident,
             args: method.sig.inputs.iter().cloned().collect(),
             return_type: &method.sig.output,
+            attrs: &method.attrs,
         }
     }
 }
 
 impl<'a> MethodInfo<'a> {
+    // Methods that take `self` by value are mutable too: they conceptually consume the contract
+    // (e.g. single-use escrows), even though its storage persists after the call
     pub fn is_mutable(&self) -> bool {
         match self.args.first() {
-            Some(FnArg::Receiver(receiver)) => receiver.mutability.is_some(),
+            Some(FnArg::Receiver(receiver)) => {
+                receiver.reference.is_none() || receiver.mutability.is_some()
+            }
             Some(FnArg::Typed(_)) => panic!("First argument must be self"),
             None => panic!("Expected `self` as the first arg"),
         }
     }
+
+    pub fn takes_self_by_value(&self) -> bool {
+        matches!(self.args.first(), Some(FnArg::Receiver(receiver)) if receiver.reference.is_none())
+    }
+
+    // `#[cfg(..)]` attributes of the method, to be replicated on the code generated for it (e.g.
+    // its dispatch arm), so that cfg-ed out methods are left out entirely
+    pub fn cfg_attrs(&self) -> Vec<&'a Attribute> {
+        self.attrs.iter().filter(|attr| attr.path.is_ident("cfg")).collect()
+    }
 }
 
 // Helper function to get the parameter names + types of a method
@@ -117,6 +135,154 @@ impl Parse for InterfaceArgs {
     }
 }
 
+pub struct ContractArgs {
+    /// Whether to generate an `execute(bytes)` entry, dispatching calldata forwarded by a relayer
+    pub execute: bool,
+}
+
+impl Parse for ContractArgs {
+    fn parse(input: ParseStream) -> Result<Self, syn::Error> {
+        let mut execute = false;
+        if !input.is_empty() {
+            let option = input.parse::<Ident>()?;
+            match option.to_string().as_str() {
+                "execute" => execute = true,
+                invalid => {
+                    return Err(syn::Error::new(
+                        option.span(),
+                        format!("unsupported option: {}. Only 'execute' is supported", invalid),
+                    ))
+                }
+            }
+        }
+
+        Ok(ContractArgs { execute })
+    }
+}
+
+// Args of `#[non_zero(to, from, error = MyError::ZeroAddress)]`
+pub struct NonZeroArgs {
+    /// Names of the `Address` params that can't be the zero address
+    pub params: Vec<Ident>,
+    /// Error to revert with, or a `Zero address: <param>` message if unset
+    pub error: Option<Expr>,
+}
+
+impl Parse for NonZeroArgs {
+    fn parse(input: ParseStream) -> Result<Self, syn::Error> {
+        let mut params = Vec::new();
+        let mut error = None;
+        while !input.is_empty() {
+            let ident = input.parse::<Ident>()?;
+            if input.peek(Token![=]) {
+                if ident != "error" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("unsupported option: {}. Only 'error' is supported", ident),
+                    ));
+                }
+                input.parse::<Token![=]>()?;
+                error = Some(input.parse::<Expr>()?);
+            } else {
+                params.push(ident);
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        if params.is_empty() {
+            return Err(input.error("expected the names of the address params to check"));
+        }
+        Ok(NonZeroArgs { params, error })
+    }
+}
+
+// Helper function to generate the zero-address checks of the `#[non_zero(..)]` attributes of a
+// method, given the names its decoded args are bound to in the dispatch.
+// As they run in the dispatch, internal calls to the method (e.g. `self.transfer(..)`) skip them.
+pub fn generate_non_zero_checks(method: &ImplItemMethod, arg_names: &[Ident]) -> TokenStream {
+    let params: Vec<_> = method
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) => Some(pat_type),
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+
+    let mut checks = Vec::new();
+    let non_zero_attrs = method.attrs.iter().filter(|attr| {
+        attr.path.segments.last().is_some_and(|segment| segment.ident == "non_zero")
+    });
+    for attr in non_zero_attrs {
+        let args = attr.parse_args::<NonZeroArgs>().expect("Invalid `non_zero` args");
+        for param in &args.params {
+            let position = params
+                .iter()
+                .position(|pat_type| matches!(&*pat_type.pat, syn::Pat::Ident(pat) if pat.ident == *param))
+                .unwrap_or_else(|| panic!("`non_zero` param `{}` not found", param));
+            match &*params[position].ty {
+                Type::Path(type_path) if type_path.path.segments.last().is_some_and(|segment| segment.ident == "Address") => {}
+                _ => panic!("`non_zero` param `{}` must be an `Address`", param),
+            }
+
+            let arg_name = &arg_names[position];
+            let revert = match &args.error {
+                Some(error) => quote! {
+                    eth_riscv_runtime::revert_with_error(&eth_riscv_runtime::error::Error::abi_encode(&(#error)))
+                },
+                None => {
+                    let message = format!("Zero address: {}", param);
+                    quote! { eth_riscv_runtime::revert_with_error(#message.as_bytes()) }
+                }
+            };
+            checks.push(quote! {
+                if #arg_name == alloy_core::primitives::Address::ZERO {
+                    #revert;
+                }
+            });
+        }
+    }
+
+    quote! { #( #checks )* }
+}
+
+// Helper function to generate the `execute` entry of `#[contract(execute)]`.
+// The forwarded calldata runs through the contract's own dispatch, and its output is returned as
+// the `bytes` of `execute(bytes) returns (bytes)` (reverts are forwarded as is). Only relayers
+// trusted by the contract's `is_trusted_relayer` method can use it, as the forwarded calls run
+// with the relayer as `msg.sender`.
+pub fn generate_execute_method() -> ImplItemMethod {
+    syn::parse_quote! {
+        pub fn execute(&mut self, data: alloy_core::primitives::Bytes) -> alloy_core::primitives::Bytes {
+            if !self.is_trusted_relayer(eth_riscv_runtime::msg_sender()) {
+                eth_riscv_runtime::revert_with_error("Untrusted relayer".as_bytes());
+            }
+
+            let output = alloy_core::primitives::Bytes::from(eth_riscv_runtime::Contract::dispatch(self, &data));
+            alloy_sol_types::SolValue::abi_encode(&output).into()
+        }
+    }
+}
+
+// Helper function to check that a `#[contract(execute)]` impl defines the `is_trusted_relayer`
+// method that `execute` relies on, returning a compile error otherwise
+pub fn check_trusted_relayer(input: &ItemImpl, public_methods: &[&ImplItemMethod]) -> Option<TokenStream> {
+    if public_methods.iter().any(|method| method.sig.ident == "is_trusted_relayer") {
+        return None;
+    }
+
+    Some(
+        syn::Error::new_spanned(
+            &input.self_ty,
+            "`#[contract(execute)]` requires a `pub fn is_trusted_relayer(&self, relayer: Address) -> bool` method",
+        )
+        .to_compile_error(),
+    )
+}
+
 // Helper function to generate interface impl from user-defined methods
 pub fn generate_interface<T>(
     methods: &[&T],
@@ -137,11 +303,16 @@ where
     let immut_method_impls = immut_methods
         .iter()
         .map(|method| generate_method_impl(method, interface_style, false));
+    let selector_consts = mut_methods
+        .iter()
+        .chain(&immut_methods)
+        .map(|method| generate_selector_const(method, interface_style));
 
     quote! {
         use core::marker::PhantomData;
         pub struct #interface_name<C: CallCtx> {
             address: Address,
+            backend: &'static dyn CallBackend,
             _ctx: PhantomData<C>
         }
 
@@ -159,6 +330,7 @@ where
             fn into_interface(self) -> #interface_name<C> {
                 #interface_name {
                     address: self.address,
+                    backend: self.backend,
                     _ctx: PhantomData
                 }
             }
@@ -170,6 +342,7 @@ where
             fn from_builder(builder: InterfaceBuilder<Self>) -> Self {
                 Self {
                     address: builder.address,
+                    backend: &eth_riscv_runtime::SyscallBackend,
                     _ctx: PhantomData
                 }
             }
@@ -179,6 +352,14 @@ where
             pub fn address(&self) -> Address {
                 self.address
             }
+
+            /// Routes the calls of this interface through `backend`, rather than the syscalls
+            pub fn with_backend(mut self, backend: &'static dyn CallBackend) -> Self {
+                self.backend = backend;
+                self
+            }
+
+            #(#selector_consts)*
         }
 
         impl<C: StaticCtx> #interface_name<C> {
@@ -191,6 +372,26 @@ where
     }
 }
 
+// Generates the `<METHOD>_SELECTOR` const of a method (e.g. `TRANSFER_SELECTOR`)
+fn generate_selector_const(
+    method: &MethodInfo,
+    interface_style: Option<InterfaceNamingStyle>,
+) -> TokenStream {
+    let selector =
+        generate_fn_selector(method, interface_style).expect("Unable to generate fn selector");
+    let signature =
+        generate_fn_signature(method, interface_style).expect("Unable to generate fn signature");
+    let doc = format!(" Selector of `{}`", signature);
+    let name = format_ident!("{}_SELECTOR", method.name.to_string().to_uppercase());
+    let cfg_attrs = method.cfg_attrs();
+
+    quote! {
+        #[doc = #doc]
+        #(#cfg_attrs)*
+        pub const #name: [u8; 4] = [#(#selector),*];
+    }
+}
+
 fn generate_method_impl(
     method: &MethodInfo,
     interface_style: Option<InterfaceNamingStyle>,
@@ -202,6 +403,16 @@ fn generate_method_impl(
         generate_fn_selector(method, interface_style).expect("Unable to generate fn selector"),
     );
 
+    // Documents the selector of the call, to help diagnosing encoding mismatches
+    let signature =
+        generate_fn_signature(method, interface_style).expect("Unable to generate fn signature");
+    let doc = format!(" Calls `{}` (selector `{:#010x}`)", signature, method_selector);
+    let cfg_attrs = method.cfg_attrs();
+    let attrs = quote! {
+        #[doc = #doc]
+        #(#cfg_attrs)*
+    };
+
     let (arg_names, arg_types) = get_arg_props_skip_first(method);
 
     let calldata = if arg_names.is_empty() {
@@ -230,12 +441,12 @@ fn generate_method_impl(
 
     let (call_fn, self_param) = if is_mutable {
         (
-            quote! { eth_riscv_runtime::call_contract },
+            quote! { self.backend.call },
             quote! { &mut self },
         )
     } else {
         (
-            quote! { eth_riscv_runtime::staticcall_contract },
+            quote! { self.backend.staticcall },
             quote! { &self},
         )
     };
@@ -244,6 +455,7 @@ fn generate_method_impl(
     match extract_wrapper_types(&method.return_type) {
         // If `Result<T, E>` handle each individual type
         WrapperType::Result(ok_type, err_type) => quote! {
+            #attrs
             pub fn #name(#self_param, #(#arg_names: #arg_types),*) -> Result<#ok_type, #err_type>  {
                 use alloy_sol_types::SolValue;
                 use alloc::vec::Vec;
@@ -257,61 +469,100 @@ fn generate_method_impl(
                     None
                 );
 
-                match <#ok_type>::abi_decode_validate(&result) {
-                    Ok(decoded) => Ok(decoded),
-                    Err(_) => Err(<#err_type>::abi_decode_validate(&result))
+                // Reverts carry the encoded error, so only decode it when the call failed
+                match result {
+                    Ok(data) => Ok(<#ok_type>::abi_decode_validate(&data)
+                        .expect("Unable to decode call output")),
+                    Err(revert_data) => Err(
+                        <#err_type as eth_riscv_runtime::error::Error>::abi_decode(&revert_data, true)
+                    ),
                 }
             }
         },
         // If `Option<T>` unwrap the type to decode, and wrap it back
         WrapperType::Option(return_ty) => {
-            quote! {
-                pub fn #name(#self_param, #(#arg_names: #arg_types),*) -> Option<#return_ty> {
-                    use alloy_sol_types::SolValue;
-                    use alloc::vec::Vec;
+            generate_optional_method_impl(
+                method,
+                &return_ty,
+                &calldata,
+                &call_fn,
+                &self_param,
+                &attrs,
+            )
+        }
+        // If raw `Bytes`, return the call output verbatim
+        WrapperType::None if is_raw_bytes(return_type) => quote! {
+            #attrs
+            pub fn #name(#self_param, #(#arg_names: #arg_types),*) -> Option<alloy_core::primitives::Bytes> {
+                use alloc::vec::Vec;
 
-                    #calldata
+                #calldata
 
-                    let result = #call_fn(
-                        self.address,
-                        0_u64,
-                        &complete_calldata,
-                        None
-                    );
+                let result = #call_fn(
+                    self.address,
+                    0_u64,
+                    &complete_calldata,
+                    None
+                );
 
-                    match <#return_ty>::abi_decode_validate(&result) {
-                        Ok(decoded) => Some(decoded),
-                        Err(_) => None
-                    }
-                }
+                result.ok()
             }
-        }
+        },
         // Otherwise, simply decode the value + wrap it in an `Option` to force error-handling
         WrapperType::None => {
             let return_ty = match return_type {
                 ReturnType::Default => quote! { () },
                 ReturnType::Type(_, ty) => quote! { #ty },
             };
-            quote! {
-                pub fn #name(#self_param, #(#arg_names: #arg_types),*) -> Option<#return_ty> {
-                    use alloy_sol_types::SolValue;
-                    use alloc::vec::Vec;
+            generate_optional_method_impl(
+                method,
+                &return_ty,
+                &calldata,
+                &call_fn,
+                &self_param,
+                &attrs,
+            )
+        }
+    }
+}
+
+// Generates a method returning `Option<T>`, which is `None` whether the call reverted or its output
+// couldn't be decoded, along with a `try_` variant that tells both cases apart. Its outer `Result`
+// holds the revert data, while a successful call with undecodable output (e.g. an empty one, if
+// the target has no code) yields `Ok(None)`.
+fn generate_optional_method_impl(
+    method: &MethodInfo,
+    return_ty: &TokenStream,
+    calldata: &TokenStream,
+    call_fn: &TokenStream,
+    self_param: &TokenStream,
+    attrs: &TokenStream,
+) -> TokenStream {
+    let name = method.name;
+    let try_name = format_ident!("try_{}", name);
+    let (arg_names, arg_types) = get_arg_props_skip_first(method);
 
-                    #calldata
+    quote! {
+        #attrs
+        pub fn #try_name(#self_param, #(#arg_names: #arg_types),*) -> Result<Option<#return_ty>, alloy_core::primitives::Bytes> {
+            use alloy_sol_types::SolValue;
+            use alloc::vec::Vec;
+
+            #calldata
+
+            let result = #call_fn(
+                self.address,
+                0_u64,
+                &complete_calldata,
+                None
+            );
 
-                    let result = #call_fn(
-                        self.address,
-                        0_u64,
-                        &complete_calldata,
-                        None
-                    );
+            result.map(|data| <#return_ty>::abi_decode_validate(&data).ok())
+        }
 
-                    match <#return_ty>::abi_decode_validate(&result) {
-                        Ok(decoded) => Some(decoded),
-                        Err(_) => None
-                    }
-                }
-            }
+        #attrs
+        pub fn #name(#self_param, #(#arg_names: #arg_types),*) -> Option<#return_ty> {
+            self.#try_name(#(#arg_names),*).ok().flatten()
         }
     }
 }
@@ -384,10 +635,35 @@ pub fn extract_wrapper_types(return_type: &ReturnType) -> WrapperType {
 }
 
 // Helper function to generate fn selector
+/// Returns `true` if the return type is raw `Bytes`, which are returned verbatim
+/// (without ABI-encoding), as they are assumed to be already ABI-framed.
+pub fn is_raw_bytes(return_type: &ReturnType) -> bool {
+    match return_type {
+        ReturnType::Type(_, ty) => {
+            match ty.as_ref() {
+                Type::Path(type_path) => type_path.path.segments.last().is_some_and(|segment| {
+                    segment.ident == "Bytes" && segment.arguments.is_empty()
+                }),
+                _ => false,
+            }
+        }
+        ReturnType::Default => false,
+    }
+}
+
 pub fn generate_fn_selector(
     method: &MethodInfo,
     style: Option<InterfaceNamingStyle>,
 ) -> Option<[u8; 4]> {
+    let signature = generate_fn_signature(method, style)?;
+    keccak256(signature.as_bytes())[..4].try_into().ok()
+}
+
+// Helper function to get the canonical signature of a method (e.g. `transfer(address,uint256)`)
+pub fn generate_fn_signature(
+    method: &MethodInfo,
+    style: Option<InterfaceNamingStyle>,
+) -> Option<String> {
     let name = match style {
         None => method.name.to_string(),
         Some(style) => match style {
@@ -407,9 +683,7 @@ pub fn generate_fn_selector(
         .collect::<Vec<_>>()
         .join(",");
 
-    let selector = format!("{}({})", name, args_str);
-    let selector_bytes = keccak256(selector.as_bytes())[..4].try_into().ok()?;
-    Some(selector_bytes)
+    Some(format!("{}({})", name, args_str))
 }
 
 // Helper function to convert rust types to their solidity equivalent
@@ -429,16 +703,29 @@ pub fn rust_type_to_sol_type(ty: &Type) -> Result<DynSolType, &'static str> {
                 "bool" | "Bool" => Ok(DynSolType::Bool),
                 "String" | "str" => Ok(DynSolType::String),
                 "Bytes" => Ok(DynSolType::Bytes),
-                // Fixed-size bytes
+                // Native integers
+                "u8" => Ok(DynSolType::Uint(8)),
+                "u16" => Ok(DynSolType::Uint(16)),
+                "u32" => Ok(DynSolType::Uint(32)),
+                "u64" => Ok(DynSolType::Uint(64)),
+                "u128" => Ok(DynSolType::Uint(128)),
+                "i8" => Ok(DynSolType::Int(8)),
+                "i16" => Ok(DynSolType::Int(16)),
+                "i32" => Ok(DynSolType::Int(32)),
+                "i64" => Ok(DynSolType::Int(64)),
+                "i128" => Ok(DynSolType::Int(128)),
+                // Fixed-size bytes (alloy aliases are sized in bits, i.e. `B32` is `bytes4`)
                 b if b.starts_with('B') => {
                     let size: usize = b
                         .trim_start_matches('B')
                         .parse()
                         .map_err(|_| "Invalid fixed bytes size")?;
-                    if size > 0 && size <= 32 {
-                        Ok(DynSolType::FixedBytes(size))
+                    // Whole bytes only, from `bytes1` to `bytes32`
+                    let bytes = size / 8;
+                    if (1..=32).contains(&bytes) && bytes * 8 == size {
+                        Ok(DynSolType::FixedBytes(bytes))
                     } else {
-                        Err("Invalid fixed bytes size (between 1-32)")
+                        Err("Invalid fixed bytes size (multiple of 8 + leq 256)")
                     }
                 }
                 // Fixed-size unsigned integers
@@ -538,6 +825,150 @@ fn to_camel_case(s: String) -> String {
     result
 }
 
+// Helper function to get the Solidity types of the constructor args (e.g. `address,uint256`), which
+// deployers check their own args against. `None` if some type has no Solidity equivalent.
+pub fn generate_constructor_args(constructor: Option<&ImplItemMethod>) -> Option<String> {
+    let Some(method) = constructor else {
+        return Some(String::new());
+    };
+
+    let method_info = MethodInfo::from(method);
+    let (_, arg_types) = get_arg_props_all(&method_info);
+    let args = arg_types
+        .iter()
+        .map(|ty| rust_type_to_sol_type(ty))
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+
+    Some(
+        args.iter()
+            .map(|ty| ty.sol_type_name().into_owned())
+            .collect::<Vec<_>>()
+            .join(","),
+    )
+}
+
+// Helper function to check that the values of a `Mapping` field are wrapped in a storage type.
+// The canonical form is `Mapping<Address, Slot<U256>>`: the mapping only derives the slot of each
+// entry, which is then read and written by the wrapper. Nested mappings are checked recursively.
+pub fn check_mapping_values(ty: &Type) -> Result<(), String> {
+    let Type::Path(type_path) = ty else { return Ok(()) };
+    let Some(segment) = type_path.path.segments.last() else { return Ok(()) };
+    let PathArguments::AngleBracketed(args) = &segment.arguments else { return Ok(()) };
+    if segment.ident != "Mapping" {
+        return Ok(());
+    }
+
+    let value = match args.args.iter().nth(1) {
+        Some(syn::GenericArgument::Type(value)) => value,
+        _ => return Err("`Mapping` expects a key and a value type".to_string()),
+    };
+    let wrapper = match value {
+        Type::Path(value_path) => value_path.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None,
+    };
+
+    match wrapper.as_deref() {
+        Some("Mapping") => check_mapping_values(value),
+        Some("Slot" | "ObservableSlot") => Ok(()),
+        _ => {
+            let value = quote!(#value).to_string().replace(' ', "");
+            Err(format!(
+                "`Mapping` values must be wrapped in a storage type: use `Slot<{}>` instead of `{}`",
+                value, value
+            ))
+        }
+    }
+}
+
+// Runtime binary embedded into the deployment code, relative to the contract crate
+const RUNTIME_PATH: &str = "target/riscv64imac-unknown-none-elf/release/runtime";
+
+// Helper function to embed the runtime binary into the deployment code.
+// `r55-compile` always builds the runtime before the deploy binary, but direct builds may not, so
+// a missing runtime is reported with the command to build it, rather than a bare `include_bytes!` error.
+fn embed_runtime(manifest_dir: Option<&Path>) -> TokenStream {
+    if let Some(dir) = manifest_dir {
+        let runtime_path = dir.join(RUNTIME_PATH);
+        if !runtime_path.exists() {
+            let msg = format!(
+                "Runtime binary not found at `{}`. Build it before the deploy binary with: \
+                 `cargo +nightly-2025-01-07 build -r --lib -Z build-std=core,alloc \
+                 --target riscv64imac-unknown-none-elf --bin runtime` (or use `r55-compile`)",
+                runtime_path.display()
+            );
+            return quote! { compile_error!(#msg) };
+        }
+    }
+
+    let include_path = format!("../{}", RUNTIME_PATH);
+    quote! { include_bytes!(#include_path) }
+}
+
+const BYTECODE_DIR: &str = "../../r55-output-bytecode";
+
+// Helper function to embed the bytecode of a contract dependency, as output by `r55-compile`.
+// Cargo packages like `evm-caller` are imported as `evm_caller`, so both spellings are looked up.
+fn embed_dependency(package: &Ident, manifest_dir: Option<&Path>) -> TokenStream {
+    let package = package.to_string();
+    let mut names = vec![package.clone(), package.replace('_', "-")];
+    names.dedup();
+
+    let name = match manifest_dir {
+        Some(dir) => {
+            let bytecode_dir = dir.join(BYTECODE_DIR);
+            match names
+                .iter()
+                .find(|name| bytecode_dir.join(format!("{}.bin", name)).exists())
+            {
+                Some(name) => name,
+                None => {
+                    let msg = format!(
+                        "Bytecode of `{}` not found in `{}`. Compile the dependency first with \
+                         `r55-compile`",
+                        package,
+                        bytecode_dir.display()
+                    );
+                    return quote! { compile_error!(#msg) };
+                }
+            }
+        }
+        None => &names[0],
+    };
+
+    let include_path = format!("../{}/{}.bin", BYTECODE_DIR, name);
+    quote! { include_bytes!(#include_path) }
+}
+
+// Helper function to implement `Deployable` for a contract dependency (`<package>::<Contract>`).
+// The bytecode is embedded at compile time, so cargo rebuilds the implementation whenever the
+// dependency is recompiled, rather than relying on a generated file to be kept up to date.
+pub fn generate_deployable(path: &syn::Path, manifest_dir: Option<&Path>) -> TokenStream {
+    let (package, contract) = match path.segments.iter().collect::<Vec<_>>().as_slice() {
+        [package, contract] => (&package.ident, &contract.ident),
+        _ => {
+            return syn::Error::new_spanned(path, "Expected a contract path like `erc20::ERC20`")
+                .to_compile_error()
+        }
+    };
+    let interface = format_ident!("I{}", contract);
+    let bytecode = embed_dependency(package, manifest_dir);
+
+    quote! {
+        pub struct #contract;
+
+        impl eth_riscv_runtime::create::Deployable for #contract {
+            type Interface = #package::#interface<eth_riscv_runtime::ReadOnly>;
+
+            const CONSTRUCTOR_ARGS: Option<&'static str> = #package::CONSTRUCTOR_ARGS;
+
+            fn __runtime() -> &'static [u8] {
+                #bytecode
+            }
+        }
+    }
+}
+
 // Helper function to generate the deployment code
 pub fn generate_deployment_code(
     struct_name: &Ident,
@@ -548,6 +979,19 @@ pub fn generate_deployment_code(
         Some(method) => {
             let method_info = MethodInfo::from(method);
             let (arg_names, arg_types) = get_arg_props_all(&method_info);
+
+            // Fallible constructors revert with their typed error
+            let init = match extract_wrapper_types(&method.sig.output) {
+                WrapperType::Result(_, _) => quote! {
+                    if let Err(err) = #struct_name::new(#(#arg_names),*) {
+                        eth_riscv_runtime::revert_with_error(
+                            &eth_riscv_runtime::error::Error::abi_encode(&err)
+                        );
+                    }
+                },
+                _ => quote! { #struct_name::new(#(#arg_names),*); },
+            };
+
             quote! {
                 impl #struct_name { #method }
 
@@ -556,7 +1000,7 @@ pub fn generate_deployment_code(
 
                 let (#(#arg_names),*) = <(#(#arg_types),*)>::abi_decode_validate(&calldata)
                     .expect("Failed to decode constructor args");
-                #struct_name::new(#(#arg_names),*);
+                #init
             }
         }
         None => quote! {
@@ -564,6 +1008,9 @@ pub fn generate_deployment_code(
         },
     };
 
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR");
+    let runtime = embed_runtime(manifest_dir.as_deref().map(Path::new));
+
     quote! {
         use alloc::vec::Vec;
         use alloy_core::primitives::U32;
@@ -573,7 +1020,7 @@ pub fn generate_deployment_code(
             #constructor_code
 
             // Return runtime code
-            let runtime: &[u8] = include_bytes!("../target/riscv64imac-unknown-none-elf/release/runtime");
+            let runtime: &[u8] = #runtime;
             let mut prepended_runtime = Vec::with_capacity(1 + runtime.len());
             prepended_runtime.push(0xff);
             prepended_runtime.extend_from_slice(runtime);
@@ -654,12 +1101,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_is_raw_bytes() {
+        assert!(is_raw_bytes(&parse_quote!(-> Bytes)));
+        assert!(is_raw_bytes(
+            &parse_quote!(-> alloy_core::primitives::Bytes)
+        ));
+
+        assert!(!is_raw_bytes(&parse_quote!()));
+        assert!(!is_raw_bytes(&parse_quote!(-> B32)));
+        assert!(!is_raw_bytes(&parse_quote!(-> Option<Bytes>)));
+        assert!(!is_raw_bytes(&parse_quote!(-> (Bytes, U256))));
+    }
+
+    #[test]
+    fn test_receiver_mutability() {
+        let receiver = |method: ImplItemMethod| {
+            let info = MethodInfo::from(&method);
+            (info.is_mutable(), info.takes_self_by_value())
+        };
+
+        assert_eq!(receiver(parse_quote!(fn f(&self) {})), (false, false));
+        assert_eq!(receiver(parse_quote!(fn f(&mut self) {})), (true, false));
+        // Consuming the contract is dispatched like a mutable method
+        assert_eq!(receiver(parse_quote!(fn f(self) {})), (true, true));
+        assert_eq!(receiver(parse_quote!(fn f(mut self, a: U256) {})), (true, true));
+    }
+
     #[test]
     fn test_rust_to_sol_fixed_bytes() {
         let test_cases = vec![
-            (parse_quote!(B1), DynSolType::FixedBytes(1)),
-            (parse_quote!(B16), DynSolType::FixedBytes(16)),
-            (parse_quote!(B32), DynSolType::FixedBytes(32)),
+            (parse_quote!(B8), DynSolType::FixedBytes(1)),
+            (parse_quote!(B32), DynSolType::FixedBytes(4)),
+            (parse_quote!(B128), DynSolType::FixedBytes(16)),
+            (parse_quote!(B256), DynSolType::FixedBytes(32)),
         ];
 
         for (rust_type, expected_sol_type) in test_cases {
@@ -671,7 +1146,14 @@ mod tests {
 
         // Invalid cases
         assert!(rust_type_to_sol_type(&parse_quote!(B0)).is_err());
-        assert!(rust_type_to_sol_type(&parse_quote!(B33)).is_err());
+        assert!(rust_type_to_sol_type(&parse_quote!(B7)).is_err()); // Not multiple of 8
+        assert!(rust_type_to_sol_type(&parse_quote!(B264)).is_err());
+
+        let method = MockMethod::new("store_hash", vec!["h: B256"]);
+        assert_eq!(
+            generate_fn_selector(&method.info(), None).unwrap(),
+            get_selector_from_sig("store_hash(bytes32)")
+        );
     }
 
     #[test]
@@ -699,6 +1181,34 @@ mod tests {
         assert!(rust_type_to_sol_type(&parse_quote!(I7)).is_err()); // Not multiple of 8
     }
 
+    #[test]
+    fn test_rust_to_sol_native_integers() {
+        let test_cases = vec![
+            (parse_quote!(u8), DynSolType::Uint(8)),
+            (parse_quote!(u32), DynSolType::Uint(32)),
+            (parse_quote!(u64), DynSolType::Uint(64)),
+            (parse_quote!(u128), DynSolType::Uint(128)),
+            (parse_quote!(i8), DynSolType::Int(8)),
+            (parse_quote!(i64), DynSolType::Int(64)),
+        ];
+
+        for (rust_type, expected_sol_type) in test_cases {
+            assert_eq!(
+                rust_type_to_sol_type(&rust_type).unwrap(),
+                expected_sol_type
+            );
+        }
+
+        // Pointer-sized integers have no fixed ABI width
+        assert!(rust_type_to_sol_type(&parse_quote!(usize)).is_err());
+
+        let method = MockMethod::new("f", vec!["x: u64"]);
+        assert_eq!(
+            generate_fn_selector(&method.info(), None).unwrap(),
+            get_selector_from_sig("f(uint64)")
+        );
+    }
+
     #[test]
     fn test_rust_to_sol_arrays() {
         // Dynamic arrays (Vec)
@@ -732,7 +1242,7 @@ mod tests {
         );
 
         assert_eq!(
-            rust_type_to_sol_type(&parse_quote!((Address, B32, I128))).unwrap(),
+            rust_type_to_sol_type(&parse_quote!((Address, B256, I128))).unwrap(),
             DynSolType::Tuple(vec![
                 DynSolType::Address,
                 DynSolType::FixedBytes(32),
@@ -821,6 +1331,13 @@ mod tests {
             get_selector_from_sig("complex_transfer((address,uint256),(address[],bool[]))")
         );
 
+        // Arrays of tuples
+        let method = MockMethod::new("batch", vec!["transfers: Vec<(Address, U256)>"]);
+        assert_eq!(
+            generate_fn_selector(&method.info(), None).unwrap(),
+            get_selector_from_sig("batch((address,uint256)[])")
+        );
+
         // Fixed arrays
         let method = MockMethod::new("multi_transfer", vec!["amounts: [U256; 3]"]);
         assert_eq!(
@@ -920,4 +1437,219 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_constructor_args() {
+        let constructor: ImplItemMethod = parse_quote! {
+            pub fn new(owner: Address, supply: U256) -> Self { todo!() }
+        };
+        assert_eq!(
+            generate_constructor_args(Some(&constructor)).as_deref(),
+            Some("address,uint256")
+        );
+
+        // Contracts without a constructor take no args
+        assert_eq!(generate_constructor_args(None).as_deref(), Some(""));
+
+        // Types without a Solidity equivalent can't be checked
+        let constructor: ImplItemMethod = parse_quote! {
+            pub fn new(config: Config) -> Self { todo!() }
+        };
+        assert_eq!(generate_constructor_args(Some(&constructor)), None);
+    }
+
+    #[test]
+    fn test_embed_missing_runtime() {
+        let dir = std::env::temp_dir().join(format!("r55-embed-runtime-{}", std::process::id()));
+
+        // Without the runtime binary, the error points to the missing build
+        let output = embed_runtime(Some(&dir)).to_string();
+        assert!(output.starts_with("compile_error !"), "{}", output);
+        assert!(output.contains("Runtime binary not found"));
+        assert!(output.contains("--bin runtime"));
+
+        // Once built, the runtime binary is embedded
+        let runtime_path = dir.join(RUNTIME_PATH);
+        std::fs::create_dir_all(runtime_path.parent().unwrap()).unwrap();
+        std::fs::write(&runtime_path, [0x7f]).unwrap();
+        let output = embed_runtime(Some(&dir)).to_string();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(output, format!("include_bytes ! (\"../{}\")", RUNTIME_PATH));
+    }
+
+    #[test]
+    fn test_generate_deployable() {
+        let dir = std::env::temp_dir().join(format!("r55-deployable-{}", std::process::id()));
+        let manifest_dir = dir.join("examples").join("token-factory");
+        let bytecode_dir = manifest_dir.join(BYTECODE_DIR);
+        std::fs::create_dir_all(&bytecode_dir).unwrap();
+
+        // Dependencies are reported until compiled
+        let path: syn::Path = parse_quote!(erc20::ERC20);
+        let output = generate_deployable(&path, Some(&manifest_dir)).to_string();
+        assert!(output.contains("compile_error !"), "{}", output);
+        assert!(output.contains("Bytecode of `erc20` not found"));
+
+        std::fs::write(bytecode_dir.join("erc20.bin"), [0xff]).unwrap();
+        let output = generate_deployable(&path, Some(&manifest_dir)).to_string();
+        assert!(output.contains("impl eth_riscv_runtime :: create :: Deployable for ERC20"));
+        assert!(output.contains("erc20 :: IERC20 < eth_riscv_runtime :: ReadOnly >"));
+        assert!(output.contains("erc20 :: CONSTRUCTOR_ARGS"));
+        assert!(output.contains("include_bytes ! (\"../../../r55-output-bytecode/erc20.bin\")"));
+
+        // Packages named with hyphens are imported with underscores
+        std::fs::write(bytecode_dir.join("evm-caller.bin"), [0xff]).unwrap();
+        let path: syn::Path = parse_quote!(evm_caller::EVMCaller);
+        let output = generate_deployable(&path, Some(&manifest_dir)).to_string();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(output.contains("evm_caller :: IEVMCaller"));
+        assert!(output.contains("r55-output-bytecode/evm-caller.bin"));
+
+        // Paths must name both the package and the contract
+        let path: syn::Path = parse_quote!(ERC20);
+        let output = generate_deployable(&path, Some(&manifest_dir)).to_string();
+        assert!(output.contains("Expected a contract path"));
+    }
+
+    #[test]
+    fn test_contract_args() {
+        let args: ContractArgs = syn::parse_str("").unwrap();
+        assert!(!args.execute);
+
+        let args: ContractArgs = syn::parse_str("execute").unwrap();
+        assert!(args.execute);
+
+        let err = syn::parse_str::<ContractArgs>("relay").err().unwrap();
+        assert!(err.to_string().contains("unsupported option: relay"));
+    }
+
+    #[test]
+    fn test_non_zero_args() {
+        let args: NonZeroArgs = syn::parse_str("to, from").unwrap();
+        assert_eq!(args.params, ["to", "from"]);
+        assert!(args.error.is_none());
+
+        let args: NonZeroArgs = syn::parse_str("to, error = ERC20Error::ZeroAddress").unwrap();
+        assert_eq!(args.params, ["to"]);
+        assert!(args.error.is_some());
+
+        let err = syn::parse_str::<NonZeroArgs>("to, reason = Err").err().unwrap();
+        assert!(err.to_string().contains("unsupported option: reason"));
+        assert!(syn::parse_str::<NonZeroArgs>("").is_err());
+    }
+
+    #[test]
+    fn test_non_zero_checks() {
+        let method: ImplItemMethod = parse_quote! {
+            #[non_zero(to)]
+            #[non_zero(from, error = ERC20Error::ZeroAddress)]
+            pub fn transfer_from(&mut self, from: Address, to: Address, amount: U256) {}
+        };
+        let arg_names = [format_ident!("arg0"), format_ident!("arg1"), format_ident!("arg2")];
+        let checks = generate_non_zero_checks(&method, &arg_names).to_string();
+
+        // Each param is checked through the arg it is decoded into
+        let to_check = quote!(if arg1 == alloy_core::primitives::Address::ZERO {
+            eth_riscv_runtime::revert_with_error("Zero address: to".as_bytes());
+        });
+        let from_check = quote!(if arg0 == alloy_core::primitives::Address::ZERO {
+            eth_riscv_runtime::revert_with_error(&eth_riscv_runtime::error::Error::abi_encode(&(ERC20Error::ZeroAddress)));
+        });
+        assert!(checks.contains(&to_check.to_string()));
+        assert!(checks.contains(&from_check.to_string()));
+        assert!(!checks.contains("arg2"));
+
+        // Methods without the attribute are left unchecked
+        let method: ImplItemMethod = parse_quote!(pub fn transfer(&mut self, to: Address) {});
+        assert!(generate_non_zero_checks(&method, &arg_names[..1]).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "`non_zero` param `amount` must be an `Address`")]
+    fn test_non_zero_checks_non_address() {
+        let method: ImplItemMethod = parse_quote! {
+            #[non_zero(amount)]
+            pub fn burn(&mut self, amount: U256) {}
+        };
+        generate_non_zero_checks(&method, &[format_ident!("arg0")]);
+    }
+
+    #[test]
+    fn test_check_trusted_relayer() {
+        let input: ItemImpl = parse_quote! {
+            impl Token {
+                pub fn is_trusted_relayer(&self, relayer: Address) -> bool { todo!() }
+            }
+        };
+        let methods: Vec<ImplItemMethod> = input
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::ImplItem::Method(method) => Some(method.clone()),
+                _ => None,
+            })
+            .collect();
+        let methods: Vec<_> = methods.iter().collect();
+        assert!(check_trusted_relayer(&input, &methods).is_none());
+
+        // Without the method, `execute` is rejected with a compile error rather than a type error
+        let error = check_trusted_relayer(&input, &[]).unwrap().to_string();
+        assert!(error.contains("compile_error"));
+        assert!(error.contains("requires a `pub fn is_trusted_relayer"));
+    }
+
+    #[test]
+    fn test_fn_selector_execute() {
+        let method = generate_execute_method();
+        let selector = generate_fn_selector(&MethodInfo::from(&method), None).unwrap();
+        assert_eq!(selector, keccak256("execute(bytes)".as_bytes())[..4]);
+        assert!(is_raw_bytes(&method.sig.output));
+    }
+
+    #[test]
+    fn test_check_mapping_values() {
+        // Values are wrapped in a storage type, like in `Mapping<Address, Slot<U256>>`
+        assert!(check_mapping_values(&parse_quote!(Mapping<Address, Slot<U256>>)).is_ok());
+        assert!(check_mapping_values(&parse_quote!(Mapping<Address, ObservableSlot<U256>>)).is_ok());
+        assert!(check_mapping_values(&parse_quote!(
+            Mapping<Address, Mapping<Address, Slot<U256>>>
+        ))
+        .is_ok());
+        assert!(check_mapping_values(&parse_quote!(Slot<U256>)).is_ok());
+
+        // The old form, with bare values, is rejected
+        assert_eq!(
+            check_mapping_values(&parse_quote!(Mapping<Address, U256>)),
+            Err(
+                "`Mapping` values must be wrapped in a storage type: use `Slot<U256>` instead of `U256`"
+                    .to_string()
+            )
+        );
+        assert!(check_mapping_values(&parse_quote!(Mapping<Address, Mapping<Address, u64>>)).is_err());
+    }
+
+    #[test]
+    fn test_cfg_attrs() {
+        let method: ImplItemMethod = parse_quote! {
+            /// Admin only
+            #[cfg(feature = "admin")]
+            #[payable]
+            pub fn reset(&mut self) {}
+        };
+        let info = MethodInfo::from(&method);
+        let cfgs = info.cfg_attrs();
+        assert_eq!(cfgs.len(), 1);
+        assert_eq!(quote!(#(#cfgs)*).to_string(), quote!(#[cfg(feature = "admin")]).to_string());
+
+        // The interface methods are gated like the contract method
+        let interface = generate_interface(&[&method], &format_ident!("IAdmin"), None).to_string();
+        for name in ["reset", "try_reset"] {
+            let name = format_ident!("{}", name);
+            let gated_fn = quote!(#[cfg(feature = "admin")] pub fn #name).to_string();
+            assert!(interface.contains(&gated_fn), "cfg not preserved: {}", interface);
+        }
+
+        let method: ImplItemMethod = parse_quote!(pub fn reset(&mut self) {});
+        assert!(MethodInfo::from(&method).cfg_attrs().is_empty());
+    }
 }
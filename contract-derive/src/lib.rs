@@ -1,17 +1,17 @@
 extern crate proc_macro;
-use alloy_core::primitives::U256;
 use alloy_sol_types::SolValue;
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
+use std::path::Path;
 use syn::{
     parse_macro_input, Data, DeriveInput, Fields, ImplItem, ImplItemMethod,
     ItemImpl, ItemTrait, ReturnType, TraitItem,
 };
 
 mod helpers;
-use crate::helpers::{InterfaceArgs, MethodInfo};
+use crate::helpers::{ContractArgs, InterfaceArgs, MethodInfo};
 
-#[proc_macro_derive(Error)]
+#[proc_macro_derive(Error, attributes(from))]
 pub fn error_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
@@ -22,6 +22,22 @@ pub fn error_derive(input: TokenStream) -> TokenStream {
         panic!("`Error` must be an enum");
     };
 
+    // `#[from]` variants wrap the error of another contract, and keep its encoding untouched
+    let (wrapped, variants): (Vec<_>, Vec<_>) = variants
+        .iter()
+        .partition(|variant| variant.attrs.iter().any(|attr| attr.path.is_ident("from")));
+
+    let wrapped: Vec<_> = wrapped
+        .into_iter()
+        .map(|variant| match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                (&variant.ident, &fields.unnamed[0].ty)
+            }
+            _ => panic!("`#[from]` variants must wrap a single error"),
+        })
+        .collect();
+    let (wrapped_names, wrapped_types): (Vec<_>, Vec<_>) = wrapped.into_iter().unzip();
+
     // Generate error encoding for each variant
     let encode_arms = variants.iter().map(|variant| {
         let variant_name = &variant.ident;
@@ -107,14 +123,14 @@ pub fn error_derive(input: TokenStream) -> TokenStream {
         let selector_bytes = quote!{ &keccak256(#signature.as_bytes())[..4].to_vec() };
 
         match &variant.fields {
-            Fields::Unit => quote! { selector if selector == #selector_bytes => #name::#variant_name },
+            Fields::Unit => quote! { selector if selector == #selector_bytes => Some(#name::#variant_name) },
             Fields::Unnamed(fields) => {
                 let field_types: Vec<_> = fields.unnamed.iter().map(|f| &f.ty).collect();
                 let indices: Vec<_> = (0..fields.unnamed.len()).collect();
                 quote!{ selector if selector == #selector_bytes => {
                     let mut values = Vec::new();
                     #( values.push(<#field_types>::abi_decode_validate(data.unwrap()).expect("Unable to decode")); )*
-                    #name::#variant_name(#(values[#indices]),*)
+                    Some(#name::#variant_name(#(values[#indices]),*))
                 }} 
             },
             Fields::Named(_) => panic!("Named fields are not supported"),
@@ -151,28 +167,48 @@ pub fn error_derive(input: TokenStream) -> TokenStream {
                 use alloy_core::primitives::keccak256;
                 use alloc::vec::Vec;
 
-                match self { #(#encode_arms),* }
+                match self {
+                    #(#encode_arms,)*
+                    #( #name::#wrapped_names(err) => eth_riscv_runtime::error::Error::abi_encode(err), )*
+                }
             }
 
-            fn abi_decode(bytes: &[u8], validate: bool) -> Self {
+            fn try_abi_decode(bytes: &[u8], validate: bool) -> Option<Self> {
                 use alloy_core::primitives::keccak256;
                 use alloy_sol_types::SolValue;
                 use alloc::vec::Vec;
 
-                if bytes.len() < 4 { panic!("Invalid error length") };
+                if bytes.len() < 4 { return None };
                 let selector = &bytes[..4];
                 let data = if bytes.len() > 4 { Some(&bytes[4..]) } else { None };
 
                 match selector {
-                    #(#decode_arms),*,
-                    _ => panic!("Unknown error")
+                    #(#decode_arms,)*
+                    // Otherwise, look for the selector among the wrapped errors
+                    _ => {
+                        #(
+                            if let Some(err) = <#wrapped_types as eth_riscv_runtime::error::Error>::try_abi_decode(bytes, validate) {
+                                return Some(#name::#wrapped_names(err));
+                            }
+                        )*
+                        None
+                    }
                 }
             }
         }
 
+        #(
+            impl From<#wrapped_types> for #name {
+                fn from(err: #wrapped_types) -> Self { #name::#wrapped_names(err) }
+            }
+        )*
+
         impl core::fmt::Debug for #name {
             fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-                match self { #(#debug_arms),* }
+                match self {
+                    #(#debug_arms,)*
+                    #( #name::#wrapped_names(err) => f.debug_tuple(stringify!(#wrapped_names)).field(err).finish(), )*
+                }
             }
         }
     };
@@ -258,6 +294,18 @@ pub fn event_derive(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+// Implements `Deployable` for a contract dependency, e.g. `deployable!(erc20::ERC20)`.
+// The dependency must be imported with its `interface-only` feature, and compiled (with
+// `r55-compile`) before the contracts deploying it.
+#[proc_macro]
+pub fn deployable(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as syn::Path);
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR");
+    let output = helpers::generate_deployable(&path, manifest_dir.as_deref().map(Path::new));
+
+    TokenStream::from(output)
+}
+
 #[proc_macro_attribute]
 pub fn show_streams(attr: TokenStream, item: TokenStream) -> TokenStream {
     println!("attr: \"{}\"", attr.to_string());
@@ -266,8 +314,9 @@ pub fn show_streams(attr: TokenStream, item: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_attribute]
-pub fn contract(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn contract(attr: TokenStream, item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as ItemImpl);
+    let args = parse_macro_input!(attr as ContractArgs);
     let struct_name = if let syn::Type::Path(type_path) = &*input.self_ty {
         &type_path.path.segments.first().unwrap().ident
     } else {
@@ -275,19 +324,31 @@ pub fn contract(_attr: TokenStream, item: TokenStream) -> TokenStream {
     };
 
     let mut constructor = None;
+    let mut receive = None;
     let mut public_methods: Vec<&ImplItemMethod> = Vec::new();
 
-    // Iterate over the items in the impl block to find pub methods + constructor
+    // Iterate over the items in the impl block to find pub methods + constructor + receive
     for item in input.items.iter() {
         if let ImplItem::Method(method) = item {
             if method.sig.ident == "new" {
                 constructor = Some(method);
+            } else if method.sig.ident == "receive" {
+                receive = Some(method);
             } else if let syn::Visibility::Public(_) = method.vis {
                 public_methods.push(method);
             }
         }
     }
 
+    // `#[contract(execute)]` exposes the dispatch to trusted relayers, like any other public method
+    if args.execute {
+        if let Some(error) = helpers::check_trusted_relayer(&input, &public_methods) {
+            return TokenStream::from(error);
+        }
+    }
+    let execute = args.execute.then(helpers::generate_execute_method);
+    public_methods.extend(execute.as_ref());
+
     let input_methods: Vec<_> = public_methods
         .iter()
         .map(|method| quote! { #method })
@@ -301,6 +362,14 @@ pub fn contract(_attr: TokenStream, item: TokenStream) -> TokenStream {
         );
         let (arg_names, arg_types) = helpers::get_arg_props_skip_first(&method_info);
 
+        // Storage structs only hold slot ids, so methods that consume the contract are handed a
+        // fresh instance, while the dispatcher keeps its own borrow
+        let receiver = if method_info.takes_self_by_value() {
+            quote! { Self::default() }
+        } else {
+            quote! { self }
+        };
+
         // Check if there are payable methods
         let checks = if !is_payable(&method) {
             quote! {
@@ -312,106 +381,79 @@ pub fn contract(_attr: TokenStream, item: TokenStream) -> TokenStream {
             quote! {}
         };
 
-        // Check if the method has a return type
+        // Reject the zero address for the params of `#[non_zero(..)]`
+        let non_zero_checks = helpers::generate_non_zero_checks(method, &arg_names);
+
+        // Check if the method has a return type. The arm evaluates to the ABI-encoded output, so
+        // that it can be returned by the frame or forwarded (e.g. by `execute`)
         let return_handling = match &method.sig.output {
             ReturnType::Default => {
                 // No return value
-                quote! { self.#method_name(#( #arg_names ),*); }
+                quote! {
+                    #receiver.#method_name(#( #arg_names ),*);
+                    alloc::vec::Vec::new()
+                }
             }
            ReturnType::Type(_,_) => {
                 match helpers::extract_wrapper_types(&method.sig.output) {
                     helpers::WrapperType::Result(_,_) => quote! {
-                        let res = self.#method_name(#( #arg_names ),*);
+                        let res = #receiver.#method_name(#( #arg_names ),*);
                         match res {
-                            Ok(success) => {
-                                let result_bytes = success.abi_encode();
-                                let result_size = result_bytes.len() as u64;
-                                let result_ptr = result_bytes.as_ptr() as u64;
-                                eth_riscv_runtime::return_riscv(result_ptr, result_size);
-                            }
+                            Ok(success) => success.abi_encode(),
                             Err(err) => {
                                 eth_riscv_runtime::revert_with_error(&err.abi_encode());
                             }
                         }
                     },
                     helpers::WrapperType::Option(_) => quote! {
-                        match self.#method_name(#( #arg_names ),*) {
-                            Some(success) => {
-                                let result_bytes = success.abi_encode();
-                                let result_size = result_bytes.len() as u64;
-                                let result_ptr = result_bytes.as_ptr() as u64;
-                                eth_riscv_runtime::return_riscv(result_ptr, result_size);
-                            },
+                        match #receiver.#method_name(#( #arg_names ),*) {
+                            Some(success) => success.abi_encode(),
                             None => eth_riscv_runtime::revert(),
                         }
                     },
+                    // Raw `Bytes` are already ABI-framed, so they are returned verbatim
+                    helpers::WrapperType::None if helpers::is_raw_bytes(&method.sig.output) => quote! {
+                        let result = #receiver.#method_name(#( #arg_names ),*);
+                        result.to_vec()
+                    },
                     helpers::WrapperType::None => quote! {
-                        let result = self.#method_name(#( #arg_names ),*);
-                        let result_bytes = result.abi_encode();
-                        let result_size = result_bytes.len() as u64;
-                        let result_ptr = result_bytes.as_ptr() as u64;
-                        eth_riscv_runtime::return_riscv(result_ptr, result_size);
+                        let result = #receiver.#method_name(#( #arg_names ),*);
+                        result.abi_encode()
                     }
                 }
             }
         };
 
+        // A cfg-ed out method is left out of the dispatch, so its selector reverts like any other
+        // unknown one
+        let cfg_attrs = method_info.cfg_attrs();
+
         quote! {
+            #( #cfg_attrs )*
             #method_selector => {
                 let (#( #arg_names ),*) = <(#( #arg_types ),*)>::abi_decode_validate(calldata).expect("abi decode failed");
                 #checks
+                #non_zero_checks
                 #return_handling
             }
         }
     }).collect();
 
-    let emit_helper = quote! {
-        #[macro_export]
-        macro_rules! get_type_signature {
-            ($arg:expr) => {
-                $arg.sol_type_name().as_bytes()
-            };
-        }
+    // Calls with empty calldata are routed to `receive` (implicitly payable), or revert otherwise
+    let receive_handling = match receive {
+        Some(_) => quote! {
+            self.receive();
+            return_riscv(0, 0);
+        },
+        None => quote! { revert(); },
+    };
 
+    let emit_helper = quote! {
+        // Shorthand for `log::emit(Event::new(..))`, so that both paths produce identical logs
         #[macro_export]
         macro_rules! emit {
             ($event:ident, $($field:expr),*) => {{
-                use alloy_sol_types::SolValue;
-                use alloy_core::primitives::{keccak256, B256, U256, I256};
-                use alloc::vec::Vec;
-
-                let mut signature = alloc::vec![];
-                signature.extend_from_slice($event::NAME.as_bytes());
-                signature.extend_from_slice(b"(");
-
-                let mut first = true;
-                let mut topics = alloc::vec![B256::default()];
-                let mut data = Vec::new();
-
-                $(
-                    if !first { signature.extend_from_slice(b","); }
-                    first = false;
-
-                    signature.extend_from_slice(get_type_signature!($field));
-                    let encoded = $field.abi_encode();
-
-                    let field_ident = stringify!($field);
-                    if $event::INDEXED_FIELDS.contains(&field_ident) && topics.len() < 4 {
-                        topics.push(B256::from_slice(&encoded));
-                    } else {
-                        data.extend_from_slice(&encoded);
-                    }
-                )*
-
-                signature.extend_from_slice(b")");
-                topics[0] = B256::from(keccak256(&signature));
-
-                if !data.is_empty() {
-                    eth_riscv_runtime::emit_log(&data, &topics);
-                } else if topics.len() > 1 {
-                    let data = topics.pop().unwrap();
-                    eth_riscv_runtime::emit_log(data.as_ref(), &topics);
-                }
+                eth_riscv_runtime::log::emit($event::new($($field),*));
             }};
         }
     };
@@ -424,9 +466,25 @@ pub fn contract(_attr: TokenStream, item: TokenStream) -> TokenStream {
         None,
     );
 
+    // Export the signatures of the `#[bench]` methods, which gas reports compare against each other
+    let benched_methods: Vec<_> = public_methods
+        .iter()
+        .filter(|method| is_bench(method))
+        .map(|method| {
+            helpers::generate_fn_signature(&MethodInfo::from(*method), None)
+                .expect("Unable to generate fn signature")
+        })
+        .collect();
+
     // Generate initcode for deployments
     let deployment_code = helpers::generate_deployment_code(struct_name, constructor);
 
+    // Export the constructor args, so that deployers can check their own args against them
+    let constructor_args = match helpers::generate_constructor_args(constructor) {
+        Some(args) => quote! { Some(#args) },
+        None => quote! { None },
+    };
+
     // Generate the complete output with module structure
     let output = quote! {
         use eth_riscv_runtime::*;
@@ -448,6 +506,16 @@ pub fn contract(_attr: TokenStream, item: TokenStream) -> TokenStream {
         pub mod interface {
             use super::*;
             #interface
+
+            impl HasInterface for #struct_name {
+                type Interface = #interface_name<ReadOnly>;
+            }
+
+            /// Solidity types of the constructor args (e.g. `address,uint256`), if all of them have one
+            pub const CONSTRUCTOR_ARGS: Option<&str> = #constructor_args;
+
+            /// Signatures of the methods tagged with `#[bench]`, to benchmark their gas usage
+            pub const BENCHED_METHODS: &[&str] = &[#( #benched_methods ),*];
         }
 
         // Generate the call method implementation privately
@@ -463,22 +531,35 @@ pub fn contract(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
             #emit_helper
 
-            impl #struct_name { #(#input_methods)* }
+            impl #struct_name { #(#input_methods)* #receive }
             impl Contract for #struct_name {
                 fn call(&mut self) {
                     self.call_with_data(&msg_data());
                 }
 
                 fn call_with_data(&mut self, calldata: &[u8]) {
+                    if calldata.is_empty() {
+                        #receive_handling
+                    }
+
+                    let output = Contract::dispatch(self, calldata);
+                    return_riscv(output.as_ptr() as u64, output.len() as u64);
+                }
+
+                fn dispatch(&mut self, calldata: &[u8]) -> alloc::vec::Vec<u8> {
+                    // Like contracts without a fallback, calldata without a known selector reverts
+                    // without any data, rather than with a panic message
+                    if calldata.len() < 4 {
+                        revert();
+                    }
+
                     let selector = u32::from_be_bytes([calldata[0], calldata[1], calldata[2], calldata[3]]);
                     let calldata = &calldata[4..];
 
                     match selector {
                         #( #match_arms )*
-                        _ => panic!("unknown method"),
+                        _ => revert(),
                     }
-
-                    return_riscv(0, 0);
                 }
             }
 
@@ -512,12 +593,35 @@ pub fn payable(_attr: TokenStream, item: TokenStream) -> TokenStream {
     item
 }
 
-// Check if a method is tagged with the payable attribute
+// Empty macro to reject the zero address for the named params of a method, e.g.
+// `#[non_zero(to, error = ERC20Error::ZeroAddress)]`. The checks are injected by `#[contract]`
+#[proc_macro_attribute]
+pub fn non_zero(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+// Empty macro to mark a method for gas benchmarking. Importing it shadows the built-in `#[bench]`
+#[proc_macro_attribute]
+pub fn bench(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+// Check if a method is tagged with the payable attribute, either as `#[payable]` or with its full
+// path (`#[contract_derive::payable]`)
 fn is_payable(method: &syn::ImplItemMethod) -> bool {
+    has_marker(method, "payable")
+}
+
+// Check if a method is tagged with the bench attribute, like `is_payable`
+fn is_bench(method: &syn::ImplItemMethod) -> bool {
+    has_marker(method, "bench")
+}
+
+fn has_marker(method: &syn::ImplItemMethod, marker: &str) -> bool {
     method.attrs.iter().any(|attr| {
         if let Ok(syn::Meta::Path(path)) = attr.parse_meta() {
-            if let Some(segment) = path.segments.first() {
-                return segment.ident == "payable";
+            if let Some(segment) = path.segments.last() {
+                return segment.ident == marker;
             }
         }
         false
@@ -578,12 +682,29 @@ pub fn storage(_attr: TokenStream, input: TokenStream) -> TokenStream {
 
     // Generate initialization code for each field
     // TODO: PoC uses a naive strategy. Enhance to support complex types like tuples or custom structs.
-    let init_fields = fields.iter().enumerate().map(|(i, f)| {
+    //
+    // Fields are allocated sequential slots, in declaration order. Direct types (like `Slot`) are
+    // stored from that slot onwards (static arrays span several slots), while mappings only use it
+    // as the seed of `keccak256(key ++ id)`. As the mapping entries are keccak-derived, they can't
+    // overlap with the (low) sequential slots of the rest of the fields, the same way Solidity lays
+    // out its storage.
+    for f in fields.iter() {
+        if let Err(msg) = helpers::check_mapping_values(&f.ty) {
+            panic!("{}", msg);
+        }
+    }
+
+    let mut next_slot = 0;
+    let mut init_fields = Vec::with_capacity(fields.len());
+    for f in fields.iter() {
         let name = &f.ident;
-        let slot = U256::from(i);
-        let [limb0, limb1, limb2, limb3] = slot.as_limbs();
-        quote! { #name: StorageLayout::allocate(#limb0, #limb1, #limb2, #limb3) }
-    });
+        let slot = next_slot;
+        next_slot += match r55_layout::storage_slots(&f.ty) {
+            Ok(slots) => slots,
+            Err(err) => return TokenStream::from(err.to_compile_error()),
+        };
+        init_fields.push(quote! { #name: StorageLayout::allocate_u256(alloy_core::primitives::U256::from(#slot)) });
+    }
 
     let expanded = quote! {
         #vis struct #name { #(#struct_fields,)* }
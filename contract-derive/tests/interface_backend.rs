@@ -0,0 +1,111 @@
+//! Host-side test of the calls performed by generated interfaces, through a mock backend.
+
+extern crate alloc;
+
+use alloy_core::primitives::{address, hex, Address, Bytes, U256};
+use contract_derive::interface;
+use core::cell::RefCell;
+
+// The call contexts of the runtime don't need any syscall, so they build on the host as well
+// Not every item is exercised here, and the runtime's own lints don't apply to this test
+#[path = "../../eth-riscv-runtime/src/call/ctx.rs"]
+#[allow(dead_code, clippy::needless_lifetimes, clippy::multiple_bound_locations)]
+mod ctx;
+
+// Stand-in for `eth_riscv_runtime`, whose syscalls only exist on RISC-V
+mod eth_riscv_runtime {
+    pub use super::ctx::*;
+    use alloy_core::primitives::{Address, Bytes};
+
+    pub struct SyscallBackend;
+
+    impl CallBackend for SyscallBackend {
+        fn call(&self, _: Address, _: u64, _: &[u8], _: Option<u64>) -> Result<Bytes, Bytes> {
+            unreachable!("No syscalls on the host")
+        }
+
+        fn staticcall(&self, _: Address, _: u64, _: &[u8], _: Option<u64>) -> Result<Bytes, Bytes> {
+            unreachable!("No syscalls on the host")
+        }
+    }
+}
+use eth_riscv_runtime::*;
+
+#[interface("camelCase")]
+trait IERC20 {
+    fn balance_of(&self, owner: Address) -> U256;
+    fn transfer(&mut self, to: Address, amount: U256) -> bool;
+}
+
+// Records the calldata of every call, and answers with `output`
+#[derive(Default)]
+struct MockBackend {
+    calls: RefCell<Vec<(Address, Vec<u8>, bool)>>,
+    output: Vec<u8>,
+}
+
+impl CallBackend for MockBackend {
+    fn call(&self, addr: Address, _: u64, data: &[u8], _: Option<u64>) -> Result<Bytes, Bytes> {
+        self.calls.borrow_mut().push((addr, data.to_vec(), false));
+        Ok(Bytes::copy_from_slice(&self.output))
+    }
+
+    fn staticcall(
+        &self,
+        addr: Address,
+        _: u64,
+        data: &[u8],
+        _: Option<u64>,
+    ) -> Result<Bytes, Bytes> {
+        self.calls.borrow_mut().push((addr, data.to_vec(), true));
+        Ok(Bytes::copy_from_slice(&self.output))
+    }
+}
+
+fn mock(output: U256) -> &'static MockBackend {
+    Box::leak(Box::new(MockBackend {
+        output: output.to_be_bytes_vec(),
+        ..Default::default()
+    }))
+}
+
+const TOKEN: Address = address!("0000000000000000000000000000000000000123");
+const ALICE: Address = address!("00000000000000000000000000000000000a11ce");
+
+#[test]
+fn test_transfer_calldata() {
+    let backend = mock(U256::from(1));
+    let mut token = IERC20::new(TOKEN).with_ctx(&mut ()).with_backend(backend);
+
+    assert_eq!(token.transfer(ALICE, U256::from(42)), Some(true));
+
+    // `transfer(address,uint256)` selector, followed by the ABI-encoded arguments
+    let expected = hex!(
+        "a9059cbb"
+        "00000000000000000000000000000000000000000000000000000000000a11ce"
+        "000000000000000000000000000000000000000000000000000000000000002a"
+    );
+    assert_eq!(
+        *backend.calls.borrow(),
+        vec![(TOKEN, expected.to_vec(), false)]
+    );
+}
+
+#[test]
+fn test_view_uses_staticcall() {
+    let backend = mock(U256::from(7));
+    let token = IERC20::new(TOKEN).with_ctx(&()).with_backend(backend);
+
+    assert_eq!(token.balance_of(ALICE), Some(U256::from(7)));
+
+    let calls = backend.calls.borrow();
+    assert_eq!(calls[0].1[..4], hex!("70a08231"));
+    assert!(calls[0].2, "View methods must be staticcalls");
+}
+
+#[test]
+fn test_selector_consts() {
+    // Canonical (camelCase) signatures, like the calldata of the calls
+    assert_eq!(IERC20::<ReadOnly>::TRANSFER_SELECTOR, hex!("a9059cbb"));
+    assert_eq!(IERC20::<ReadOnly>::BALANCE_OF_SELECTOR, hex!("70a08231"));
+}
@@ -1,5 +1,7 @@
 //! RISC-V interpreter crate errors
 
+use rvemu::exception::Exception;
+
 pub type Result<T> = core::result::Result<T, Error>;
 
 /// Error encountered on RISC-V interpreter setup
@@ -9,4 +11,16 @@ pub enum Error {
     /// [`goblin`] crate error representation
     #[error(transparent)]
     GoblinError(#[from] goblin::error::Error),
+    /// [`rvemu`] exception raised while accessing the emulator DRAM
+    #[error("Got RISC-V emulator exception: {0:?}")]
+    RvEmuException(Exception),
+}
+
+// Note: this `From` implementation here because `rvemu::exception::Exception`
+// doesn't implement `std::error::Error`, so `#[from]` can't be used
+impl From<Exception> for Error {
+    #[inline]
+    fn from(exception: Exception) -> Self {
+        Self::RvEmuException(exception)
+    }
 }
@@ -1,52 +1,241 @@
-use rvemu::{bus::DRAM_BASE, dram::DRAM_SIZE, emulator::Emulator};
+use rvemu::{bus::DRAM_BASE, cpu::XRegisters, dram::DRAM_SIZE, emulator::Emulator};
+use std::{collections::HashMap, rc::Rc};
 
 mod error;
 pub use error::{Error, Result};
 
+/// Size of the call data region, which starts at `DRAM_BASE`
+const CALL_DATA_SIZE: usize = 1024 * 1024;
+
+/// Maximum number of idle emulators kept around by an [`EmulatorPool`]
+const MAX_IDLE_EMULATORS: usize = 16;
+
+/// Maximum number of ELF images cached by an [`EmulatorPool`]
+const MAX_CACHED_IMAGES: usize = 256;
+
+/// Symbol of the global allocator of `eth-riscv-runtime`, whose first word is its heap usage
+const HEAP_SYMBOL: &str = "__r55_heap";
+
+/// Symbol of the top of the stack, defined by the `riscv-rt` linker script
+const STACK_SYMBOL: &str = "_stack_start";
+
 pub fn setup_from_elf(elf_data: &[u8], call_data: &[u8]) -> Result<Emulator> {
-    let elf = goblin::elf::Elf::parse(elf_data)?;
+    EmulatorPool::new().setup_from_elf(elf_data, call_data)
+}
 
-    // Allocate 1MB for the call data
-    let mut mem = vec![0; 1024 * 1024];
-    {
-        assert!(call_data.len() < mem.len() - 8);
+/// Memory image of an ELF: its entry point and the contents of its `PT_LOAD` segments, ready to be
+/// copied into the DRAM of every frame that runs it.
+#[derive(Debug)]
+pub struct ElfImage {
+    entry: u64,
+    /// DRAM offset and file contents of each segment. The rest of `p_memsz` is zero-filled.
+    segments: Vec<(usize, Vec<u8>)>,
+    /// End of the highest segment, relative to `DRAM_BASE`
+    loaded_len: usize,
+    layout: Option<MemoryLayout>,
+}
 
-        let (size_bytes, data_bytes) = mem.split_at_mut(8);
-        size_bytes.copy_from_slice(&(call_data.len() as u64).to_le_bytes());
-        data_bytes[..call_data.len()].copy_from_slice(call_data);
-    }
+/// Addresses the memory usage of a frame can be read from, if its ELF exports them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryLayout {
+    /// Address of the heap allocator, which starts with the number of bytes it handed out
+    pub heap: u64,
+    /// Initial stack pointer, as the stack grows downwards from it
+    pub stack_top: u64,
+}
+
+impl ElfImage {
+    pub fn parse(elf_data: &[u8]) -> Result<Self> {
+        let elf = goblin::elf::Elf::parse(elf_data)?;
 
-    load_sections(&mut mem, &elf, elf_data);
+        let mut segments = Vec::new();
+        let mut loaded_len = CALL_DATA_SIZE;
+        for ph in &elf.program_headers {
+            if ph.p_type == goblin::elf::program_header::PT_LOAD {
+                // The interpreter RAM is DRAM_SIZE starting at DRAM_BASE
+                assert!(ph.p_vaddr >= DRAM_BASE);
+                assert!(ph.p_memsz <= DRAM_SIZE);
 
-    let mut emu = Emulator::new();
+                let start_vec = (ph.p_vaddr - DRAM_BASE) as usize;
+                let start_offset = ph.p_offset as usize;
+                loaded_len = loaded_len.max(start_vec + ph.p_memsz as usize);
 
-    emu.initialize_dram(mem);
-    emu.initialize_pc(elf.header.e_entry);
+                // The data available to copy may be smaller than the required size
+                let size_to_copy = ph.p_filesz as usize;
+                segments.push((
+                    start_vec,
+                    elf_data[start_offset..(start_offset + size_to_copy)].to_vec(),
+                ));
+            }
+        }
+
+        let symbol = |name: &str| {
+            elf.syms
+                .iter()
+                .find(|sym| elf.strtab.get_at(sym.st_name) == Some(name))
+                .map(|sym| sym.st_value)
+        };
+        let layout = symbol(HEAP_SYMBOL)
+            .zip(symbol(STACK_SYMBOL))
+            .map(|(heap, stack_top)| MemoryLayout { heap, stack_top });
+
+        Ok(Self {
+            entry: elf.header.e_entry,
+            segments,
+            loaded_len,
+            layout,
+        })
+    }
 
-    Ok(emu)
+    /// Memory layout of the contract, unless it isn't built with `eth-riscv-runtime`
+    pub fn memory_layout(&self) -> Option<MemoryLayout> {
+        self.layout
+    }
+
+    /// Copies the segments into `mem`, which must be zeroed beyond the call data
+    fn write(&self, mem: &mut [u8]) {
+        for (start_vec, data) in &self.segments {
+            mem[*start_vec..(start_vec + data.len())].copy_from_slice(data);
+        }
+    }
 }
 
-fn load_sections(mem: &mut Vec<u8>, elf: &goblin::elf::Elf, elf_data: &[u8]) {
-    for ph in &elf.program_headers {
-        if ph.p_type == goblin::elf::program_header::PT_LOAD {
-            // The interpreter RAM is DRAM_SIZE starting at DRAM_BASE
-            assert!(ph.p_vaddr >= DRAM_BASE);
-            assert!(ph.p_memsz <= DRAM_SIZE);
+/// Pool of emulators which are reset and reused across call frames, rather than allocating (and
+/// page-faulting) a fresh DRAM for every frame.
+///
+/// Frames are expected to only write to the call data, the stack and their own loaded sections,
+/// which all lie below the end of the highest `PT_LOAD` segment. Only that prefix of the DRAM is
+/// zeroed when an emulator is reused.
+///
+/// The pool also caches the [`ElfImage`] of each contract by code hash, so that the ELF is only
+/// parsed on the first call.
+#[derive(Default)]
+pub struct EmulatorPool {
+    idle: Vec<Emulator>,
+    images: HashMap<[u8; 32], Rc<ElfImage>>,
+    /// Length of the DRAM prefix that emulators handed out by the pool may have written to
+    dirty_len: usize,
+    /// Number of emulators allocated by the pool
+    allocated: usize,
+    /// Number of ELFs parsed by the pool
+    parsed: usize,
+}
 
-            let start_vec = (ph.p_vaddr - DRAM_BASE) as usize;
-            let start_offset = ph.p_offset as usize;
+impl EmulatorPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Same as [`setup_from_elf`], but reuses an idle emulator when available
+    pub fn setup_from_elf(&mut self, elf_data: &[u8], call_data: &[u8]) -> Result<Emulator> {
+        let image = self.image(None, elf_data)?;
+        self.setup_from_image(&image, call_data)
+    }
+
+    /// Same as [`Self::setup_from_elf`], but only parses the ELF the first time `code_hash` is seen
+    pub fn setup_from_cached_elf(
+        &mut self,
+        code_hash: [u8; 32],
+        elf_data: &[u8],
+        call_data: &[u8],
+    ) -> Result<Emulator> {
+        let image = self.image(Some(code_hash), elf_data)?;
+        self.setup_from_image(&image, call_data)
+    }
 
-            let end_vec = start_vec + ph.p_memsz as usize;
-            if mem.len() < end_vec {
-                mem.resize(end_vec, 0);
+    /// Parses the image of an ELF, reusing the cached one of `code_hash` (if any)
+    pub fn image(&mut self, code_hash: Option<[u8; 32]>, elf_data: &[u8]) -> Result<Rc<ElfImage>> {
+        if let Some(image) = code_hash.and_then(|code_hash| self.images.get(&code_hash)) {
+            return Ok(image.clone());
+        }
+
+        self.parsed += 1;
+        let image = Rc::new(ElfImage::parse(elf_data)?);
+        if let Some(code_hash) = code_hash {
+            if self.images.len() >= MAX_CACHED_IMAGES {
+                self.images.clear();
             }
+            self.images.insert(code_hash, image.clone());
+        }
+
+        Ok(image)
+    }
 
-            // The data available to copy may be smaller than the required size
-            let size_to_copy = ph.p_filesz as usize;
-            mem[start_vec..(start_vec + size_to_copy)]
-                .copy_from_slice(&elf_data[start_offset..(start_offset + size_to_copy)]);
+    /// Sets up an emulator, reusing an idle one when available, to run `image` with `call_data`
+    pub fn setup_from_image(&mut self, image: &ElfImage, call_data: &[u8]) -> Result<Emulator> {
+        let mut emu = match self.idle.pop() {
+            Some(mut emu) => {
+                reset(&mut emu, self.dirty_len)?;
+                emu
+            }
+            None => {
+                self.allocated += 1;
+                Emulator::new()
+            }
+        };
+
+        // Write the call data and the sections straight into the DRAM, skipping the staging buffer
+        let mem = emu
+            .cpu
+            .bus
+            .get_dram_slice(DRAM_BASE..(DRAM_BASE + DRAM_SIZE))?;
+        write_call_data(mem, call_data);
+        image.write(mem);
+        self.dirty_len = self.dirty_len.max(image.loaded_len);
+
+        emu.initialize_pc(image.entry);
+
+        Ok(emu)
+    }
+
+    /// Returns an emulator to the pool, so that it can be reused by later frames
+    pub fn release(&mut self, emu: Emulator) {
+        if self.idle.len() < MAX_IDLE_EMULATORS {
+            self.idle.push(emu);
         }
     }
+
+    /// Number of emulators allocated since the creation of the pool
+    pub fn allocated(&self) -> usize {
+        self.allocated
+    }
+
+    /// Number of ELFs parsed since the creation of the pool
+    pub fn parsed(&self) -> usize {
+        self.parsed
+    }
+
+    /// Length of the DRAM prefix that emulators handed out by the pool may have written to.
+    ///
+    /// The DRAM of those emulators is all zeroes beyond it.
+    pub fn dirty_len(&self) -> usize {
+        self.dirty_len
+    }
+}
+
+/// Clears the CPU state and the first `dirty_len` bytes of the DRAM of a previously used emulator
+fn reset(emu: &mut Emulator, dirty_len: usize) -> Result<()> {
+    emu.cpu.xregs = XRegisters::new();
+    emu.cpu.inst_counter.clear();
+    emu.cpu.is_count = false;
+
+    if dirty_len != 0 {
+        emu.cpu
+            .bus
+            .get_dram_slice(DRAM_BASE..(DRAM_BASE + dirty_len as u64))?
+            .fill(0);
+    }
+
+    Ok(())
+}
+
+/// Writes the call data, prefixed by its little-endian `u64` length, at the start of `mem`
+fn write_call_data(mem: &mut [u8], call_data: &[u8]) {
+    assert!(call_data.len() < CALL_DATA_SIZE - 8);
+
+    let (size_bytes, data_bytes) = mem[..CALL_DATA_SIZE].split_at_mut(8);
+    size_bytes.copy_from_slice(&(call_data.len() as u64).to_le_bytes());
+    data_bytes[..call_data.len()].copy_from_slice(call_data);
 }
 
 #[cfg(test)]
@@ -57,8 +246,7 @@ mod tests {
     use std::fs;
     use std::process::Command;
 
-    #[test]
-    fn test_execute_elf() -> eyre::Result<()> {
+    fn runtime_elf() -> eyre::Result<Vec<u8>> {
         let runtime_path = "../asm-runtime-example/runtime";
 
         // Check if the runtime ELF exists; if not, run `make` to generate it
@@ -73,8 +261,11 @@ mod tests {
             }
         }
 
-        let elf_data = fs::read(runtime_path)?;
-        let mut emu = setup_from_elf(&elf_data, &[])?;
+        Ok(fs::read(runtime_path)?)
+    }
+
+    /// Runs the emulator and returns the little-endian `u64` returned by the runtime
+    fn run_to_return(emu: &mut Emulator) -> eyre::Result<u64> {
         let result = emu.start();
         assert_eq!(result, Err(Exception::EnvironmentCallFromMMode));
         let t0 = emu.cpu.xregs.read(5);
@@ -85,8 +276,80 @@ mod tests {
         assert_eq!(a1, 8); // data returned should be a little-endian u64
         let data_bytes = emu.cpu.bus.get_dram_slice(a0..(a0 + a1)).unwrap();
 
-        let data = u64::from_le_bytes(data_bytes.try_into()?);
-        assert_eq!(data, 5);
+        Ok(u64::from_le_bytes(data_bytes.try_into()?))
+    }
+
+    #[test]
+    fn test_execute_elf() -> eyre::Result<()> {
+        let elf_data = runtime_elf()?;
+        let mut emu = setup_from_elf(&elf_data, &[])?;
+        assert_eq!(run_to_return(&mut emu)?, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_emulator_pool_reuse() -> eyre::Result<()> {
+        let elf_data = runtime_elf()?;
+        let mut pool = EmulatorPool::new();
+
+        for _ in 0..100 {
+            let mut emu = pool.setup_from_elf(&elf_data, &[0xAA; 64])?;
+            assert_eq!(run_to_return(&mut emu)?, 5);
+
+            // Scribble over the stack, which must not leak into the next frame
+            let stack = DRAM_BASE + CALL_DATA_SIZE as u64;
+            emu.cpu
+                .bus
+                .get_dram_slice(stack..(stack + 64))
+                .unwrap()
+                .fill(0xFF);
+            pool.release(emu);
+        }
+        assert_eq!(pool.allocated(), 1, "Emulator was not reused");
+
+        // A reused emulator starts from a clean state
+        let mut emu = pool.setup_from_elf(&elf_data, &[0xBB; 8])?;
+        let stack = DRAM_BASE + CALL_DATA_SIZE as u64;
+        assert!(emu
+            .cpu
+            .bus
+            .get_dram_slice(stack..(stack + 64))
+            .unwrap()
+            .iter()
+            .all(|b| *b == 0));
+        let call_data = emu
+            .cpu
+            .bus
+            .get_dram_slice(DRAM_BASE..(DRAM_BASE + 80))
+            .unwrap();
+        assert_eq!(call_data[..8], 8u64.to_le_bytes());
+        assert_eq!(call_data[8..16], [0xBB; 8]);
+        assert!(call_data[16..].iter().all(|b| *b == 0));
+        assert!(emu.cpu.inst_counter.is_empty());
+        assert_eq!(run_to_return(&mut emu)?, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cached_elf_image() -> eyre::Result<()> {
+        let elf_data = runtime_elf()?;
+        let code_hash = [0x55; 32];
+        let mut pool = EmulatorPool::new();
+
+        for i in 0..100 {
+            let mut emu = pool.setup_from_cached_elf(code_hash, &elf_data, &[i as u8; 32])?;
+            assert_eq!(run_to_return(&mut emu)?, 5);
+            pool.release(emu);
+        }
+        assert_eq!(pool.parsed(), 1, "ELF was parsed more than once");
+        assert_eq!(pool.allocated(), 1, "Emulator was not reused");
+
+        // Distinct contracts get their own image
+        let mut emu = pool.setup_from_cached_elf([0xAA; 32], &elf_data, &[])?;
+        assert_eq!(run_to_return(&mut emu)?, 5);
+        assert_eq!(pool.parsed(), 2);
 
         Ok(())
     }
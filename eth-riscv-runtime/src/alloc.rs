@@ -59,7 +59,9 @@ unsafe impl<const SIZE: usize> GlobalAlloc for FixedMemoryAllocator<SIZE> {
     }
 }
 
+// Exported under a fixed name, so that the interpreter can read the heap usage of the frame
 #[global_allocator]
+#[export_name = "__r55_heap"]
 pub static mut GLOBAL: FixedMemoryAllocator<{ 1024 * 1024 * 10 }> = FixedMemoryAllocator::new();
 
 #[alloc_error_handler]
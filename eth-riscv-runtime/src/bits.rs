@@ -0,0 +1,43 @@
+//! 256-bit shifts and byte extraction, offloaded to the host instead of being done in software.
+//! Worth it for bit-manipulation-heavy contracts, otherwise `U256` operators work just as well.
+
+use alloy_core::primitives::U256;
+use core::arch::asm;
+use eth_riscv_syscalls::Syscall;
+
+fn bit_op(syscall: Syscall, value: U256, arg: u64) -> U256 {
+    let value = value.as_limbs();
+    let first: u64;
+    let second: u64;
+    let third: u64;
+    let fourth: u64;
+    unsafe {
+        asm!(
+            "ecall",
+            inlateout("a0") value[0] => first, inlateout("a1") value[1] => second,
+            inlateout("a2") value[2] => third, inlateout("a3") value[3] => fourth,
+            in("a4") arg, in("t0") u8::from(syscall)
+        );
+    }
+    U256::from_limbs([first, second, third, fourth])
+}
+
+// Returns the `index`-th byte of `value`, counting from the most significant one (zero if `index >= 32`)
+pub fn byte(value: U256, index: u64) -> U256 {
+    bit_op(Syscall::Byte, value, index)
+}
+
+// Returns `value << shift` (zero if `shift >= 256`)
+pub fn shl(value: U256, shift: u64) -> U256 {
+    bit_op(Syscall::Shl, value, shift)
+}
+
+// Returns `value >> shift` (zero if `shift >= 256`)
+pub fn shr(value: U256, shift: u64) -> U256 {
+    bit_op(Syscall::Shr, value, shift)
+}
+
+// Returns `value >> shift`, sign-extending `value` as a two's complement integer
+pub fn sar(value: U256, shift: u64) -> U256 {
+    bit_op(Syscall::Sar, value, shift)
+}
@@ -1,7 +1,19 @@
-use alloy_core::primitives::U256;
+use alloy_core::primitives::{B256, U256};
 use eth_riscv_syscalls::Syscall;
 use core::arch::asm;
 
+// Returns the hash of block `number`, which is zero unless it is one of the last 256 blocks
+pub fn block_hash(number: u64) -> B256 {
+    let first: u64;
+    let second: u64;
+    let third: u64;
+    let fourth: u64;
+    unsafe {
+        asm!("ecall", inlateout("a0") number => first, lateout("a1") second, lateout("a2") third, lateout("a3") fourth, in("t0") u8::from(Syscall::BlockHash));
+    }
+    B256::from(U256::from_limbs([first, second, third, fourth]))
+}
+
 // Returns current block timestamp in seconds since Unix epoch
 pub fn timestamp() -> U256 {
     let first: u64;
@@ -47,6 +59,11 @@ pub fn gas_limit() -> U256 {
     U256::from_limbs([first, second, third, fourth])
 }
 
+// Same as `gas_limit`, as a `u64` (saturating, although real block gas limits are far below it)
+pub fn gas_limit_u64() -> u64 {
+    gas_limit().try_into().unwrap_or(u64::MAX)
+}
+
 // Returns current block number
 pub fn number() -> U256 {
     let first: u64;
@@ -57,4 +74,22 @@ pub fn number() -> U256 {
         asm!("ecall", lateout("a0") first, lateout("a1") second, lateout("a2") third, lateout("a3") fourth, in("t0") u8::from(Syscall::Number));
     }
     U256::from_limbs([first, second, third, fourth])
-}
\ No newline at end of file
+}
+
+// Returns the previous block's RANDAO mix (EIP-4399)
+pub fn prevrandao() -> U256 {
+    let first: u64;
+    let second: u64;
+    let third: u64;
+    let fourth: u64;
+    unsafe {
+        asm!("ecall", lateout("a0") first, lateout("a1") second, lateout("a2") third, lateout("a3") fourth, in("t0") u8::from(Syscall::PrevRandao));
+    }
+    U256::from_limbs([first, second, third, fourth])
+}
+
+// Returns the block difficulty. Since the merge, the `DIFFICULTY` opcode (0x44) was
+// repurposed as `PREVRANDAO`, so this is an alias kept for legacy source compatibility
+pub fn difficulty() -> U256 {
+    prevrandao()
+}
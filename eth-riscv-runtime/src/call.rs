@@ -1,104 +1,91 @@
 extern crate alloc;
 use alloc::vec::Vec;
 use alloy_core::primitives::{Address, Bytes, U256};
-use core::{arch::asm, marker::PhantomData};
+use core::arch::asm;
 use eth_riscv_syscalls::Syscall;
 
-// Concrete types implementing the context traits
-pub struct ReadOnly;
-pub struct ReadWrite;
-
-// Marker traits to determine call context
-pub trait CallCtx {}
-pub trait StaticCtx: CallCtx {}
-pub trait MutableCtx: StaticCtx {}
-
-impl CallCtx for ReadOnly {}
-impl CallCtx for ReadWrite {}
-impl StaticCtx for ReadOnly {}
-impl StaticCtx for ReadWrite {}
-impl MutableCtx for ReadWrite {}
-
-// Marker trait to connect contract method context with call ctx
-pub trait MethodCtx { type Allowed: CallCtx; }
-impl<'a, T> MethodCtx for &'a T { type Allowed = ReadOnly; }
-impl<'a, T> MethodCtx for &'a mut T { type Allowed = ReadWrite; }
-
-// Types and traits to build a MethodCtx-aware interface
-pub struct InterfaceBuilder<I> {
-    pub address: Address,
-    pub _phantom: PhantomData<I>,
-}
+mod ctx;
+pub use ctx::*;
 
-pub trait InitInterface: Sized {
-    fn new(address: Address) -> InterfaceBuilder<Self>;
+/// Trait for contracts to have an entry point for txs  
+pub trait Contract {
+    fn call(&mut self);
+    fn call_with_data(&mut self, calldata: &[u8]);
+    /// Runs the method selected by `calldata`, returning its ABI-encoded output (or reverting)
+    fn dispatch(&mut self, calldata: &[u8]) -> Vec<u8>;
 }
 
-// Key change: Add trait to convert between interface types
-pub trait IntoInterface<T> {
-    fn into_interface(self) -> T;
-}
+/// Default [`CallBackend`] of generated interfaces, performing their calls through the syscalls.
+pub struct SyscallBackend;
 
-impl<I> InterfaceBuilder<I> {
-    pub fn with_ctx<M: MethodCtx, T>(
-        self,
-        _: M
-    ) -> T 
-    where
-        I: IntoInterface<T>,
-        M: MethodCtx<Allowed = T::Context>,
-        T: FromBuilder
-    {
-        let target_builder = InterfaceBuilder {
-            address: self.address,
-            _phantom: PhantomData,
-        };
-        T::from_builder(target_builder)
+impl CallBackend for SyscallBackend {
+    fn call(&self, addr: Address, value: u64, data: &[u8], ret_size: Option<u64>) -> Result<Bytes, Bytes> {
+        call_contract(addr, value, data, ret_size)
     }
-}
-
-pub trait FromBuilder: Sized {
-    type Context: CallCtx;
-    fn from_builder(builder: InterfaceBuilder<Self>) -> Self;
-}
 
-
-/// Trait for contracts to have an entry point for txs  
-pub trait Contract {
-    fn call(&mut self);
-    fn call_with_data(&mut self, calldata: &[u8]);
+    fn staticcall(&self, addr: Address, value: u64, data: &[u8], ret_size: Option<u64>) -> Result<Bytes, Bytes> {
+        staticcall_contract(addr, value, data, ret_size)
+    }
 }
 
+/// Performs a call and loads its output into memory.
+/// Returns `Ok(output)` if the call succeeded, or `Err(revert_data)` if it reverted.
 pub fn call_contract(
     addr: Address,
     value: u64,
     data: &[u8],
     ret_size: Option<u64>,
-) -> Bytes {
+) -> Result<Bytes, Bytes> {
     // Perform the call without writing return data into (REVM) memory
-    call(addr, value, data.as_ptr() as u64, data.len() as u64);
+    let success = call(addr, value, data.as_ptr() as u64, data.len() as u64);
     // Load call output to memory
-    handle_call_output(ret_size)
+    let output = handle_call_output(ret_size);
+
+    if success { Ok(output) } else { Err(output) }
+}
+
+/// Returns `true` if the call succeeded, `false` if it reverted.
+pub fn call(addr: Address, value: u64, data_offset: u64, data_size: u64) -> bool {
+    call_with_gas(addr, value, data_offset, data_size, u64::MAX)
 }
 
-pub fn call(addr: Address, value: u64, data_offset: u64, data_size: u64) {
-    let addr: U256 = addr.into_word().into();
+/// Same as [`call`], but forwards at most `gas` to the callee. Either way, the caller keeps one
+/// 64th of its remaining gas, so a callee running out of gas only fails its own call.
+pub fn call_with_gas(addr: Address, value: u64, data_offset: u64, data_size: u64, gas: u64) -> bool {
+    let addr = crate::addr_to_u256(addr);
     let addr = addr.as_limbs();
+    let success: u64;
     unsafe {
         asm!(
             "ecall",
-            in("a0") addr[0], in("a1") addr[1], in("a2") addr[2],
-            in("a3") value, in("a4") data_offset, in("a5") data_size,
+            inlateout("a0") addr[0] => success, in("a1") addr[1], in("a2") addr[2],
+            in("a3") value, in("a4") data_offset, in("a5") data_size, in("a6") gas,
             in("t0") u8::from(Syscall::Call)
         );
     }
+    success != 0
 }
 
-pub fn staticcall_contract(addr: Address, value: u64, data: &[u8], ret_size: Option<u64>) -> Bytes {
+/// Gas stipend that the host adds on top of the forwarded gas of any call transferring value.
+pub const SEND_GAS_STIPEND: u64 = 2300;
+
+/// Sends `amount` wei to `to` without forwarding any gas, like Solidity's `transfer` and `send`.
+/// The recipient only gets the [`SEND_GAS_STIPEND`]: enough to log an event, but not to write to
+/// storage nor call back into the sender.
+/// Returns `true` if the transfer succeeded, `false` otherwise, without reverting.
+pub fn send_eth(to: Address, amount: u64) -> bool {
+    call_with_gas(to, amount, 0, 0, 0)
+}
+
+/// Performs a staticcall and loads its output into memory.
+/// Returns `Ok(output)` if the call succeeded, or `Err(revert_data)` if it reverted.
+pub fn staticcall_contract(addr: Address, value: u64, data: &[u8], ret_size: Option<u64>) -> Result<Bytes, Bytes> {
     // Perform the staticcall without writing return data into (REVM) memory
-    staticcall(addr, value, data.as_ptr() as u64, data.len() as u64);
+    let success = staticcall(addr, value, data.as_ptr() as u64, data.len() as u64);
     // Load call output to memory
-    handle_call_output(ret_size)
+    let output = handle_call_output(ret_size);
+
+    if success { Ok(output) } else { Err(output) }
 }
 
 fn handle_call_output(ret_size: Option<u64>) -> Bytes {
@@ -133,17 +120,53 @@ fn handle_call_output(ret_size: Option<u64>) -> Bytes {
     Bytes::from(ret_data)
 }
 
-pub fn staticcall(addr: Address, value: u64, data_offset: u64, data_size: u64) {
-    let addr: U256 = addr.into_word().into();
+/// Returns `true` if the staticcall succeeded, `false` if it reverted.
+pub fn staticcall(addr: Address, value: u64, data_offset: u64, data_size: u64) -> bool {
+    staticcall_with_gas(addr, value, data_offset, data_size, u64::MAX)
+}
+
+/// Same as [`staticcall`], but forwards at most `gas` to the callee, like [`call_with_gas`].
+pub fn staticcall_with_gas(addr: Address, value: u64, data_offset: u64, data_size: u64, gas: u64) -> bool {
+    let addr = crate::addr_to_u256(addr);
     let addr = addr.as_limbs();
+    let success: u64;
     unsafe {
         asm!(
             "ecall",
-            in("a0") addr[0], in("a1") addr[1], in("a2") addr[2],
-            in("a3") value, in("a4") data_offset, in("a5") data_size,
+            inlateout("a0") addr[0] => success, in("a1") addr[1], in("a2") addr[2],
+            in("a3") value, in("a4") data_offset, in("a5") data_size, in("a6") gas,
             in("t0") u8::from(Syscall::StaticCall)
         );
     }
+    success != 0
+}
+
+/// Performs a delegatecall and loads its output into memory.
+/// The code at `addr` runs with the storage, caller and value of the current contract.
+/// Returns `Ok(output)` if the call succeeded, or `Err(revert_data)` if it reverted.
+pub fn delegatecall_contract(addr: Address, data: &[u8], ret_size: Option<u64>) -> Result<Bytes, Bytes> {
+    // Perform the delegatecall without writing return data into (REVM) memory
+    let success = delegatecall(addr, data.as_ptr() as u64, data.len() as u64);
+    // Load call output to memory
+    let output = handle_call_output(ret_size);
+
+    if success { Ok(output) } else { Err(output) }
+}
+
+/// Returns `true` if the delegatecall succeeded, `false` if it reverted.
+pub fn delegatecall(addr: Address, data_offset: u64, data_size: u64) -> bool {
+    let addr = crate::addr_to_u256(addr);
+    let addr = addr.as_limbs();
+    let success: u64;
+    unsafe {
+        asm!(
+            "ecall",
+            inlateout("a0") addr[0] => success, in("a1") addr[1], in("a2") addr[2],
+            in("a3") data_offset, in("a4") data_size,
+            in("t0") u8::from(Syscall::DelegateCall)
+        );
+    }
+    success != 0
 }
 
 pub fn return_data_size() -> u64 {
@@ -0,0 +1,97 @@
+// Call contexts and backends of the interfaces generated by `contract-derive`.
+//
+// Free of syscalls, so that host-side tests can include this file to exercise generated interfaces.
+use alloy_core::primitives::{Address, Bytes};
+use core::marker::PhantomData;
+
+// Concrete types implementing the context traits
+pub struct ReadOnly;
+pub struct ReadWrite;
+
+// Marker traits to determine call context
+pub trait CallCtx {}
+pub trait StaticCtx: CallCtx {}
+pub trait MutableCtx: StaticCtx {}
+
+impl CallCtx for ReadOnly {}
+impl CallCtx for ReadWrite {}
+impl StaticCtx for ReadOnly {}
+impl StaticCtx for ReadWrite {}
+impl MutableCtx for ReadWrite {}
+
+// Marker trait to connect contract method context with call ctx
+pub trait MethodCtx {
+    type Allowed: CallCtx;
+}
+impl<'a, T> MethodCtx for &'a T {
+    type Allowed = ReadOnly;
+}
+impl<'a, T> MethodCtx for &'a mut T {
+    type Allowed = ReadWrite;
+}
+
+// Types and traits to build a MethodCtx-aware interface
+pub struct InterfaceBuilder<I> {
+    pub address: Address,
+    pub _phantom: PhantomData<I>,
+}
+
+pub trait InitInterface: Sized {
+    fn new(address: Address) -> InterfaceBuilder<Self>;
+}
+
+// Key change: Add trait to convert between interface types
+pub trait IntoInterface<T> {
+    fn into_interface(self) -> T;
+}
+
+impl<I> InterfaceBuilder<I> {
+    pub fn with_ctx<M: MethodCtx, T>(self, _: M) -> T
+    where
+        I: IntoInterface<T>,
+        M: MethodCtx<Allowed = T::Context>,
+        T: FromBuilder,
+    {
+        let target_builder = InterfaceBuilder {
+            address: self.address,
+            _phantom: PhantomData,
+        };
+        T::from_builder(target_builder)
+    }
+}
+
+/// Links a contract to its generated interface (implemented by `#[contract]`), so that it can be
+/// called through its own interface, e.g. to call another instance of the same contract.
+pub trait HasInterface {
+    type Interface: InitInterface;
+
+    /// Builds the interface of the instance deployed at `address`.
+    fn at(address: Address) -> InterfaceBuilder<Self::Interface> {
+        Self::Interface::new(address)
+    }
+}
+
+pub trait FromBuilder: Sized {
+    type Context: CallCtx;
+    fn from_builder(builder: InterfaceBuilder<Self>) -> Self;
+}
+
+/// Performs the calls of generated interfaces, returning `Ok(output)` if the call succeeded, or
+/// `Err(revert_data)` if it reverted. Contracts use the syscalls ([`crate::SyscallBackend`]),
+/// while tests can swap in a mock with `with_backend` to capture the calldata.
+pub trait CallBackend {
+    fn call(
+        &self,
+        addr: Address,
+        value: u64,
+        data: &[u8],
+        ret_size: Option<u64>,
+    ) -> Result<Bytes, Bytes>;
+    fn staticcall(
+        &self,
+        addr: Address,
+        value: u64,
+        data: &[u8],
+        ret_size: Option<u64>,
+    ) -> Result<Bytes, Bytes>;
+}
@@ -1,7 +1,7 @@
 extern crate alloc;
 use alloy_core::primitives::{Address, Bytes, U32};
 use alloy_sol_types::{SolType, SolValue};
-use ext_alloc::vec::Vec;
+use ext_alloc::{string::String, vec::Vec};
 use core::{arch::asm, marker::PhantomData, u64};
 use eth_riscv_syscalls::Syscall;
 
@@ -10,6 +10,10 @@ use crate::{FromBuilder, InitInterface, MethodCtx, ReadWrite};
 pub trait Deployable {
     type Interface: InitInterface;
 
+    /// Solidity types of the constructor args (e.g. `address,uint256`), as exported by the contract.
+    /// When known, deployments with different args revert rather than deploying a misconfigured contract.
+    const CONSTRUCTOR_ARGS: Option<&'static str> = None;
+
     /// Returns the contract's runtime bytecode
     fn __runtime() -> &'static [u8];
 
@@ -52,6 +56,12 @@ where
         T: FromBuilder<Context = M::Allowed>,
         D::Interface: crate::IntoInterface<T>
     {
+        if let Some(expected) = D::CONSTRUCTOR_ARGS {
+            if !matches_constructor_args(expected, <Args::SolType as SolType>::SOL_NAME) {
+                crate::revert_with_error("Constructor args mismatch".as_bytes());
+            }
+        }
+
         let bytecode = D::__runtime();
         let encoded_args = self.args.abi_encode();
 
@@ -85,6 +95,84 @@ where
     }
 }
 
+// Compares the ABI param types of the deployment args (`SOL_NAME` of `Args`) against the exported ones.
+// Single args are named as themselves, and multiple ones as a tuple: `address` or `(address,uint256)`.
+// A tuple may also be the only param of the constructor, in which case it isn't unpacked.
+fn matches_constructor_args(expected: &str, args: &str) -> bool {
+    let expected = param_types(expected);
+    let args = normalize_type(args);
+
+    let unpacked = if is_tuple(&args) {
+        param_types(&args[1..args.len() - 1])
+    } else {
+        Vec::from([args.clone()])
+    };
+    unpacked == expected || expected == [args]
+}
+
+// Splits a list of ABI types at its top-level commas, normalizing each of them
+fn param_types(list: &str) -> Vec<String> {
+    let mut types = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in list.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                types.push(normalize_type(&list[start..i]));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    let last = normalize_type(&list[start..]);
+    if !(types.is_empty() && last.is_empty()) {
+        types.push(last);
+    }
+    types
+}
+
+// Canonical form of an ABI type: without whitespace, and with `uint`/`int` sized as 256 bits
+fn normalize_type(ty: &str) -> String {
+    let mut normalized = String::new();
+    let mut word = String::new();
+    for c in ty.chars().filter(|c| !c.is_whitespace()).chain(core::iter::once(' ')) {
+        if c.is_ascii_alphanumeric() {
+            word.push(c);
+            continue;
+        }
+
+        normalized.push_str(&word);
+        if word == "uint" || word == "int" {
+            normalized.push_str("256");
+        }
+        word.clear();
+        if c != ' ' {
+            normalized.push(c);
+        }
+    }
+    normalized
+}
+
+// Whether `ty` is a single tuple, e.g. `(address,uint256)` but not `(address)[]`
+fn is_tuple(ty: &str) -> bool {
+    let mut depth = 0usize;
+    for (i, c) in ty.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return i == ty.len() - 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
 fn create(value: u64, data_offset: u64, data_size: u64) {
     unsafe {
         asm!(
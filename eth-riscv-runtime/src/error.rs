@@ -1,11 +1,18 @@
 extern crate alloc;
 use alloc::vec::Vec;
-use core::arch::asm;
+use core::{arch::asm, fmt};
 use crate::Syscall;
 
-pub trait Error {
+pub trait Error: Sized {
     fn abi_encode(&self) -> Vec<u8>;
-    fn abi_decode(bytes: &[u8], validate: bool) -> Self;
+
+    /// Decodes the revert data, or returns `None` if its selector doesn't belong to this error.
+    fn try_abi_decode(bytes: &[u8], validate: bool) -> Option<Self>;
+
+    fn abi_decode(bytes: &[u8], validate: bool) -> Self {
+        if bytes.len() < 4 { panic!("Invalid error length") };
+        Self::try_abi_decode(bytes, validate).expect("Unknown error")
+    }
 }
 
 pub fn revert() -> ! { revert_with_error(Vec::new().as_slice()) }
@@ -18,3 +25,34 @@ pub fn revert_with_error(data: &[u8]) -> ! {
     }
     unreachable!()
 }
+
+/// Reverts with the formatted message, see [`revert_fmt!`].
+pub fn revert_with_message(args: fmt::Arguments<'_>) -> ! {
+    revert_with_error(alloc::fmt::format(args).as_bytes())
+}
+
+/// Reverts with a message built like `format!`, e.g. to include the offending values:
+///
+/// ```ignore
+/// revert_fmt!("Insufficient balance of {}: {} < {}", owner, balance, amount);
+/// ```
+#[macro_export]
+macro_rules! revert_fmt {
+    ($($arg:tt)*) => {
+        $crate::error::revert_with_message(::core::format_args!($($arg)*))
+    };
+}
+
+/// Reverts with `msg` if `cond` doesn't hold, like `debug_assert!`.
+///
+/// The invariant is only checked when the contract is built with debug assertions. Release builds
+/// (the default profile of `r55-compile`) skip it entirely, so it doesn't cost any gas in production.
+/// Contracts can opt-in with `debug-assertions = true` under `[profile.release]`.
+#[macro_export]
+macro_rules! runtime_assert {
+    ($cond:expr, $msg:expr $(,)?) => {
+        if cfg!(debug_assertions) && !$cond {
+            $crate::revert_with_error($msg.as_bytes());
+        }
+    };
+}
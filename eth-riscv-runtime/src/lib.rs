@@ -2,12 +2,13 @@
 #![no_main]
 #![feature(alloc_error_handler, maybe_uninit_write_slice, round_char_boundary)]
 
-use alloy_core::primitives::{Address, U256};
+use alloy_core::primitives::{Address, B256, U256};
 use core::{arch::asm, fmt::Write, panic::PanicInfo, slice};
 pub use riscv_rt::entry;
 extern crate alloc as ext_alloc;
 
 mod alloc;
+pub mod bits;
 pub mod block;
 pub mod tx;
 pub mod types;
@@ -37,9 +38,13 @@ unsafe fn panic(info: &PanicInfo<'_>) -> ! {
     if !IS_PANICKING {
         IS_PANICKING = true;
 
-        // Capture the panic info msg
+        // Capture the panic info msg, along with its location when debugging panics
         let mut message = ext_alloc::string::String::new();
-        let _ = write!(message, "{:?}", info.message());
+        if cfg!(feature = "debug-panics") {
+            let _ = write!(message, "{}", info);
+        } else {
+            let _ = write!(message, "{:?}", info.message());
+        }
 
         // Convert to bytes and revert
         let msg = message.into_bytes();
@@ -72,6 +77,9 @@ pub fn sload(key: U256) -> U256 {
 }
 
 pub fn sstore(key: U256, value: U256) {
+    #[cfg(feature = "log-slot-preimages")]
+    types::flush_slot_preimages();
+
     let key = key.as_limbs();
     let value = value.as_limbs();
 
@@ -102,7 +110,84 @@ pub fn keccak256(offset: u64, size: u64) -> U256 {
     U256::from_limbs([first, second, third, fourth])
 }
 
+/// Same as [`keccak256`], but hashes `data` and returns the big-endian bytes of the hash,
+/// as used for event topics.
+pub fn keccak_b256(data: &[u8]) -> B256 {
+    B256::from(keccak256(data.as_ptr() as u64, data.len() as u64))
+}
+
+/// Keccak-256 hash of data fed in chunks (e.g. the encoded fields of an EIP-712 struct), which
+/// are accumulated and hashed at once by [`Keccak::finalize`].
+#[derive(Default)]
+pub struct Keccak {
+    buffer: ext_alloc::vec::Vec<u8>,
+}
+
+impl Keccak {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `data` to the hashed input
+    pub fn update(&mut self, data: &[u8]) -> &mut Self {
+        self.buffer.extend_from_slice(data);
+        self
+    }
+
+    /// Returns the hash of all the data fed so far, like [`keccak_b256`] of its concatenation
+    pub fn finalize(&self) -> B256 {
+        keccak_b256(&self.buffer)
+    }
+}
+
+/// Converts an address into a `U256`, left-padded with zeroes like `uint256(uint160(addr))`.
+pub fn addr_to_u256(addr: Address) -> U256 {
+    U256::from_be_bytes(addr.into_word().0)
+}
+
+/// Converts a `U256` into an address, keeping its lowest 20 bytes like `address(uint160(value))`.
+pub fn u256_to_addr(value: U256) -> Address {
+    Address::from_word(B256::from(value.to_be_bytes::<32>()))
+}
+
+/// Formats a `U256` as a decimal string, like `Strings.toString(value)` in Solidity.
+pub fn u256_to_string(value: U256) -> ext_alloc::string::String {
+    if value.is_zero() {
+        return ext_alloc::string::String::from("0");
+    }
+
+    let mut digits: ext_alloc::vec::Vec<u8> = value
+        .to_base_le(10)
+        .map(|digit| b'0' + digit as u8)
+        .collect();
+    digits.reverse();
+    ext_alloc::string::String::from_utf8(digits).expect("Digits are valid UTF-8")
+}
+
+/// Parses a decimal (e.g. `"255"`) or `0x`-prefixed hex (e.g. `"0xff"`) string into a `U256`.
+///
+/// Fails on empty strings, any other character (including signs, whitespace or `_` separators),
+/// and values that don't fit in 256 bits.
+pub fn parse_u256(s: &str) -> Result<U256, ()> {
+    let (digits, radix) = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => (hex, 16),
+        None => (s, 10),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return Err(());
+    }
+
+    U256::from_str_radix(digits, radix as u64).map_err(|_| ())
+}
+
+// The caller can't change during a call, so it is only fetched once per call frame
+static mut MSG_SENDER: Option<Address> = None;
+
 pub fn msg_sender() -> Address {
+    if let Some(sender) = unsafe { MSG_SENDER } {
+        return sender;
+    }
+
     let (first, second, third): (u64, u64, u64);
     unsafe {
         asm!("ecall", lateout("a0") first, lateout("a1") second, lateout("a2") third, in("t0") u8::from(Syscall::Caller));
@@ -111,7 +196,33 @@ pub fn msg_sender() -> Address {
     bytes[0..8].copy_from_slice(&first.to_be_bytes());
     bytes[8..16].copy_from_slice(&second.to_be_bytes());
     bytes[16..20].copy_from_slice(&third.to_be_bytes()[..4]);
-    Address::from_slice(&bytes)
+    let sender = Address::from_slice(&bytes);
+
+    unsafe { MSG_SENDER = Some(sender) };
+    sender
+}
+
+// The executing contract can't change during a call, so it is only fetched once per call frame
+static mut CONTRACT_ADDRESS: Option<Address> = None;
+
+// Returns the address of the executing contract, like `address(this)` in Solidity
+pub fn contract_address() -> Address {
+    if let Some(address) = unsafe { CONTRACT_ADDRESS } {
+        return address;
+    }
+
+    let (first, second, third): (u64, u64, u64);
+    unsafe {
+        asm!("ecall", lateout("a0") first, lateout("a1") second, lateout("a2") third, in("t0") u8::from(Syscall::Address));
+    }
+    let mut bytes = [0u8; 20];
+    bytes[0..8].copy_from_slice(&first.to_be_bytes());
+    bytes[8..16].copy_from_slice(&second.to_be_bytes());
+    bytes[16..20].copy_from_slice(&third.to_be_bytes()[..4]);
+    let address = Address::from_slice(&bytes);
+
+    unsafe { CONTRACT_ADDRESS = Some(address) };
+    address
 }
 
 pub fn msg_value() -> U256 {
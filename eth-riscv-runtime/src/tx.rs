@@ -2,7 +2,7 @@ use alloy_core::primitives::{Address, U256};
 use core::arch::asm;
 use eth_riscv_syscalls::Syscall;
 
-// Returns the gas price of the current transaction
+// Returns the effective gas price of the current transaction (capped by its EIP-1559 fees)
 pub fn gas_price() -> U256 {
     let first: u64;
     let second: u64;
@@ -12,8 +12,15 @@ pub fn gas_price() -> U256 {
     U256::from_limbs([first, second, third, fourth])
 }
 
+// The origin is fixed for the whole transaction, so it is only fetched once per call frame
+static mut ORIGIN: Option<Address> = None;
+
 // Returns sender of the transaction (full call chain)
 pub fn origin() -> Address {
+    if let Some(origin) = unsafe { ORIGIN } {
+        return origin;
+    }
+
     let first: u64;
     let second: u64;
     let third: u64;
@@ -24,5 +31,8 @@ pub fn origin() -> Address {
     bytes[0..8].copy_from_slice(&first.to_be_bytes());
     bytes[8..16].copy_from_slice(&second.to_be_bytes());
     bytes[16..20].copy_from_slice(&third.to_be_bytes()[..4]);
-    Address::from_slice(&bytes)
-}
\ No newline at end of file
+    let origin = Address::from_slice(&bytes);
+
+    unsafe { ORIGIN = Some(origin) };
+    origin
+}
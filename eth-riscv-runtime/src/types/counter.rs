@@ -0,0 +1,33 @@
+use super::*;
+
+/// Monotonic counter stored in a single slot, e.g. to allocate token ids or nonces.
+#[derive(Default)]
+pub struct Counter {
+    value: Slot<U256>,
+}
+
+impl StorageLayout for Counter {
+    fn allocate(first: u64, second: u64, third: u64, fourth: u64) -> Self {
+        Self {
+            value: Slot::allocate(first, second, third, fourth),
+        }
+    }
+}
+
+impl Counter {
+    pub fn current(&self) -> U256 {
+        self.value.read()
+    }
+
+    /// Increments the counter, and returns its value before the increment (i.e. the allocated id).
+    ///
+    /// Reverts if the counter overflows, so that an id is never allocated twice.
+    pub fn increment(&mut self) -> U256 {
+        let current = self.value.read();
+        let next = current
+            .checked_add(U256::from(1))
+            .unwrap_or_else(|| revert_with_error("Counter overflow".as_bytes()));
+        self.value.write(next);
+        current
+    }
+}
@@ -9,6 +9,15 @@ use crate::alloc::GLOBAL;
 use super::*;
 
 /// Implements a Solidity-like Mapping type.
+///
+/// The `id` is the slot allocated by the `storage` macro (the field index). Like in Solidity,
+/// that slot is never written: each entry lives at `keccak256(abi.encode(key) ++ id)`.
+/// Hence, entries can't collide with the low sequential slots of direct storage types (like
+/// `Slot`), unless a keccak256 preimage of such a slot is found.
+///
+/// For value-type keys (like `Address` or `U256`), `abi.encode(key)` is the key left-padded to a
+/// 32-byte word, which is exactly how Solidity derives mapping slots. Nested mappings use the
+/// derived slot as the `id` of the inner mapping, so the layout is storage-compatible with Solidity.
 #[derive(Default)]
 pub struct Mapping<K, V> {
     id: U256,
@@ -40,8 +49,31 @@ where
         // Call the keccak256 syscall with the concatenated bytes
         let offset = concatenated.as_ptr() as u64;
         let size = concatenated.len() as u64;
+        let slot = keccak256(offset, size);
 
-        keccak256(offset, size)
+        // Debug hook to trace the slot back to its key and mapping id
+        #[cfg(feature = "log-slot-preimages")]
+        unsafe {
+            #[allow(static_mut_refs)]
+            PENDING_PREIMAGES.push((concatenated, B256::from(slot)));
+        }
+
+        slot
+    }
+}
+
+// Preimages derived since the last `SSTORE`. Logging them right away would halt static frames
+// (which can't emit logs), so they are only logged once the frame writes storage.
+#[cfg(feature = "log-slot-preimages")]
+static mut PENDING_PREIMAGES: Vec<(Vec<u8>, B256)> = Vec::new();
+
+/// Logs the mapping slot preimages derived since the last call. Invoked by `sstore`.
+#[cfg(feature = "log-slot-preimages")]
+pub(crate) fn flush_slot_preimages() {
+    #[allow(static_mut_refs)]
+    let pending = unsafe { core::mem::take(&mut PENDING_PREIMAGES) };
+    for (preimage, slot) in pending {
+        crate::log::emit_log(&preimage, &[slot]);
     }
 }
 
@@ -69,6 +101,15 @@ where
             _phantom: PhantomData,
         }
     }
+
+    /// Reads the value, applies `f` to it, and writes the result back.
+    ///
+    /// Both accesses reuse the storage key of the guard, whereas indexing the mapping twice (to read
+    /// and then write) derives the key with keccak256 each time.
+    pub fn modify<F: FnOnce(V::Value) -> V::Value>(&mut self, f: F) {
+        let value = V::__read(self.storage_key);
+        V::__write(self.storage_key, f(value));
+    }
 }
 
 impl<V> IndirectStorage<V> for MappingGuard<V>
@@ -10,10 +10,21 @@ use alloc::vec::Vec;
 
 mod mapping;
 pub use mapping::Mapping;
+#[cfg(feature = "log-slot-preimages")]
+pub(crate) use mapping::flush_slot_preimages;
 
 mod slot;
 pub use slot::Slot;
 
+mod observable;
+pub use observable::ObservableSlot;
+
+mod counter;
+pub use counter::Counter;
+
+mod storage_vec;
+pub use storage_vec::StorageVec;
+
 ///  STORAGE TYPES:
 ///  > Must implement the following traits:
 ///     - `StorageLayout`: Allows the `storage` macro to allocate a storage slot.
@@ -28,9 +39,23 @@ pub use slot::Slot;
 /// A trait for storage types that require a dedicated slot in the storage layout
 pub trait StorageLayout {
     fn allocate(limb0: u64, limb1: u64, limb2: u64, limb3: u64) -> Self;
+
+    /// Allocates the storage type at `slot`, which can be anywhere in the `U256` range (e.g. the
+    /// hashed base slot of an ERC-7201 namespace).
+    fn allocate_u256(slot: U256) -> Self
+    where
+        Self: Sized,
+    {
+        let [limb0, limb1, limb2, limb3] = slot.into_limbs();
+        Self::allocate(limb0, limb1, limb2, limb3)
+    }
 }
 
 /// Internal trait, for low-level storage operations.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be stored directly",
+    note = "wrap the value in a storage type, e.g. `Mapping<K, Slot<{Self}>>`"
+)]
 pub trait StorageStorable {
     type Value: SolValue
         + core::convert::From<<<Self::Value as SolValue>::SolType as SolType>::RustType>;
@@ -0,0 +1,62 @@
+use super::*;
+
+use alloc::format;
+
+/// `Slot` that emits a log every time it is written, for debugging or indexing purposes.
+///
+/// The log matches the Solidity event `SlotChanged(uint256 indexed slot, V oldValue, V newValue)`,
+/// so indexers can decode it like any other event. Observing a value isn't free: each write also
+/// reads the old value and emits the log.
+#[derive(Default)]
+pub struct ObservableSlot<V> {
+    id: U256,
+    _pd: PhantomData<V>,
+}
+
+impl<V> StorageLayout for ObservableSlot<V> {
+    fn allocate(first: u64, second: u64, third: u64, fourth: u64) -> Self {
+        Self {
+            id: U256::from_limbs([first, second, third, fourth]),
+            _pd: PhantomData::default(),
+        }
+    }
+}
+
+impl<V> StorageStorable for ObservableSlot<V>
+where
+    V: SolValue + core::convert::From<<<V as SolValue>::SolType as SolType>::RustType> + Clone,
+{
+    type Value = V;
+
+    fn __read(key: U256) -> Self::Value {
+        Slot::<V>::__read(key)
+    }
+
+    fn __write(key: U256, value: Self::Value) {
+        let old = Slot::<V>::__read(key);
+        let data = (old, value.clone()).abi_encode_params();
+        Slot::<V>::__write(key, value);
+
+        emit_log(&data, &[slot_changed_topic::<V>(), B256::from(key)]);
+    }
+}
+
+/// Topic of the `SlotChanged(uint256,V,V)` event
+fn slot_changed_topic<V: SolValue>() -> B256 {
+    let sol_name = <V::SolType as SolType>::SOL_NAME;
+    let signature = format!("SlotChanged(uint256,{},{})", sol_name, sol_name);
+    keccak_b256(signature.as_bytes())
+}
+
+impl<V> DirectStorage<V> for ObservableSlot<V>
+where
+    Self: StorageStorable<Value = V>,
+{
+    fn read(&self) -> V {
+        Self::__read(self.id)
+    }
+
+    fn write(&mut self, value: V) {
+        Self::__write(self.id, value)
+    }
+}
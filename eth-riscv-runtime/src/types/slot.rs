@@ -1,8 +1,15 @@
 use super::*;
 
+use alloc::vec;
+use core::cmp::Ordering;
 use core::ops::{Add, AddAssign, Sub, SubAssign};
 
 /// Wrapper around `alloy::primitives` that can be written in a single slot (single EVM word).
+///
+/// Tuples of value types that fit in 32 bytes, like `(u64, u64)`, are packed into the slot. Other
+/// tuples, like `(Address, Address)`, are rejected by the `storage` macro.
+/// Static arrays of value types, like `[U256; 4]`, are stored in consecutive slots starting at the
+/// allocated one, packing as many elements as fit in each of them.
 #[derive(Default)]
 pub struct Slot<V> {
     id: U256,
@@ -25,18 +32,164 @@ where
     type Value = V;
 
     fn __read(key: U256) -> Self::Value {
-        let bytes: [u8; 32] = sload(key).to_be_bytes();
+        if let Some((member, len)) = array_members::<V>() {
+            return V::abi_decode(&read_array(key, member, len)).unwrap_or_else(|_| revert());
+        }
+
+        let word = sload(key);
+        let bytes = match packed_members::<V>() {
+            Some(members) => unpack(word, &members),
+            None => (word << fixed_bytes_shift::<V>())
+                .to_be_bytes::<32>()
+                .to_vec(),
+        };
         V::abi_decode(&bytes).unwrap_or_else(|_| revert())
     }
 
     fn __write(key: U256, value: Self::Value) {
         let bytes = value.abi_encode();
-        let mut padded = [0u8; 32];
-        padded[..bytes.len()].copy_from_slice(&bytes);
-        sstore(key, U256::from_be_bytes(padded));
+        if let Some((member, _)) = array_members::<V>() {
+            write_array(key, member, &bytes);
+            return;
+        }
+
+        let word = match packed_members::<V>() {
+            Some(members) => pack(&bytes, &members),
+            None => {
+                let mut padded = [0u8; 32];
+                padded[..bytes.len()].copy_from_slice(&bytes);
+                U256::from_be_bytes(padded) >> fixed_bytes_shift::<V>()
+            }
+        };
+        sstore(key, word);
     }
 }
 
+/// Solidity value types, by how they are laid out in their ABI word. Sizes are in bytes.
+#[derive(Clone, Copy)]
+enum ValueType {
+    /// `bytesN`, left-aligned
+    FixedBytes(usize),
+    /// `intN`, right-aligned and sign-extended
+    Int(usize),
+    /// `uintN`, `address` and `bool`, right-aligned
+    Uint(usize),
+}
+
+impl ValueType {
+    fn parse(sol_name: &str) -> Option<Self> {
+        let bits = |bits: &str| bits.parse::<usize>().ok().map(|bits| bits / 8);
+        match sol_name {
+            "address" => Some(Self::Uint(20)),
+            "bool" => Some(Self::Uint(1)),
+            _ => {
+                if let Some(size) = sol_name.strip_prefix("bytes") {
+                    size.parse().ok().map(Self::FixedBytes)
+                } else if let Some(size) = sol_name.strip_prefix("uint") {
+                    bits(size).map(Self::Uint)
+                } else {
+                    bits(sol_name.strip_prefix("int")?).map(Self::Int)
+                }
+            }
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Self::FixedBytes(size) | Self::Int(size) | Self::Uint(size) => size,
+        }
+    }
+}
+
+/// Solidity stores `bytesN` values right-aligned in their slot, whereas the ABI left-aligns them.
+/// Returns the amount of bits between both alignments, or 0 for any other type.
+fn fixed_bytes_shift<V: SolValue>() -> usize {
+    match ValueType::parse(<V::SolType as SolType>::SOL_NAME) {
+        Some(ValueType::FixedBytes(size)) => (32 - size) * 8,
+        _ => 0,
+    }
+}
+
+/// Returns the members of `V` if it is a tuple of value types that fits in a single slot.
+fn packed_members<V: SolValue>() -> Option<Vec<ValueType>> {
+    let members = <V::SolType as SolType>::SOL_NAME
+        .strip_prefix('(')?
+        .strip_suffix(')')?
+        .split(',')
+        .map(ValueType::parse)
+        .collect::<Option<Vec<_>>>()?;
+
+    (members.iter().map(|m| m.size()).sum::<usize>() <= 32).then_some(members)
+}
+
+/// Returns the element type and length of `V` if it is a static array of value types.
+fn array_members<V: SolValue>() -> Option<(ValueType, usize)> {
+    let (element, len) = <V::SolType as SolType>::SOL_NAME
+        .strip_suffix(']')?
+        .rsplit_once('[')?;
+
+    Some((ValueType::parse(element)?, len.parse().ok()?))
+}
+
+/// Reads the ABI encoding of a static array, whose elements are packed from the `key` slot onwards
+fn read_array(key: U256, member: ValueType, len: usize) -> Vec<u8> {
+    let per_slot = 32 / member.size();
+    let mut words = Vec::with_capacity(len * 32);
+    for (i, first) in (0..len).step_by(per_slot).enumerate() {
+        let members = vec![member; per_slot.min(len - first)];
+        words.extend(unpack(sload(key + U256::from(i)), &members));
+    }
+    words
+}
+
+/// Packs the elements of an ABI-encoded static array into consecutive slots, from `key` onwards
+fn write_array(key: U256, member: ValueType, words: &[u8]) {
+    let per_slot = 32 / member.size();
+    for (i, chunk) in words.chunks(per_slot * 32).enumerate() {
+        let members = vec![member; chunk.len() / 32];
+        sstore(key + U256::from(i), pack(chunk, &members));
+    }
+}
+
+/// Concatenates the members of an ABI-encoded tuple into a single word. Like Solidity, the first
+/// member takes the lowest-order bytes.
+fn pack(words: &[u8], members: &[ValueType]) -> U256 {
+    let mut packed = [0u8; 32];
+    let mut end = 32;
+    for (word, member) in words.chunks(32).zip(members) {
+        let size = member.size();
+        let bytes = match member {
+            ValueType::FixedBytes(_) => &word[..size],
+            ValueType::Int(_) | ValueType::Uint(_) => &word[32 - size..],
+        };
+        packed[(end - size)..end].copy_from_slice(bytes);
+        end -= size;
+    }
+    U256::from_be_bytes(packed)
+}
+
+/// Splits a word packed with [`pack`] back into the ABI encoding of its tuple
+fn unpack(packed: U256, members: &[ValueType]) -> Vec<u8> {
+    let packed = packed.to_be_bytes::<32>();
+    let mut words = vec![0u8; members.len() * 32];
+    let mut end = 32;
+    for (word, member) in words.chunks_mut(32).zip(members) {
+        let size = member.size();
+        let bytes = &packed[(end - size)..end];
+        match member {
+            ValueType::FixedBytes(_) => word[..size].copy_from_slice(bytes),
+            ValueType::Int(_) | ValueType::Uint(_) => {
+                if matches!(member, ValueType::Int(_)) && bytes[0] & 0x80 != 0 {
+                    word.fill(0xff);
+                }
+                word[(32 - size)..].copy_from_slice(bytes);
+            }
+        }
+        end -= size;
+    }
+    words
+}
+
 impl<V> DirectStorage<V> for Slot<V>
 where
     Self: StorageStorable<Value = V>,
@@ -96,9 +249,19 @@ where
 impl<V> PartialEq for Slot<V>
 where
     Self: StorageStorable<Value = V>,
-    V: StorageStorable + PartialEq,
+    V: PartialEq,
 {
     fn eq(&self, other: &Self) -> bool {
         self.read() == other.read()
     }
 }
+
+impl<V> PartialOrd for Slot<V>
+where
+    Self: StorageStorable<Value = V>,
+    V: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.read().partial_cmp(&other.read())
+    }
+}
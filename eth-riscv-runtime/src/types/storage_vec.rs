@@ -0,0 +1,89 @@
+use super::*;
+
+/// Implements a Solidity-like dynamic array of value types (like `Address` or `U256`).
+///
+/// Like in Solidity, the `id` slot holds the length of the array, while its elements live at
+/// consecutive slots from `keccak256(abi.encode(id))` onwards. Unlike Solidity, elements are never
+/// packed: each of them takes a whole slot, however small it is.
+#[derive(Default)]
+pub struct StorageVec<V> {
+    id: U256,
+    _pd: PhantomData<V>,
+}
+
+impl<V> StorageLayout for StorageVec<V> {
+    fn allocate(first: u64, second: u64, third: u64, fourth: u64) -> Self {
+        Self {
+            id: U256::from_limbs([first, second, third, fourth]),
+            _pd: PhantomData::default(),
+        }
+    }
+}
+
+impl<V> StorageVec<V>
+where
+    Slot<V>: StorageStorable<Value = V>,
+{
+    pub fn len(&self) -> u64 {
+        sload(self.id).to::<u64>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the element at `index`, or `None` if it is out of bounds.
+    pub fn get(&self, index: u64) -> Option<V> {
+        (index < self.len()).then(|| Slot::<V>::__read(self.element_slot(index)))
+    }
+
+    /// Overwrites the element at `index`, reverting if it is out of bounds.
+    pub fn set(&mut self, index: u64, value: V) {
+        if index >= self.len() {
+            revert_with_error("Index out of bounds".as_bytes());
+        }
+        Slot::<V>::__write(self.element_slot(index), value);
+    }
+
+    pub fn push(&mut self, value: V) {
+        let len = self.len();
+        Slot::<V>::__write(self.element_slot(len), value);
+        sstore(self.id, U256::from(len + 1));
+    }
+
+    /// Removes the last element, clearing its slot, and returns it (`None` if the array is empty).
+    pub fn pop(&mut self) -> Option<V> {
+        let last = self.len().checked_sub(1)?;
+        let slot = self.element_slot(last);
+        let value = Slot::<V>::__read(slot);
+
+        sstore(slot, U256::ZERO);
+        sstore(self.id, U256::from(last));
+        Some(value)
+    }
+
+    /// Removes the element at `index` and returns it, reverting if it is out of bounds.
+    ///
+    /// The last element is moved into its slot, rather than shifting every following element, so
+    /// the removal costs the same whatever the length of the array, but doesn't preserve its order.
+    pub fn swap_remove(&mut self, index: u64) -> V {
+        let len = self.len();
+        if index >= len {
+            revert_with_error("Index out of bounds".as_bytes());
+        }
+
+        let removed = Slot::<V>::__read(self.element_slot(index));
+        if index != len - 1 {
+            let last = Slot::<V>::__read(self.element_slot(len - 1));
+            Slot::<V>::__write(self.element_slot(index), last);
+        }
+        sstore(self.element_slot(len - 1), U256::ZERO);
+        sstore(self.id, U256::from(len - 1));
+        removed
+    }
+
+    fn element_slot(&self, index: u64) -> U256 {
+        let id_bytes: [u8; 32] = self.id.to_be_bytes();
+        keccak256(id_bytes.as_ptr() as u64, 32) + U256::from(index)
+    }
+}
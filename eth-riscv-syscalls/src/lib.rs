@@ -54,18 +54,26 @@ macro_rules! syscalls {
 // The opcode for each syscall matches the corresponding EVM opcode,
 // as described on https://www.evm.codes.
 //
+// t0: 0x1a, opcode for byte, a0-a3: 256-bit value, a4: byte index (from the most significant), returns 256-bit value
+// t0: 0x1b, opcode for shl, a0-a3: 256-bit value, a4: shift, returns 256-bit value
+// t0: 0x1c, opcode for shr, a0-a3: 256-bit value, a4: shift, returns 256-bit value
+// t0: 0x1d, opcode for sar, a0-a3: 256-bit value, a4: shift, returns 256-bit value
 // t0: 0x20, opcode for keccak256, a0: offset, a1: size, returns keccak256 hash
+// t0: 0x30, opcode for address, returns the address of the executing contract
 // t0: 0x32, opcode for origin, returns an address
 // t0: 0x33, opcode for caller, returns an address
 // t0: 0x34, opcode for callvalue, a0: first limb, a1: second limb, a2: third limb, a3: fourth limb, returns 256-bit value
 // t0: 0x3A, opcode for gasprice, returns 256-bit value
 // t0: 0x3d, opcode for returndatasize, returns 64-bit value
 // t0: 0x3e, opcode for returndatacopy, a0: memory offset, a1: return data offset, a2: return data size, returns nothing
+// t0: 0x40, opcode for blockhash, a0: block number, returns 256-bit hash (zero outside of the last 256 blocks)
+// t0: 0x44, opcode for prevrandao (formerly difficulty), returns 256-bit value
 // t0: 0x54, opcode for sload, a0: storage key, returns 256-bit value
 // t0: 0x55, opcode for sstore, a0-a3: 256-bit storage key, a4-a7: 256-bit storage value, returns nothing
 // t0: 0xf0, opcode for create, args: a0: 64-bit value, a1: calldata offset, a2: calldata size, returns an address
-// t0: 0xf1, opcode for call, args: a0-a2: address, a3: 64-bit value, a4: calldata offset, a5: calldata size
-// t0: 0xfa, opcode for staticcall, args: a0-a2: address, a3: 64-bit value, a4: calldata offset, a5: calldata size
+// t0: 0xf1, opcode for call, args: a0-a2: address, a3: 64-bit value, a4: calldata offset, a5: calldata size, a6: gas limit
+// t0: 0xf4, opcode for delegatecall, args: a0-a2: address, a3: calldata offset, a4: calldata size
+// t0: 0xfa, opcode for staticcall, args: a0-a2: address, a3: 64-bit value, a4: calldata offset, a5: calldata size, a6: gas limit
 // t0: 0xf3, opcode for return, a0: memory address of data, a1: length of data in bytes, doesn't return
 // t0: 0xfd, opcode for revert, doesn't return
 //
@@ -76,15 +84,22 @@ macro_rules! syscalls {
 
 syscalls!(
     // EVM opcodes
+    (0x1a, Byte, "byte"),
+    (0x1b, Shl, "shl"),
+    (0x1c, Shr, "shr"),
+    (0x1d, Sar, "sar"),
     (0x20, Keccak256, "keccak256"),
+    (0x30, Address, "address"),
     (0x32, Origin, "origin"),
     (0x33, Caller, "caller"),
     (0x34, CallValue, "callvalue"),
     (0x3A, GasPrice, "gasprice"),
     (0x3D, ReturnDataSize, "returndatasize"),
     (0x3E, ReturnDataCopy, "returndatacopy"),
+    (0x40, BlockHash, "blockhash"),
     (0x42, Timestamp, "timestamp"),
     (0x43, Number, "number"),
+    (0x44, PrevRandao, "prevrandao"),
     (0x45, GasLimit, "gaslimit"),
     (0x46, ChainId, "chainid"),
     (0x48, BaseFee, "basefee"),
@@ -92,6 +107,7 @@ syscalls!(
     (0x55, SStore, "sstore"),
     (0xf0, Create, "create"),
     (0xf1, Call, "call"),
+    (0xf4, DelegateCall, "delegatecall"),
     (0xfa, StaticCall, "staticcall"),
     (0xf3, Return, "return"),
     (0xfd, Revert, "revert"),
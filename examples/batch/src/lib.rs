@@ -0,0 +1,44 @@
+#![no_std]
+#![no_main]
+
+use core::default::Default;
+
+use contract_derive::{contract, storage};
+use eth_riscv_runtime::types::*;
+
+use alloy_core::primitives::{Address, U256};
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+// -- CONTRACT -----------------------------------------------------------------
+// Ledger credited in batches, whose `Vec<(Address, U256)>` arg is exposed as `(address,uint256)[]`
+#[storage]
+pub struct Batch {
+    total: Slot<U256>,
+    amount_of: Mapping<Address, Slot<U256>>,
+}
+
+#[contract]
+impl Batch {
+    // -- STATE MODIFYING FUNCTIONS --------------------------------------------
+    // Credits each `(recipient, amount)` pair, returning the number of pairs
+    pub fn batch(&mut self, transfers: Vec<(Address, U256)>) -> U256 {
+        let count = transfers.len();
+        for (to, amount) in transfers {
+            let to_amount = self.amount_of[to].read();
+            self.amount_of[to].write(to_amount + amount);
+            self.total += amount;
+        }
+        U256::from(count)
+    }
+
+    // -- READ-ONLY FUNCTIONS --------------------------------------------------
+    pub fn total(&self) -> U256 {
+        self.total.read()
+    }
+
+    pub fn amount_of(&self, account: Address) -> U256 {
+        self.amount_of[account].read()
+    }
+}
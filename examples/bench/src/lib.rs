@@ -0,0 +1,47 @@
+#![no_std]
+#![no_main]
+
+use core::default::Default;
+
+use contract_derive::{bench, contract, storage};
+use eth_riscv_runtime::types::*;
+
+use alloy_core::primitives::U256;
+
+extern crate alloc;
+
+// -- CONTRACT -----------------------------------------------------------------
+// Fixture for gas reports, whose `#[bench]` methods have increasingly heavy storage usage
+#[storage]
+pub struct Bench {
+    value: Slot<U256>,
+    values: Mapping<U256, Slot<U256>>,
+}
+
+#[contract]
+impl Bench {
+    // -- STATE MODIFYING FUNCTIONS --------------------------------------------
+    #[bench]
+    pub fn store(&mut self, value: U256) {
+        self.value.write(value);
+    }
+
+    // Writes `count` slots, so that it outweighs any other method for a few of them
+    #[bench]
+    pub fn fill(&mut self, count: u64) {
+        for i in 0..count {
+            self.values[U256::from(i)].write(U256::from(i + 1));
+        }
+    }
+
+    // -- READ-ONLY FUNCTIONS --------------------------------------------------
+    #[bench]
+    pub fn load(&self) -> U256 {
+        self.value.read()
+    }
+
+    // Not benchmarked, so left out of `BENCHED_METHODS`
+    pub fn value_at(&self, index: U256) -> U256 {
+        self.values[index].read()
+    }
+}
@@ -0,0 +1,77 @@
+#![no_std]
+#![no_main]
+
+use core::default::Default;
+
+use alloy_core::primitives::U256;
+use contract_derive::{contract, storage};
+use eth_riscv_runtime::types::*;
+
+extern crate alloc;
+
+#[storage]
+pub struct Bounds {
+    floor: Slot<U256>,
+    ceiling: Slot<U256>,
+}
+
+#[contract]
+impl Bounds {
+    // -- STATE MODIFYING FUNCTIONS --------------------------------------------
+    pub fn set_floor(&mut self, value: U256) {
+        self.floor.write(value);
+    }
+
+    pub fn set_ceiling(&mut self, value: U256) {
+        self.ceiling.write(value);
+    }
+
+    pub fn set_range(&mut self, floor: U256, ceiling: U256) {
+        runtime_assert!(floor <= ceiling, "Floor above ceiling");
+
+        self.floor.write(floor);
+        self.ceiling.write(ceiling);
+    }
+
+    pub fn raise_floor(&mut self, value: U256) {
+        let ceiling = self.ceiling.read();
+        if value > ceiling {
+            revert_fmt!("Floor {} above ceiling {}", value, ceiling);
+        }
+
+        self.floor.write(value);
+    }
+
+    // Only dispatched when built with the `admin` feature
+    #[cfg(feature = "admin")]
+    pub fn reset(&mut self) {
+        self.floor.write(U256::ZERO);
+        self.ceiling.write(U256::ZERO);
+    }
+
+    // Single-use: consumes the contract, although its (cleared) storage persists
+    pub fn close(mut self) -> U256 {
+        let width = self.ceiling.read() - self.floor.read();
+        self.floor.write(U256::ZERO);
+        self.ceiling.write(U256::ZERO);
+        width
+    }
+
+    // -- READ-ONLY FUNCTIONS --------------------------------------------------
+    // Panics if the floor is above the ceiling, reverting with its location (`debug-panics`)
+    pub fn width(&self) -> U256 {
+        self.ceiling
+            .read()
+            .checked_sub(self.floor.read())
+            .expect("Floor above ceiling")
+    }
+
+    // Slots are compared by their stored values
+    pub fn is_below(&self) -> bool {
+        self.floor < self.ceiling
+    }
+
+    pub fn is_equal(&self) -> bool {
+        self.floor == self.ceiling
+    }
+}
@@ -0,0 +1,99 @@
+#![no_std]
+#![no_main]
+
+use core::default::Default;
+
+use alloy_core::primitives::{Address, Bytes, B256, U256};
+use contract_derive::contract;
+
+extern crate alloc;
+use alloc::{string::String, vec::Vec};
+
+#[derive(Default)]
+pub struct EnvInfo;
+
+#[contract]
+impl EnvInfo {
+    pub fn prevrandao(&self) -> U256 {
+        block::prevrandao()
+    }
+
+    pub fn difficulty(&self) -> U256 {
+        block::difficulty()
+    }
+
+    pub fn gas_price(&self) -> U256 {
+        tx::gas_price()
+    }
+
+    pub fn block_gas_limit(&self) -> u64 {
+        block::gas_limit_u64()
+    }
+
+    pub fn block_hash(&self, number: u64) -> B256 {
+        block::block_hash(number)
+    }
+
+    // Time-locked view, which only unlocks from block `unlock_at` onwards
+    pub fn is_unlocked(&self, unlock_at: u64) -> bool {
+        block::number() >= U256::from(unlock_at)
+    }
+
+    pub fn sender(&self) -> Address {
+        msg_sender()
+    }
+
+    // Same logic either way, except for reading the caller
+    pub fn maybe_sender(&self, read: bool) -> Address {
+        if read { msg_sender() } else { Address::ZERO }
+    }
+
+    // Repeated reads are served from the runtime cache, without further syscalls
+    pub fn senders(&self) -> (Address, Address, Address) {
+        (msg_sender(), msg_sender(), tx::origin())
+    }
+
+    pub fn to_u256(&self, addr: Address) -> U256 {
+        addr_to_u256(addr)
+    }
+
+    pub fn to_address(&self, value: U256) -> Address {
+        u256_to_addr(value)
+    }
+
+    pub fn to_decimal(&self, value: U256) -> String {
+        u256_to_string(value)
+    }
+
+    // Reverts on invalid input
+    pub fn from_decimal_or_hex(&self, value: String) -> U256 {
+        parse_u256(&value).unwrap_or_else(|_| revert())
+    }
+
+    pub fn hash(&self, data: Bytes) -> B256 {
+        keccak_b256(&data)
+    }
+
+    pub fn hash_parts(&self, parts: Vec<Bytes>) -> B256 {
+        let mut hasher = Keccak::new();
+        for part in &parts {
+            hasher.update(part);
+        }
+        hasher.finalize()
+    }
+
+    // Shifts and byte extraction, offloaded to the host
+    pub fn bit_ops(&self, value: U256, shift: u64, index: u64) -> (U256, U256, U256, U256) {
+        (
+            bits::shl(value, shift),
+            bits::shr(value, shift),
+            bits::sar(value, shift),
+            bits::byte(value, index),
+        )
+    }
+
+    // Keeps the lower 32 bits
+    pub fn truncate(&self, x: u64) -> u32 {
+        x as u32
+    }
+}
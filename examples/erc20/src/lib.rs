@@ -9,6 +9,7 @@ use eth_riscv_runtime::types::*;
 use alloy_core::primitives::{Address, U256};
 
 extern crate alloc;
+use alloc::string::String;
 
 // -- EVENTS -------------------------------------------------------------------
 #[derive(Event)]
@@ -49,6 +50,11 @@ pub enum ERC20Error {
     ZeroAddress,
 }
 
+// -- CONSTANTS ----------------------------------------------------------------
+// TODO: move to storage once strings can be stored. Spans two ABI words, as names often do.
+const NAME: &str = "R55 Example Token (RISC-V contract)";
+const SYMBOL: &str = "R55";
+
 // -- CONTRACT -----------------------------------------------------------------
 #[storage]
 pub struct ERC20 {
@@ -65,7 +71,10 @@ pub struct ERC20 {
 #[contract]
 impl ERC20 {
     // -- CONSTRUCTOR ----------------------------------------------------------
-    pub fn new(owner: Address) -> Self {
+    pub fn new(owner: Address) -> Result<Self, ERC20Error> {
+        // Perform sanity checks
+        if owner == Address::ZERO { return Err(ERC20Error::ZeroAddress) };
+
         // Init the contract
         let mut erc20 = ERC20::default();
 
@@ -73,7 +82,7 @@ impl ERC20 {
         erc20.owner.write(owner);
 
         // Return the initialized contract
-        erc20
+        Ok(erc20)
     }
 
     // -- STATE MODIFYING FUNCTIONS --------------------------------------------
@@ -169,6 +178,8 @@ impl ERC20 {
         // Perform safety check 
         if from != self.owner.read() { return Err(ERC20Error::OnlyOwner) }; 
         if from == new_owner { return Err(ERC20Error::SelfTransfer) }; 
+        // Renouncing must be intentional, see `renounce_ownership`
+        if new_owner == Address::ZERO { return Err(ERC20Error::ZeroAddress) };
 
         // Update state
         self.owner.write(new_owner);
@@ -178,7 +189,30 @@ impl ERC20 {
         Ok(true)
     }
 
+    // Leaves the token without an owner, so that owner-only methods can't be called anymore
+    pub fn renounce_ownership(&mut self) -> Result<bool, ERC20Error> {
+        let from = msg_sender();
+
+        // Perform safety check
+        if from != self.owner.read() { return Err(ERC20Error::OnlyOwner) };
+
+        // Update state
+        self.owner.write(Address::ZERO);
+
+        // Emit event + return
+        log::emit(OwnershipTransferred::new(from, Address::ZERO));
+        Ok(true)
+    }
+
     // -- READ-ONLY FUNCTIONS --------------------------------------------------
+    pub fn name(&self) -> String {
+        String::from(NAME)
+    }
+
+    pub fn symbol(&self) -> String {
+        String::from(SYMBOL)
+    }
+
     pub fn owner(&self) -> Address {
         self.owner.read()
     }
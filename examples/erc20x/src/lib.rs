@@ -3,15 +3,29 @@
 
 use core::default::Default;
 
-use alloy_core::primitives::{Address, U256, Bytes};
-use contract_derive::{contract, show_streams};
+use alloy_core::primitives::{keccak256, Address, U256, Bytes};
+use contract_derive::{contract, deployable, show_streams, Error, Event};
 
 extern crate alloc;
+use alloc::string::String;
 
 use erc20::{ERC20Error, IERC20};
 
-mod deployable;
-use deployable::ERC20;
+deployable!(erc20::ERC20);
+
+#[derive(Event)]
+pub struct CrossMint {
+    #[indexed]
+    pub token: Address,
+    pub done: bool,
+}
+
+#[derive(Error)]
+pub enum ERC20xError {
+    ZeroToken,
+    #[from]
+    ERC20(ERC20Error),
+}
 
 #[derive(Default, )]
 pub struct ERC20x;
@@ -26,18 +40,45 @@ impl ERC20x {
         (token.address(), owner)
     }
 
+    // Fails to deploy a new ERC20 token instance, as its constructor takes an address
+    pub fn x_deploy_mismatched(&mut self, supply: U256) -> Address {
+        ERC20::deploy(supply).with_ctx(self).address()
+    }
+
     // Performs a staticcall to an ERC20
     pub fn x_balance_of(&self, owner: Address, token_addr: Address) -> Option<U256> {
         let token = IERC20::new(token_addr).with_ctx(self);         // IERC20<ReadOnly>
         token.balance_of(owner)
     }
 
+    // Performs a staticcall to an ERC20, decoding its dynamic (string) output
+    pub fn x_name(&self, token_addr: Address) -> String {
+        let token = IERC20::new(token_addr).with_ctx(self);         // IERC20<ReadOnly>
+        token.name().expect("Unable to get name")
+    }
+
     // Performs a (mutable) call to an ERC20
     pub fn x_mint(&mut self, to: Address, amount: U256, token_addr: Address) -> Result<bool, ERC20Error> {
         let mut token = IERC20::new(token_addr).with_ctx(self);     // IERC20<ReadWrite>
         token.mint(to, amount)
     }
 
+    // Performs a (mutable) call to an ERC20, and propagates its errors wrapped into `ERC20xError`
+    pub fn x_mint_wrapped(&mut self, to: Address, amount: U256, token_addr: Address) -> Result<bool, ERC20xError> {
+        if token_addr == Address::ZERO { return Err(ERC20xError::ZeroToken) };
+
+        let mut token = IERC20::new(token_addr).with_ctx(self);     // IERC20<ReadWrite>
+        Ok(token.mint(to, amount)?)
+    }
+
+    // Emits before and after minting through an ERC20, which emits its own `Transfer` in between
+    pub fn x_mint_logged(&mut self, to: Address, amount: U256, token_addr: Address) -> bool {
+        log::emit(CrossMint::new(token_addr, false));
+        let minted = IERC20::new(token_addr).with_ctx(self).mint(to, amount).is_ok();
+        log::emit(CrossMint::new(token_addr, true));
+        minted
+    }
+
     // Fails to perform a (mutable) call to an ERC20, due to the lack of mutability in the ERC20x method
     // pub fn x_mint_fails(&self, to: Address, token_addr: Address) -> Result<bool, ERC20Error> {
     //     let mut token = IERC20::new(token_addr).with_ctx(self);  // IERC20<ReadOnly>
@@ -62,6 +103,29 @@ impl ERC20x {
         }
     }
 
+    // Performs a low-level call to an ERC20, and returns the raw revert data (if any)
+    pub fn x_mint_revert_data(&mut self, to: Address, amount: U256, token_addr: Address) -> Bytes {
+        let mut calldata = keccak256("mint(address,uint256)")[..4].to_vec();
+        calldata.extend_from_slice(&(to, amount).abi_encode());
+
+        // reverted calls keep their output in the return data buffer
+        match call_contract(token_addr, 0, &calldata, None) {
+            Ok(_) => Bytes::new(),
+            Err(revert_data) => revert_data,
+        }
+    }
+
+    // Reads the raw output of `balance_of(owner)`, and then mints in a sibling call, whose output
+    // replaces the return data buffer. Returns the first output, along with whether the mint succeeded
+    pub fn x_balance_then_mint(&mut self, owner: Address, amount: U256, token_addr: Address) -> (Bytes, bool) {
+        let mut calldata = keccak256("balance_of(address)")[..4].to_vec();
+        calldata.extend_from_slice(&owner.abi_encode());
+        let balance = staticcall_contract(token_addr, 0, &calldata, None).expect("Unable to get balance");
+
+        let mut token = IERC20::new(token_addr).with_ctx(self);     // IERC20<ReadWrite>
+        (balance, token.mint(owner, amount).is_ok())
+    }
+
     // Always reverts with a str msg
     pub fn panics(&self) { panic!("This function always panics"); }
 
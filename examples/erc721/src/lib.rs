@@ -78,6 +78,9 @@ pub struct ERC721 {
 impl ERC721 {
     // -- CONSTRUCTOR ----------------------------------------------------------
     pub fn new(owner: Address) -> Self {
+        // Perform sanity checks
+        if owner == Address::ZERO { panic!("Owner can't be the zero address") };
+
         // Init the contract
         let mut erc721 = ERC721::default();
 
@@ -169,6 +172,8 @@ impl ERC721 {
         // Perform safety check 
         let from = msg_sender();
         if from != self.owner.read() { return Err(ERC721Error::OnlyOwner) }; 
+        // Renouncing must be intentional, see `renounce_ownership`
+        if new_owner == Address::ZERO { return Err(ERC721Error::ZeroAddress) };
 
         // Update state
         self.owner.write(new_owner);
@@ -178,6 +183,20 @@ impl ERC721 {
         Ok(true)
     }
 
+    // Leaves the collection without an owner, so that no more tokens can be minted
+    pub fn renounce_ownership(&mut self) -> Result<bool, ERC721Error> {
+        // Perform safety check
+        let from = msg_sender();
+        if from != self.owner.read() { return Err(ERC721Error::OnlyOwner) };
+
+        // Update state
+        self.owner.write(Address::ZERO);
+
+        // Emit event + return
+        log::emit(OwnershipTransferred::new(from, Address::ZERO));
+        Ok(true)
+    }
+
     // -- READ-ONLY FUNCTIONS --------------------------------------------------
     pub fn owner(&self) -> Address {
         self.owner.read()
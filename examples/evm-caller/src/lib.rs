@@ -3,8 +3,8 @@
 
 use core::default::Default;
 
-use alloy_core::primitives::{address, Bytes, Address, U256};
-use contract_derive::{contract, interface};
+use alloy_core::primitives::{address, keccak256, Bytes, Address, U256};
+use contract_derive::{contract, interface, payable};
 
 extern crate alloc;
 use alloc::{string::String, vec::Vec};
@@ -27,4 +27,95 @@ impl EVMCaller {
     pub fn x_get(&self, target: Address) -> U256 {
         ISimpleStorage::new(target).with_ctx(self).get().expect("Unable to get value")
     }
+
+    // Reads `get()`, defaulting to zero when the output can't be decoded (e.g. `target` has no code)
+    pub fn x_get_or_default(&self, target: Address) -> U256 {
+        ISimpleStorage::new(target).with_ctx(self).get().unwrap_or_default()
+    }
+
+    // Reads `get()`, returning whether the staticcall succeeded and whether its output was decoded
+    pub fn x_try_get(&self, target: Address) -> (bool, bool) {
+        match ISimpleStorage::new(target).with_ctx(self).try_get() {
+            Ok(value) => (true, value.is_some()),
+            Err(_) => (false, false),
+        }
+    }
+
+    // Reads `get()` twice with the same staticcall, like an oracle read multiple times per tx
+    pub fn x_get_twice(&self, target: Address) -> (U256, U256) {
+        let storage = ISimpleStorage::new(target).with_ctx(self);
+        (
+            storage.get().expect("Unable to get value"),
+            storage.get().expect("Unable to get value"),
+        )
+    }
+
+    // Reads `get()` before and after setting `value`
+    pub fn x_get_set_get(&mut self, target: Address, value: U256) -> (U256, U256) {
+        let before = self.x_get(target);
+        self.x_set(target, value);
+        (before, self.x_get(target))
+    }
+
+    // Forwards the (already ABI-encoded) output of `get()` verbatim
+    pub fn x_get_raw(&self, target: Address) -> Bytes {
+        let selector = &keccak256("get()")[..4];
+        staticcall_contract(target, 0, selector, None).expect("Unable to get value")
+    }
+
+    // Forwards the received value to `target`, returning whether the call succeeded
+    #[payable]
+    pub fn x_send(&mut self, target: Address, data: Bytes) -> bool {
+        call_contract(target, msg_value().to::<u64>(), &data, None).is_ok()
+    }
+
+    // Calls `target` with `data`, reverting with the revert data of the call if it fails
+    pub fn x_call_or_bubble(&mut self, target: Address, data: Bytes) -> Bytes {
+        match call_contract(target, 0, &data, None) {
+            Ok(output) => output,
+            Err(revert_data) => revert_with_error(&revert_data),
+        }
+    }
+
+    // Forwards `amount` out of the received value to `target`, returning the value received by this
+    // frame along with the output of the call
+    #[payable]
+    pub fn x_split_value(&mut self, target: Address, amount: U256, data: Bytes) -> (U256, Bytes) {
+        let received = msg_value();
+        match call_contract(target, amount.to::<u64>(), &data, None) {
+            Ok(output) => (received, output),
+            Err(revert_data) => revert_with_error(&revert_data),
+        }
+    }
+
+    // Sends the received value to `target` with the 2300 gas stipend, like Solidity's `send`
+    #[payable]
+    pub fn x_send_eth(&mut self, target: Address) -> bool {
+        send_eth(target, msg_value().to::<u64>())
+    }
+
+    // Staticcalls `target` with `data`, returning whether the call succeeded
+    pub fn x_static_send(&self, target: Address, data: Bytes) -> bool {
+        staticcall(target, 0, data.as_ptr() as u64, data.len() as u64)
+    }
+
+    // Reads `get()`, then again forwarding at most `gas`, returning whether each staticcall succeeded
+    pub fn x_get_then_capped(&self, target: Address, gas: u64) -> (bool, bool) {
+        let selector = &keccak256("get()")[..4];
+        let data = selector.as_ptr() as u64;
+        (staticcall(target, 0, data, 4), staticcall_with_gas(target, 0, data, 4, gas))
+    }
+
+    // Calls `set(value)` on `target` with at most `gas`, returning whether the call succeeded
+    pub fn x_set_with_gas(&mut self, target: Address, value: U256, gas: u64) -> bool {
+        let mut data = keccak256("set(uint256)")[..4].to_vec();
+        data.extend_from_slice(&value.to_be_bytes::<32>());
+        call_with_gas(target, 0, data.as_ptr() as u64, data.len() as u64, gas)
+    }
+
+    // Calls `target` claiming `size` bytes of calldata, returning whether the call succeeded
+    pub fn x_call_with_size(&mut self, target: Address, size: u64) -> bool {
+        let data = [0u8; 4];
+        call(target, 0, data.as_ptr() as u64, size)
+    }
 }
@@ -0,0 +1,49 @@
+#![no_std]
+#![no_main]
+
+use core::default::Default;
+
+use alloy_core::primitives::{
+    aliases::{B32, B8},
+    B256,
+};
+use contract_derive::{contract, storage};
+use eth_riscv_runtime::types::*;
+
+extern crate alloc;
+
+#[storage]
+pub struct FixedBytesStore {
+    hash: Slot<B256>,
+    selector: Slot<B32>,
+    tag: Slot<B8>,
+}
+
+#[contract]
+impl FixedBytesStore {
+    // -- STATE MODIFYING FUNCTIONS --------------------------------------------
+    pub fn store_hash(&mut self, h: B256) {
+        self.hash.write(h);
+    }
+
+    pub fn store_selector(&mut self, s: B32) {
+        self.selector.write(s);
+    }
+
+    pub fn store_tag(&mut self, t: B8) {
+        self.tag.write(t);
+    }
+
+    // -- READ-ONLY FUNCTIONS --------------------------------------------------
+    pub fn hash(&self) -> B256 {
+        self.hash.read()
+    }
+
+    pub fn selector(&self) -> B32 {
+        self.selector.read()
+    }
+
+    pub fn tag(&self) -> B8 {
+        self.tag.read()
+    }
+}
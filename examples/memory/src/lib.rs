@@ -0,0 +1,47 @@
+#![no_std]
+#![no_main]
+
+use core::default::Default;
+
+use alloc::vec;
+use alloy_core::primitives::{Address, U256};
+use contract_derive::contract;
+use eth_riscv_runtime::{call, log, sstore};
+
+extern crate alloc;
+
+/// Number of bytes written by `touch`
+const WRITES: u64 = 64;
+
+#[derive(Default)]
+pub struct Memory;
+
+#[contract]
+impl Memory {
+    // Writes the same number of bytes for any `stride`, spread over `WRITES * stride` bytes
+    // of memory, so that the executed instructions don't depend on the memory usage
+    pub fn touch(&self, stride: u64) -> u64 {
+        let mut buffer = vec![0u8; (WRITES * stride) as usize];
+        for i in 0..WRITES {
+            buffer[(i * stride) as usize] = 1;
+        }
+        (0..WRITES).map(|i| buffer[(i * stride) as usize] as u64).sum()
+    }
+
+    // Emits a log (without topics) of the raw memory range `[data_ptr, data_ptr + data_size)`
+    pub fn log_at(&self, data_ptr: u64, data_size: u64) -> bool {
+        log::log(data_ptr, data_size, 0, 0);
+        true
+    }
+
+    // Writes `value` at `key` through the raw syscall, regardless of the `&self` receiver
+    pub fn store_at(&self, key: U256, value: U256) -> bool {
+        sstore(key, value);
+        true
+    }
+
+    // Sends 1 wei to `target`, returning whether the call succeeded
+    pub fn send_wei(&self, target: Address) -> bool {
+        call::call(target, 1, 0, 0)
+    }
+}
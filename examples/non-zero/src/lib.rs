@@ -0,0 +1,50 @@
+#![no_std]
+#![no_main]
+
+use core::default::Default;
+
+use contract_derive::{contract, non_zero, storage, Error};
+use eth_riscv_runtime::types::*;
+
+use alloy_core::primitives::{Address, U256};
+
+extern crate alloc;
+
+// -- ERRORS -------------------------------------------------------------------
+#[derive(Error)]
+pub enum RegistryError {
+    ZeroAddress,
+    NotOwner,
+}
+
+// -- CONTRACT -----------------------------------------------------------------
+// Registry of ids, whose address params are checked by `#[non_zero]` rather than by hand
+#[storage]
+pub struct Registry {
+    owner_of: Mapping<U256, Slot<Address>>,
+}
+
+#[contract]
+impl Registry {
+    // -- STATE MODIFYING FUNCTIONS --------------------------------------------
+    // Reverts with `RegistryError::ZeroAddress` for the zero address
+    #[non_zero(to, error = RegistryError::ZeroAddress)]
+    pub fn assign(&mut self, id: U256, to: Address) -> bool {
+        self.owner_of[id].write(to);
+        true
+    }
+
+    // Reverts with the default `Zero address: <param>` message for the zero address
+    #[non_zero(from, to)]
+    pub fn reassign(&mut self, id: U256, from: Address, to: Address) -> Result<bool, RegistryError> {
+        if self.owner_of[id].read() != from { return Err(RegistryError::NotOwner) };
+
+        self.owner_of[id].write(to);
+        Ok(true)
+    }
+
+    // -- READ-ONLY FUNCTIONS --------------------------------------------------
+    pub fn owner_of(&self, id: U256) -> Address {
+        self.owner_of[id].read()
+    }
+}
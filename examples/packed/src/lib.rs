@@ -0,0 +1,139 @@
+#![no_std]
+#![no_main]
+
+use core::default::Default;
+
+use contract_derive::{contract, storage};
+use eth_riscv_runtime::types::*;
+
+use alloy_core::primitives::{keccak256, Address, U256};
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+#[storage]
+pub struct Packed {
+    // Both members share a single slot
+    pair: Slot<(u64, u64)>,
+    // Each element takes its own slot, from the base slot onwards
+    words: Slot<[U256; 4]>,
+    // Allocated right after the last element of `words`
+    last: Slot<U256>,
+    // Logs the preimage of its slots once written, as the runtime is built with `log-slot-preimages`
+    balances: Mapping<Address, Slot<U256>>,
+    // Logs its old and new values on every write
+    observed: ObservableSlot<U256>,
+    // Allocates sequential ids, starting at zero
+    next_id: Counter,
+    // Unordered set, whose removals move the last member into the freed slot
+    members: StorageVec<Address>,
+}
+
+// Base slot of the `r55.packed.namespaced` ERC-7201 namespace:
+// `keccak256(abi.encode(uint256(keccak256(id)) - 1)) & ~bytes32(uint256(0xff))`
+fn namespaced_slot() -> Slot<U256> {
+    let id = U256::from_be_bytes(keccak256("r55.packed.namespaced").0) - U256::from(1);
+    let slot = U256::from_be_bytes(keccak256(id.to_be_bytes::<32>()).0) & !U256::from(0xff);
+    Slot::allocate_u256(slot)
+}
+
+// Moves a value between a direct slot and a mapping entry. Both bounds resolve to
+// `StorageStorable<Value = U256>`, so the unified trait backs either kind of storage
+fn move_value<S, G>(from: &mut S, to: &mut G)
+where
+    S: DirectStorage<U256>,
+    G: IndirectStorage<Slot<U256>>,
+{
+    let value = from.read();
+    from.write(U256::ZERO);
+    to.write(value);
+}
+
+#[contract]
+impl Packed {
+    // -- STATE MODIFYING FUNCTIONS --------------------------------------------
+    pub fn set_pair(&mut self, first: u64, second: u64) {
+        self.pair.write((first, second));
+    }
+
+    pub fn set_words(&mut self, words: [U256; 4], last: U256) {
+        self.words.write(words);
+        self.last.write(last);
+    }
+
+    pub fn set_balance(&mut self, owner: Address, amount: U256) {
+        self.balances[owner].write(amount);
+    }
+
+    pub fn add_balance(&mut self, owner: Address, amount: U256) {
+        self.balances[owner].modify(|balance| balance + amount);
+    }
+
+    // Moves `last` into the balance of `owner`, leaving `last` empty
+    pub fn move_last(&mut self, owner: Address) {
+        move_value(&mut self.last, &mut self.balances[owner]);
+    }
+
+    pub fn set_observed(&mut self, value: U256) {
+        self.observed.write(value);
+    }
+
+    pub fn set_namespaced(&mut self, value: U256) {
+        namespaced_slot().write(value);
+    }
+
+    pub fn allocate_id(&mut self) -> U256 {
+        self.next_id.increment()
+    }
+
+    pub fn add_member(&mut self, member: Address) {
+        self.members.push(member);
+    }
+
+    pub fn remove_member(&mut self, index: u64) -> Address {
+        self.members.swap_remove(index)
+    }
+
+    // -- READ-ONLY FUNCTIONS --------------------------------------------------
+    pub fn pair(&self) -> (u64, u64) {
+        self.pair.read()
+    }
+
+    pub fn first(&self) -> u64 {
+        self.pair.read().0
+    }
+
+    pub fn second(&self) -> u64 {
+        self.pair.read().1
+    }
+
+    pub fn words(&self) -> [U256; 4] {
+        self.words.read()
+    }
+
+    pub fn last(&self) -> U256 {
+        self.last.read()
+    }
+
+    pub fn balance_of(&self, owner: Address) -> U256 {
+        self.balances[owner].read()
+    }
+
+    pub fn observed(&self) -> U256 {
+        self.observed.read()
+    }
+
+    pub fn namespaced(&self) -> U256 {
+        namespaced_slot().read()
+    }
+
+    pub fn next_id(&self) -> U256 {
+        self.next_id.current()
+    }
+
+    pub fn members(&self) -> Vec<Address> {
+        (0..self.members.len())
+            .filter_map(|index| self.members.get(index))
+            .collect()
+    }
+}
@@ -0,0 +1,56 @@
+#![no_std]
+#![no_main]
+
+use core::default::Default;
+
+use contract_derive::{contract, payable, storage};
+use eth_riscv_runtime::types::*;
+
+use alloy_core::primitives::{Address, U256};
+
+extern crate alloc;
+
+// -- CONTRACT -----------------------------------------------------------------
+// Token whose `mint` can be paid for, accounting the ETH received per payer
+#[storage]
+pub struct PayableToken {
+    balance_of: Mapping<Address, Slot<U256>>,
+    // ETH paid along `mint`, credited to the payer
+    deposit_of: Mapping<Address, Slot<U256>>,
+}
+
+#[contract]
+impl PayableToken {
+    // -- STATE MODIFYING FUNCTIONS --------------------------------------------
+    #[payable]
+    pub fn mint(&mut self, to: Address, amount: U256) -> bool {
+        // Credit the ETH paid along the mint, which stays in the contract
+        let value = msg_value();
+        if value > U256::ZERO {
+            let deposit = self.deposit_of[msg_sender()].read();
+            self.deposit_of[msg_sender()].write(deposit + value);
+        }
+
+        let to_balance = self.balance_of[to].read();
+        self.balance_of[to].write(to_balance + amount);
+        true
+    }
+
+    pub fn burn(&mut self, amount: U256) -> bool {
+        let from = msg_sender();
+        let from_balance = self.balance_of[from].read();
+        if from_balance < amount { return false };
+
+        self.balance_of[from].write(from_balance - amount);
+        true
+    }
+
+    // -- READ-ONLY FUNCTIONS --------------------------------------------------
+    pub fn balance_of(&self, owner: Address) -> U256 {
+        self.balance_of[owner].read()
+    }
+
+    pub fn deposit_of(&self, payer: Address) -> U256 {
+        self.deposit_of[payer].read()
+    }
+}
@@ -0,0 +1,23 @@
+#![no_std]
+#![no_main]
+
+use core::default::Default;
+
+use alloy_core::primitives::{Address, Bytes};
+use contract_derive::contract;
+
+extern crate alloc;
+
+#[derive(Default)]
+pub struct Proxy;
+
+#[contract]
+impl Proxy {
+    // Runs the logic contract's code in the context of the proxy, and returns its raw output
+    pub fn delegate(&mut self, logic: Address, data: Bytes) -> Bytes {
+        match delegatecall_contract(logic, &data, None) {
+            Ok(output) => output,
+            Err(revert_data) => revert_with_error(&revert_data),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+#![no_std]
+#![no_main]
+
+use core::default::Default;
+
+use contract_derive::{contract, storage, Error};
+use eth_riscv_runtime::types::*;
+
+use alloy_core::primitives::{Address, U256};
+
+extern crate alloc;
+
+// -- ERRORS -------------------------------------------------------------------
+#[derive(Error)]
+pub enum RelayerError {
+    ZeroValue,
+}
+
+// -- CONTRACT -----------------------------------------------------------------
+// Registry that its owner can relay calls to, through the `execute` entry of `#[contract(execute)]`
+#[storage]
+pub struct Relayer {
+    owner: Slot<Address>,
+    value_of: Mapping<Address, Slot<U256>>,
+}
+
+#[contract(execute)]
+impl Relayer {
+    // -- CONSTRUCTOR ----------------------------------------------------------
+    pub fn new(owner: Address) -> Self {
+        let mut relayer = Relayer::default();
+        relayer.owner.write(owner);
+        relayer
+    }
+
+    // -- STATE MODIFYING FUNCTIONS --------------------------------------------
+    // Stores `value` for the caller, which is the relayer itself for relayed calls
+    pub fn set(&mut self, value: U256) -> Result<bool, RelayerError> {
+        if value == U256::ZERO { return Err(RelayerError::ZeroValue) };
+
+        self.value_of[msg_sender()].write(value);
+        Ok(true)
+    }
+
+    // -- READ-ONLY FUNCTIONS --------------------------------------------------
+    pub fn value_of(&self, account: Address) -> U256 {
+        self.value_of[account].read()
+    }
+
+    // Only the owner can relay calls through `execute`
+    pub fn is_trusted_relayer(&self, relayer: Address) -> bool {
+        relayer == self.owner.read()
+    }
+}
@@ -0,0 +1,85 @@
+#![no_std]
+#![no_main]
+
+use core::default::Default;
+
+use contract_derive::{contract, storage, Error};
+use eth_riscv_runtime::types::*;
+
+use alloy_core::primitives::{Address, U256};
+
+extern crate alloc;
+
+// -- ERRORS -------------------------------------------------------------------
+#[derive(Error)]
+pub enum SelfCallerError {
+    OnlyOwner,
+}
+
+// -- CONTRACT -----------------------------------------------------------------
+// Test contract that calls its own external methods through its generated interface
+#[storage]
+pub struct SelfCaller {
+    total_supply: Slot<U256>,
+    owner: Slot<Address>,
+}
+
+#[contract]
+impl SelfCaller {
+    // -- CONSTRUCTOR ----------------------------------------------------------
+    pub fn new(owner: Address) -> Self {
+        let mut caller = SelfCaller::default();
+        caller.owner.write(owner);
+        caller
+    }
+
+    // -- STATE MODIFYING FUNCTIONS --------------------------------------------
+    pub fn mint(&mut self, amount: U256) -> Result<bool, SelfCallerError> {
+        if msg_sender() != self.owner.read() { return Err(SelfCallerError::OnlyOwner) };
+
+        self.total_supply += amount;
+        Ok(true)
+    }
+
+    // Follows the (EVM) ERC20Burnable convention of not returning any value
+    pub fn burn(&mut self, amount: U256) {
+        self.total_supply -= amount;
+    }
+
+    pub fn set_owner(&mut self, owner: Address) -> Result<bool, SelfCallerError> {
+        if msg_sender() != self.owner.read() { return Err(SelfCallerError::OnlyOwner) };
+
+        self.owner.write(owner);
+        Ok(true)
+    }
+
+    // -- READ-ONLY FUNCTIONS --------------------------------------------------
+    pub fn total_supply(&self) -> U256 {
+        self.total_supply.read()
+    }
+
+    // -- SELF-CALLS -----------------------------------------------------------
+    // Reads the total supply through the contract's own interface, in a new call frame
+    pub fn self_total_supply(&self) -> U256 {
+        let this = ISelfCaller::new(contract_address()).with_ctx(self);   // ISelfCaller<ReadOnly>
+        this.total_supply().expect("Unable to get total supply")
+    }
+
+    // Mints through the contract's own interface, so the contract itself is the `msg_sender`
+    pub fn self_mint(&mut self, amount: U256) -> Result<bool, SelfCallerError> {
+        let mut this = ISelfCaller::new(contract_address()).with_ctx(self); // ISelfCaller<ReadWrite>
+        this.mint(amount)
+    }
+
+    // Burns through the contract's own interface: a (mutable) call to a method without return data
+    pub fn self_burn(&mut self, amount: U256) -> bool {
+        let mut this = ISelfCaller::new(contract_address()).with_ctx(self); // ISelfCaller<ReadWrite>
+        this.burn(amount).is_some()
+    }
+
+    // Reads the total supply of another instance (or of itself), through `Self::at`
+    pub fn total_supply_at(&self, other: Address) -> U256 {
+        let other = Self::at(other).with_ctx(self);                        // ISelfCaller<ReadOnly>
+        other.total_supply().expect("Unable to get total supply")
+    }
+}
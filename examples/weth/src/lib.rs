@@ -0,0 +1,71 @@
+#![no_std]
+#![no_main]
+
+use core::default::Default;
+
+use contract_derive::{contract, payable, storage, Event};
+use eth_riscv_runtime::types::*;
+
+use alloy_core::primitives::{Address, U256};
+
+extern crate alloc;
+
+// -- EVENTS -------------------------------------------------------------------
+#[derive(Event)]
+pub struct Deposit {
+    #[indexed]
+    pub to: Address,
+    pub amount: U256,
+}
+
+// -- CONTRACT -----------------------------------------------------------------
+#[storage]
+pub struct WETH {
+    total_supply: Slot<U256>,
+    balance_of: Mapping<Address, Slot<U256>>,
+}
+
+#[contract]
+impl WETH {
+    // -- RECEIVE --------------------------------------------------------------
+    #[payable]
+    pub fn receive(&mut self) {
+        self.deposit();
+    }
+
+    // -- STATE MODIFYING FUNCTIONS --------------------------------------------
+    #[payable]
+    pub fn deposit(&mut self) {
+        let (to, amount) = (msg_sender(), msg_value());
+
+        // Credit the sender with the received value
+        let to_balance = self.balance_of[to].read();
+        self.balance_of[to].write(to_balance + amount);
+        self.total_supply += amount;
+
+        // Emit event
+        log::emit(Deposit::new(to, amount));
+    }
+
+    #[payable]
+    pub fn deposit_to(&mut self, to: Address) {
+        let amount = msg_value();
+
+        // Credit the recipient with the received value
+        let to_balance = self.balance_of[to].read();
+        self.balance_of[to].write(to_balance + amount);
+        self.total_supply += amount;
+
+        // Emit event
+        emit!(Deposit, to, amount);
+    }
+
+    // -- READ-ONLY FUNCTIONS --------------------------------------------------
+    pub fn total_supply(&self) -> U256 {
+        self.total_supply.read()
+    }
+
+    pub fn balance_of(&self, owner: Address) -> U256 {
+        self.balance_of[owner].read()
+    }
+}
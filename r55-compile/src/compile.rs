@@ -10,6 +10,7 @@ use syn::{Attribute, Item, ItemImpl};
 use thiserror::Error;
 use toml::Value;
 use tracing::{debug, error, info, warn};
+use crate::size::CodeSize;
 
 #[derive(Debug, Error)]
 pub enum ContractError {
@@ -241,16 +242,19 @@ impl Contract {
         None
     }
 
-    pub fn compile_r55(&self) -> eyre::Result<Vec<u8>> {
+    /// Compiles the contract, returning its deploy bytecode along with the size of the runtime code
+    /// it deploys.
+    pub fn compile_r55(&self) -> eyre::Result<(Vec<u8>, CodeSize)> {
         // First compile runtime
-        self.compile_runtime()?;
+        let runtime = self.compile_runtime()?;
+        let code_size = CodeSize::from_runtime(&self.name.package, &runtime);
 
         // Then compile deployment code
         let bytecode = self.compile_deploy()?;
         let mut prefixed_bytecode = vec![0xff]; // Add the 0xff prefix
         prefixed_bytecode.extend_from_slice(&bytecode);
 
-        Ok(prefixed_bytecode)
+        Ok((prefixed_bytecode, code_size))
     }
 
     fn compile_runtime(&self) -> eyre::Result<Vec<u8>> {
@@ -0,0 +1,270 @@
+//! Storage layout of R55 contracts, and compatibility checks between two builds of a contract.
+
+use quote::ToTokens;
+use r55_layout::storage_slots;
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, path::Path};
+use syn::{Attribute, Fields, Item};
+
+/// Field of a `#[storage]` struct, and the slot allocated to it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageField {
+    pub name: String,
+    pub slot: u64,
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// Storage layout of a contract, exported as `<package>.layout.json` next to its bytecode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageLayout {
+    pub contract: String,
+    pub storage: Vec<StorageField>,
+}
+
+impl StorageLayout {
+    pub fn from_file(file_path: &Path) -> eyre::Result<Self> {
+        let content = fs::read_to_string(file_path)?;
+        Self::from_source(&content)
+    }
+
+    /// Extracts the layout of the `#[storage]` struct of a contract.
+    ///
+    /// Mirrors the `storage` macro, which allocates sequential slots to the fields. Static arrays
+    /// span as many slots as they need, and the following field starts right after them.
+    pub fn from_source(content: &str) -> eyre::Result<Self> {
+        let file = syn::parse_file(content)?;
+
+        for item in file.items {
+            if let Item::Struct(item_struct) = item {
+                if !has_storage_attribute(&item_struct.attrs) {
+                    continue;
+                }
+
+                let mut next_slot = 0;
+                let storage = match item_struct.fields {
+                    Fields::Named(fields) => fields
+                        .named
+                        .iter()
+                        .map(|field| {
+                            let slot = next_slot;
+                            next_slot += storage_slots(&field.ty)?;
+                            Ok(StorageField {
+                                name: field.ident.as_ref().unwrap().to_string(),
+                                slot,
+                                ty: field.ty.to_token_stream().to_string().replace(' ', ""),
+                            })
+                        })
+                        .collect::<eyre::Result<_>>()?,
+                    _ => Vec::new(),
+                };
+
+                return Ok(Self {
+                    contract: item_struct.ident.to_string(),
+                    storage,
+                });
+            }
+        }
+
+        eyre::bail!("No storage struct found")
+    }
+
+    pub fn from_json(json: &str) -> eyre::Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> eyre::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Change between two storage layouts that would corrupt the state of an upgraded contract
+#[derive(Debug, Clone, PartialEq)]
+pub enum Incompatibility {
+    /// The field was allocated a different slot
+    Moved {
+        name: String,
+        old_slot: u64,
+        new_slot: u64,
+    },
+    /// The slot now holds a value of a different type
+    Retyped {
+        slot: u64,
+        old_ty: String,
+        new_ty: String,
+    },
+    /// The field no longer exists
+    Removed { name: String, slot: u64 },
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Moved {
+                name,
+                old_slot,
+                new_slot,
+            } => write!(f, "`{}` moved from slot {} to {}", name, old_slot, new_slot),
+            Self::Retyped {
+                slot,
+                old_ty,
+                new_ty,
+            } => write!(
+                f,
+                "slot {} changed type from `{}` to `{}`",
+                slot, old_ty, new_ty
+            ),
+            Self::Removed { name, slot } => write!(f, "`{}` removed from slot {}", name, slot),
+        }
+    }
+}
+
+/// Compares the layout of a new build of a contract against the old one.
+///
+/// New fields can only be appended: every field of `old` must keep its slot and type in `new`.
+pub fn check_compatibility(old: &StorageLayout, new: &StorageLayout) -> Vec<Incompatibility> {
+    let mut incompatibilities = Vec::new();
+
+    for old_field in &old.storage {
+        match new.storage.iter().find(|f| f.name == old_field.name) {
+            Some(new_field) if new_field.slot != old_field.slot => {
+                incompatibilities.push(Incompatibility::Moved {
+                    name: old_field.name.clone(),
+                    old_slot: old_field.slot,
+                    new_slot: new_field.slot,
+                })
+            }
+            Some(_) => {}
+            None => incompatibilities.push(Incompatibility::Removed {
+                name: old_field.name.clone(),
+                slot: old_field.slot,
+            }),
+        }
+
+        // Renaming a field is fine, as long as its slot keeps the same type
+        if let Some(new_field) = new.storage.iter().find(|f| f.slot == old_field.slot) {
+            if new_field.ty != old_field.ty {
+                incompatibilities.push(Incompatibility::Retyped {
+                    slot: old_field.slot,
+                    old_ty: old_field.ty.clone(),
+                    new_ty: new_field.ty.clone(),
+                });
+            }
+        }
+    }
+
+    incompatibilities
+}
+
+// Check if attributes contain #[storage]
+fn has_storage_attribute(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .any(|attr| attr.path.segments.len() == 1 && attr.path.segments[0].ident == "storage")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1: &str = r#"
+        #[storage]
+        pub struct Token {
+            balances: Mapping<Address, Slot<U256>>,
+            total_supply: Slot<U256>,
+            owner: Slot<Address>,
+        }
+    "#;
+
+    fn layout(source: &str) -> StorageLayout {
+        // Round-trip through JSON, like layouts exported by two different builds
+        let json = StorageLayout::from_source(source)
+            .unwrap()
+            .to_json()
+            .unwrap();
+        StorageLayout::from_json(&json).unwrap()
+    }
+
+    #[test]
+    fn test_storage_layout() {
+        let layout = layout(V1);
+        assert_eq!(layout.contract, "Token");
+        assert_eq!(
+            layout.storage[0],
+            StorageField {
+                name: "balances".to_string(),
+                slot: 0,
+                ty: "Mapping<Address,Slot<U256>>".to_string(),
+            }
+        );
+        assert_eq!(layout.storage[2].slot, 2);
+    }
+
+    #[test]
+    fn test_storage_layout_arrays() {
+        // Arrays take as many slots as their elements need, and shift the following fields
+        let layout = layout(
+            r#"
+            #[storage]
+            pub struct Packed {
+                pair: Slot<(u64, u64)>,
+                words: Slot<[U256; 4]>,
+                ids: Slot<[u64; 5]>,
+                last: Slot<U256>,
+            }
+        "#,
+        );
+        let slots: Vec<_> = layout.storage.iter().map(|f| f.slot).collect();
+        assert_eq!(slots, vec![0, 1, 5, 7]);
+
+        // Arrays of unknown length aren't exported with made-up slots
+        let source = "#[storage] pub struct Packed { words: Slot<[U256; LEN]>, last: Slot<U256> }";
+        assert!(StorageLayout::from_source(source).is_err());
+    }
+
+    #[test]
+    fn test_compatible_layouts() {
+        // Appending a field keeps the existing slots untouched
+        let v2 = V1.replace(
+            "owner: Slot<Address>,",
+            "owner: Slot<Address>, paused: Slot<bool>,",
+        );
+        assert!(check_compatibility(&layout(V1), &layout(&v2)).is_empty());
+    }
+
+    #[test]
+    fn test_retyped_field() {
+        let v2 = V1.replace("total_supply: Slot<U256>", "total_supply: Slot<u64>");
+        assert_eq!(
+            check_compatibility(&layout(V1), &layout(&v2)),
+            vec![Incompatibility::Retyped {
+                slot: 1,
+                old_ty: "Slot<U256>".to_string(),
+                new_ty: "Slot<u64>".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_moved_field() {
+        let v2 = r#"
+            #[storage]
+            pub struct Token {
+                total_supply: Slot<U256>,
+                owner: Slot<Address>,
+                balances: Mapping<Address, Slot<U256>>,
+            }
+        "#;
+        let incompatibilities = check_compatibility(&layout(V1), &layout(v2));
+        assert!(incompatibilities.contains(&Incompatibility::Moved {
+            name: "balances".to_string(),
+            old_slot: 0,
+            new_slot: 2,
+        }));
+        assert!(incompatibilities.contains(&Incompatibility::Retyped {
+            slot: 0,
+            old_ty: "Mapping<Address,Slot<U256>>".to_string(),
+            new_ty: "Slot<U256>".to_string(),
+        }));
+    }
+}
@@ -0,0 +1,6 @@
+//! Compilation of R55 contracts, shared by the `r55-compile` binary and the `r55` test utils.
+
+pub mod compile;
+pub mod config;
+pub mod layout;
+pub mod size;
@@ -1,14 +1,11 @@
-mod compile;
-use compile::{find_r55_contracts_in_dirs, sort_r55_contracts};
-
-mod config;
-use config::R55Config;
-
-mod deployable;
-use deployable::generate_deployable;
+use r55_compile::{
+    compile::{find_r55_contracts_in_dirs, sort_r55_contracts},
+    config::R55Config,
+    layout::{check_compatibility, StorageLayout},
+};
 
 use std::{fs, path::Path};
-use tracing::info;
+use tracing::{debug, error, info, warn};
 
 fn main() -> eyre::Result<()> {
     // Initialize logging
@@ -18,6 +15,14 @@ fn main() -> eyre::Result<()> {
         .finish();
     tracing::subscriber::set_global_default(tracing_sub)?;
 
+    // `r55-compile check-layout <old.json> <new.json>` compares two exported storage layouts
+    let args: Vec<String> = std::env::args().collect();
+    if let [_, cmd, old, new] = args.as_slice() {
+        if cmd == "check-layout" {
+            return check_layout(Path::new(old), Path::new(new));
+        }
+    }
+
     // Load configuration
     let config = R55Config::load()?;
     
@@ -55,13 +60,6 @@ fn main() -> eyre::Result<()> {
     info!("Searching for contracts in: {:?}", search_dirs);
     let contracts = find_r55_contracts_in_dirs(&search_dirs);
 
-    // Generate deployable files for the dependencies
-    if let Some(contracts_with_deps) = contracts.get(&false) {
-        for c in contracts_with_deps {
-            generate_deployable(c)?;
-        }
-    }
-
     // Sort contracts in the correct compilation order
     let contracts = sort_r55_contracts(contracts)?;
 
@@ -77,14 +75,56 @@ fn main() -> eyre::Result<()> {
     );
 
     // Compile each contract
+    let mut code_sizes = Vec::with_capacity(contracts.len());
     for contract in contracts {
         info!("Compiling contract: {}", contract.name.ident);
 
         // Compile deployment code and save in the file
-        let deploy_bytecode = contract.compile_r55()?;
+        let (deploy_bytecode, code_size) = contract.compile_r55()?;
         let deploy_path = output_dir.join(format!("{}.bin", contract.name.package));
         fs::write(deploy_path, deploy_bytecode)?;
+        code_sizes.push(code_size);
+
+        // Export the storage layout, to check the compatibility of future upgrades
+        match StorageLayout::from_file(&contract.path.join("src/lib.rs")) {
+            Ok(layout) => {
+                let layout_path = output_dir.join(format!("{}.layout.json", contract.name.package));
+                fs::write(layout_path, layout.to_json()?)?;
+            }
+            Err(e) => debug!("No storage layout for {}: {}", contract.name.ident, e),
+        }
+    }
+
+    // Report the size of the deployed code, to keep contracts under the EIP-170 limit
+    info!("Contract sizes (runtime code):");
+    for code_size in &code_sizes {
+        if code_size.exceeds_limit() {
+            warn!("  {}, over the EIP-170 limit", code_size);
+        } else if code_size.approaches_limit() {
+            warn!("  {}, approaching the EIP-170 limit", code_size);
+        } else {
+            info!("  {}", code_size);
+        }
     }
 
     Ok(())
 }
+
+fn check_layout(old_path: &Path, new_path: &Path) -> eyre::Result<()> {
+    let old = StorageLayout::from_json(&fs::read_to_string(old_path)?)?;
+    let new = StorageLayout::from_json(&fs::read_to_string(new_path)?)?;
+
+    let incompatibilities = check_compatibility(&old, &new);
+    if incompatibilities.is_empty() {
+        info!("Storage layout of {} is compatible", new.contract);
+        return Ok(());
+    }
+
+    for incompatibility in &incompatibilities {
+        error!("{}: {}", new.contract, incompatibility);
+    }
+    eyre::bail!(
+        "Found {} storage layout incompatibilities",
+        incompatibilities.len()
+    )
+}
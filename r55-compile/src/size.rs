@@ -0,0 +1,74 @@
+//! Code size of compiled R55 contracts, against the EIP-170 limit.
+
+use std::fmt;
+
+/// Maximum size of the code of a deployed contract (EIP-170)
+pub const MAX_CODE_SIZE: usize = 24_576;
+
+/// Share of `MAX_CODE_SIZE` above which a contract is reported as approaching the limit
+const WARN_THRESHOLD_PCT: usize = 90;
+
+/// Size of the code a contract leaves on-chain once deployed
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeSize {
+    pub contract: String,
+    pub size: usize,
+}
+
+impl CodeSize {
+    /// Size of the deployed code of a contract, from its runtime ELF.
+    ///
+    /// The deploy code returns the runtime prefixed with `0xff` (the R55 marker), so that is what
+    /// ends up stored on-chain. The rest of the `.bin` is the deploy wrapper, which only runs once.
+    pub fn from_runtime(contract: &str, runtime: &[u8]) -> Self {
+        Self {
+            contract: contract.to_string(),
+            size: 1 + runtime.len(),
+        }
+    }
+
+    pub fn exceeds_limit(&self) -> bool {
+        self.size > MAX_CODE_SIZE
+    }
+
+    pub fn approaches_limit(&self) -> bool {
+        self.size * 100 > MAX_CODE_SIZE * WARN_THRESHOLD_PCT
+    }
+}
+
+impl fmt::Display for CodeSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} bytes ({:.1}% of {})",
+            self.contract,
+            self.size,
+            self.size as f64 * 100.0 / MAX_CODE_SIZE as f64,
+            MAX_CODE_SIZE
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_size_display() {
+        // The deployed code is the runtime, prefixed with `0xff`. Sizes against deployed
+        // contracts are tested in `r55/tests/e2e.rs`
+        let size = CodeSize::from_runtime("token", &vec![0x7f; 1_000]);
+        assert!(!size.approaches_limit());
+        assert_eq!(size.to_string(), "token: 1001 bytes (4.1% of 24576)");
+    }
+
+    #[test]
+    fn test_code_size_limit() {
+        let near = CodeSize::from_runtime("near", &vec![0; MAX_CODE_SIZE - 100]);
+        assert!(near.approaches_limit());
+        assert!(!near.exceeds_limit());
+
+        let over = CodeSize::from_runtime("over", &vec![0; MAX_CODE_SIZE]);
+        assert!(over.exceeds_limit());
+    }
+}
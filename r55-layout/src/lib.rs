@@ -0,0 +1,126 @@
+//! Slot allocation rules of the `storage` macro, shared with the layouts exported by `r55-compile`.
+
+use syn::{Expr, ExprLit, GenericArgument, Lit, PathArguments, Type, TypeArray};
+
+/// Amount of consecutive slots allocated to a storage field.
+///
+/// Like in Solidity, a `Slot` (or `ObservableSlot`) holding a static array of value types (e.g.
+/// `Slot<[U256; 4]>`) stores its elements from the base slot onwards, packing as many of them as
+/// fit in each slot. Any other field takes a single slot.
+///
+/// Fails for arrays whose slots can't be computed (a length other than an integer literal, or
+/// elements which aren't value types), as they would overwrite the fields after them. Also fails
+/// for tuples that can't be packed into their slot: nested ones, or larger than 32 bytes.
+pub fn storage_slots(ty: &Type) -> syn::Result<u64> {
+    match slot_value(ty) {
+        Some(Type::Array(array)) => array_slots(array).ok_or_else(|| {
+            syn::Error::new_spanned(
+                array,
+                "Unable to compute the storage slots of this array. Static arrays in storage \
+                 need an integer literal length and value type elements",
+            )
+        }),
+        Some(Type::Tuple(tuple)) => {
+            let size = tuple.elems.iter().map(value_type_size).sum::<Option<u64>>();
+            match size {
+                Some(size) if size <= 32 => Ok(1),
+                _ => Err(syn::Error::new_spanned(
+                    tuple,
+                    "Unable to pack this tuple into a single slot. Tuples in storage need value \
+                     type members, of up to 32 bytes in total",
+                )),
+            }
+        }
+        _ => Ok(1),
+    }
+}
+
+// Type of the value held by a `Slot` (or `ObservableSlot`)
+fn slot_value(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(type_path) => type_path.path.segments.last()?,
+        _ => return None,
+    };
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args)
+            if segment.ident == "Slot" || segment.ident == "ObservableSlot" =>
+        {
+            match args.args.first()? {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn array_slots(array: &TypeArray) -> Option<u64> {
+    let len = match &array.len {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(len), ..
+        }) => len.base10_parse::<u64>().ok()?,
+        _ => return None,
+    };
+
+    Some(len.div_ceil(32 / value_type_size(&array.elem)?))
+}
+
+/// Size in bytes of the value types that can be packed in a slot, named like the `contract`
+/// macro maps them to Solidity types: native integers, alloy's `U<bits>`, `I<bits>` and
+/// `B<bits>` aliases, `Address` and `bool`.
+fn value_type_size(ty: &Type) -> Option<u64> {
+    let name = match ty {
+        Type::Path(type_path) => type_path.path.segments.last()?.ident.to_string(),
+        _ => return None,
+    };
+
+    let bits = match name.as_str() {
+        "Address" => return Some(20),
+        "bool" | "Bool" => return Some(1),
+        "u8" | "i8" => 8,
+        "u16" | "i16" => 16,
+        "u32" | "i32" => 32,
+        "u64" | "i64" => 64,
+        "u128" | "i128" => 128,
+        _ => name.strip_prefix(['U', 'I', 'B'])?.parse::<u64>().ok()?,
+    };
+    // Whole bytes only, up to a full slot
+    let bytes = bits / 8;
+    ((1..=32).contains(&bytes) && bytes * 8 == bits).then_some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_storage_slots() {
+        let slots = |ty: Type| storage_slots(&ty).unwrap();
+
+        // Each 32-byte element takes a whole slot, whereas smaller elements are packed
+        assert_eq!(slots(parse_quote!(Slot<[U256; 4]>)), 4);
+        assert_eq!(slots(parse_quote!(Slot<[B256; 2]>)), 2);
+        assert_eq!(slots(parse_quote!(Slot<[u64; 5]>)), 2);
+        assert_eq!(slots(parse_quote!(Slot<[B32; 9]>)), 2);
+        assert_eq!(slots(parse_quote!(Slot<[bool; 33]>)), 2);
+        assert_eq!(slots(parse_quote!(Slot<[Address; 3]>)), 3);
+        assert_eq!(slots(parse_quote!(ObservableSlot<[U256; 2]>)), 2);
+
+        // Anything else takes a single slot
+        assert_eq!(slots(parse_quote!(Slot<U256>)), 1);
+        assert_eq!(slots(parse_quote!(Slot<(u64, u64)>)), 1);
+        assert_eq!(slots(parse_quote!(Mapping<Address, Slot<U256>>)), 1);
+        assert_eq!(slots(parse_quote!(Mapping<Address, Slot<[U256; LEN]>>)), 1);
+
+        // Arrays whose slots can't be computed are rejected, rather than overlapping other fields
+        assert!(storage_slots(&parse_quote!(Slot<[U256; LEN]>)).is_err());
+        assert!(storage_slots(&parse_quote!(Slot<[u256; 2]>)).is_err());
+
+        // So are tuples that don't fit in their slot, which would otherwise trap when written
+        assert_eq!(slots(parse_quote!(Slot<(Address, u64, u32)>)), 1);
+        assert!(storage_slots(&parse_quote!(Slot<(Address, Address)>)).is_err());
+        assert!(storage_slots(&parse_quote!(Slot<((u64, u64), u64)>)).is_err());
+        assert!(storage_slots(&parse_quote!(Slot<(U256, String)>)).is_err());
+    }
+}
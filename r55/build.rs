@@ -21,38 +21,36 @@ use core::include_bytes;
 "#,
     );
 
-    // Add bytecode constants
-    for entry in fs::read_dir(&contracts_dir).unwrap() {
-        let entry = entry.unwrap();
-        let path = entry.path();
+    // Collect the compiled contracts, sorted so that the generated code is stable across builds
+    let mut contract_names: Vec<String> = fs::read_dir(&contracts_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().unwrap_or_default() == "bin")
+        .map(|path| path.file_stem().unwrap().to_str().unwrap().to_string())
+        .collect();
+    contract_names.sort();
 
-        if path.extension().unwrap_or_default() == "bin" {
-            let contract_name = path.file_stem().unwrap().to_str().unwrap().to_uppercase();
+    // Package names may be hyphenated, but Rust identifiers can't, so both use underscores
+    let normalize = |name: &str| name.replace('-', "_");
 
-            generated.push_str(&format!(
-                "\npub const {}_BYTECODE: &[u8] = include_bytes!(\"../../../r55-output-bytecode/{}.bin\");",
-                contract_name.replace("-", "_"),
-                contract_name.to_lowercase()
-            ));
-        }
+    // Add bytecode constants
+    for contract_name in &contract_names {
+        generated.push_str(&format!(
+            "\npub const {}_BYTECODE: &[u8] = include_bytes!(\"../../../r55-output-bytecode/{}.bin\");",
+            normalize(contract_name).to_uppercase(),
+            contract_name
+        ));
     }
 
-    // Helper function to get the bytecode given a contract name
-    generated.push_str(&format!("\n{}", "\npub fn get_bytecode(contract_name: &str) -> Bytes {\n    let initcode = match contract_name {\n"));
-
-    for entry in fs::read_dir(&contracts_dir).unwrap() {
-        let entry = entry.unwrap();
-        let path = entry.path();
-
-        if path.extension().unwrap_or_default() == "bin" {
-            let contract_name = path.file_stem().unwrap().to_str().unwrap();
+    // Helper function to get the bytecode given a contract name (either hyphenated or underscored)
+    generated.push_str(&format!("\n{}", "\npub fn get_bytecode(contract_name: &str) -> Bytes {\n    let initcode = match contract_name.replace('-', \"_\").as_str() {\n"));
 
-            generated.push_str(&format!(
-                "        \"{}\" => {}_BYTECODE,\n",
-                contract_name.replace("-", "_"),
-                contract_name.replace("-", "_").to_uppercase()
-            ));
-        }
+    for contract_name in &contract_names {
+        generated.push_str(&format!(
+            "        \"{}\" => {}_BYTECODE,\n",
+            normalize(contract_name),
+            normalize(contract_name).to_uppercase()
+        ));
     }
 
     generated.push_str(
@@ -71,5 +69,5 @@ use core::include_bytes;
     fs::write(generated_path, generated).unwrap();
 
     // Tell cargo to rerun if any compiled contracts change
-    println!("cargo:rerun-if-changed=r55_output_bytecode");
+    println!("cargo:rerun-if-changed=../r55-output-bytecode");
 }
@@ -2,9 +2,10 @@
 
 use core::fmt;
 
-use alloy_primitives::{keccak256, Bytes};
+use alloy_primitives::{keccak256, Address, Bytes, U256};
+use alloy_sol_types::{SolType, SolValue};
 use revm::{
-    primitives::{EVMError, ExecutionResult, Log},
+    primitives::{EVMError, ExecutionResult, HaltReason, Log, OutOfGasError},
     Database, InMemoryDB,
 };
 use rvemu::exception::Exception;
@@ -17,6 +18,55 @@ pub struct TxResult {
     pub logs: Vec<Log>,
     pub gas_used: u64,
     pub status: bool,
+    /// Calls that re-entered an address of the active call stack. Only collected when requested,
+    /// see [`crate::exec::run_tx_with_reentrancy_detection`].
+    pub reentrancies: Vec<Reentrancy>,
+    /// SLOADs and SSTOREs of the RISC-V frames, in execution order. Only collected when requested,
+    /// see [`crate::exec::simulate_call_trace`].
+    pub storage_accesses: Vec<StorageAccess>,
+    /// Address of the contract created by a CREATE tx, see [`crate::exec::run_create_tx`]
+    pub created_address: Option<Address>,
+}
+
+impl TxResult {
+    /// Length of the output, in bytes
+    pub fn output_len(&self) -> usize {
+        self.output.len()
+    }
+
+    /// Whether the tx didn't return any data
+    pub fn is_empty_output(&self) -> bool {
+        self.output.is_empty()
+    }
+
+    /// ABI-decodes the output into `T`
+    pub fn decode<T>(&self) -> Result<T>
+    where
+        T: SolValue + From<<T::SolType as SolType>::RustType>,
+    {
+        Ok(T::abi_decode(&self.output, true)?)
+    }
+}
+
+/// Call frame that re-entered an address which was already in the active call stack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reentrancy {
+    /// Re-entered address
+    pub address: Address,
+    /// Depth of the re-entering frame, where the tx frame has depth 0
+    pub depth: usize,
+}
+
+/// SLOAD or SSTORE performed by a RISC-V frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageAccess {
+    /// Address whose storage was accessed
+    pub address: Address,
+    pub key: U256,
+    /// Value that was loaded, or stored
+    pub value: U256,
+    /// Whether the value was stored (SSTORE), rather than loaded (SLOAD)
+    pub is_store: bool,
 }
 
 /// Error encountered on RISC-V execution
@@ -34,8 +84,15 @@ where
     TryFromSliceError(#[from] std::array::TryFromSliceError),
     /// Unhandled syscall error
     SyscallError(eth_riscv_syscalls::Error),
+    /// Error returned when the call output can't be ABI-decoded
+    AbiDecodeError(#[from] alloy_sol_types::Error),
     /// Unexpected result of the transaction execution error
     UnexpectedExecResult(ExecutionResult),
+    /// The tx gas limit exceeds the gas limit of its block, so it can't be included
+    GasLimitExceedsBlock {
+        gas_limit: u64,
+        block_gas_limit: u64,
+    },
 }
 
 // Note: this `From` implementation here because `rvemu::exception::Exception`
@@ -91,10 +148,27 @@ where
                     gas_used
                 )
             }
+            Self::UnexpectedExecResult(ExecutionResult::Halt { reason, gas_used }) => {
+                write!(
+                    f,
+                    "Unexpected result of the transaction execution:\n HALT:\n > reason: {}\n > gas used: {}",
+                    describe_halt(reason),
+                    gas_used
+                )
+            }
             Self::RvEmuException(e) => write!(f, "Got RISC-V emulator exception: {:?}", e),
             Self::EvmError(e) => write!(f, "{}", e),
             Self::TryFromSliceError(e) => write!(f, "{}", e),
             Self::SyscallError(e) => write!(f, "Syscall error: {}", e),
+            Self::AbiDecodeError(e) => write!(f, "ABI decode error: {}", e),
+            Self::GasLimitExceedsBlock {
+                gas_limit,
+                block_gas_limit,
+            } => write!(
+                f,
+                "Tx gas limit {} exceeds the block gas limit {}",
+                gas_limit, block_gas_limit
+            ),
             Self::UnexpectedExecResult(other) => write!(
                 f,
                 "Unexpected result of the transaction execution: {:?}",
@@ -104,10 +178,37 @@ where
     }
 }
 
+/// Human-readable reason of a halted execution
+fn describe_halt(reason: &HaltReason) -> String {
+    match reason {
+        HaltReason::OutOfGas(OutOfGasError::Basic) => "out of gas".to_string(),
+        HaltReason::OutOfGas(kind) => format!("out of gas ({:?})", kind),
+        HaltReason::OpcodeNotFound | HaltReason::InvalidFEOpcode => "invalid opcode".to_string(),
+        HaltReason::InvalidJump => "invalid jump destination".to_string(),
+        HaltReason::StackUnderflow => "stack underflow".to_string(),
+        HaltReason::StackOverflow => "stack overflow".to_string(),
+        HaltReason::CallTooDeep => "call depth limit reached".to_string(),
+        HaltReason::OutOfFunds => "insufficient balance for transfer".to_string(),
+        HaltReason::StateChangeDuringStaticCall | HaltReason::CallNotAllowedInsideStatic => {
+            "state change during a static call".to_string()
+        }
+        HaltReason::CreateCollision => "contract address collision".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
 impl<DB: Database> Error<DB>
 where
     DB::Error: std::error::Error + 'static,
 {
+    /// Returns the gas used by a tx that was executed but didn't succeed (i.e. reverted or halted).
+    pub fn gas_used(&self) -> Option<u64> {
+        match self {
+            Error::UnexpectedExecResult(result) => Some(result.gas_used()),
+            _ => None,
+        }
+    }
+
     pub fn matches_string_error(&self, err: &'static str) -> bool {
         if let Error::UnexpectedExecResult(ExecutionResult::Revert {
             gas_used: _,
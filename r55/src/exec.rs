@@ -1,6 +1,7 @@
 use alloy_core::primitives::{Keccak256, U32};
-use core::cell::RefCell;
-use eth_riscv_interpreter::setup_from_elf;
+use alloy_sol_types::{SolType, SolValue};
+use core::cell::{Cell, RefCell};
+use eth_riscv_interpreter::{EmulatorPool, MemoryLayout};
 use eth_riscv_syscalls::Syscall;
 use revm::{
     handler::register::EvmHandler,
@@ -8,19 +9,45 @@ use revm::{
         CallInputs, CallScheme, CallValue, CreateInputs, CreateScheme, Host, InstructionResult,
         Interpreter, InterpreterAction, InterpreterResult, SharedMemory,
     },
-    primitives::{address, Address, Bytes, ExecutionResult, Log, Output, TransactTo, B256, U256},
+    primitives::{
+        address, Address, Bytes, ExecutionResult, Log, Output, SpecId, TransactTo, B256, U256,
+    },
     Database, Evm, Frame, FrameOrResult, InMemoryDB,
 };
-use rvemu::{emulator::Emulator, exception::Exception};
-use std::{collections::BTreeMap, rc::Rc, sync::Arc};
+use rvemu::{bus::DRAM_BASE, dram::DRAM_SIZE, emulator::Emulator, exception::Exception};
+use std::{collections::HashMap, ops::Range, rc::Rc, sync::Arc};
 use tracing::{debug, info, trace, warn};
 
-use super::error::{Error, Result, TxResult};
-use super::gas;
+use super::error::{Error, Reentrancy, Result, StorageAccess, TxResult};
+use super::gas::{self, GasReport, GasSchedule};
 use super::syscall_gas;
 
 const R5_REST_OF_RAM_INIT: u64 = 0x80300000; // Defined at `r5-rust-rt.x`
 
+thread_local! {
+    /// Emulators of returned frames, reused by later frames (and txs) instead of reallocating DRAM.
+    /// Also caches the ELF image of called contracts, so that their code is only parsed once.
+    static EMULATOR_POOL: RefCell<EmulatorPool> = RefCell::new(EmulatorPool::new());
+
+    /// Gas used per method by the txs of the current thread, while a gas report is active
+    static GAS_REPORT: RefCell<Option<GasReport>> = const { RefCell::new(None) };
+
+    /// Number of state changes made by the frames of the current thread, see `StaticCallCache`
+    static STATE_CHANGES: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Starts collecting the gas used by the calls of this thread, keyed by method selector.
+///
+/// Meant for benchmarking the methods of a contract within a test, discarding any previous report.
+pub fn start_gas_report() {
+    GAS_REPORT.with_borrow_mut(|report| *report = Some(GasReport::default()));
+}
+
+/// Stops collecting gas, returning the report gathered since [`start_gas_report`]
+pub fn take_gas_report() -> GasReport {
+    GAS_REPORT.with_borrow_mut(Option::take).unwrap_or_default()
+}
+
 pub fn deploy_contract(
     db: &mut InMemoryDB,
     bytecode: Bytes,
@@ -78,6 +105,7 @@ pub fn deploy_contract(
             );
             Ok(addr)
         }
+        // Creations without an address (i.e. reverted constructors) keep their revert reason
         result => Err(Error::UnexpectedExecResult(result)),
     }
 }
@@ -88,21 +116,365 @@ pub fn run_tx(
     calldata: Vec<u8>,
     caller: &Address,
 ) -> Result<TxResult> {
+    run_tx_with_value(db, addr, calldata, caller, U256::ZERO)
+}
+
+/// Runs a CREATE tx with `init_code`, whose result holds the address of the created contract.
+///
+/// Unlike [`deploy_contract`], the init code is sent as is: R55 bytecode must already be prefixed
+/// with its size, and followed by the constructor args.
+pub fn run_create_tx(
+    db: &mut InMemoryDB,
+    init_code: Vec<u8>,
+    caller: &Address,
+) -> Result<TxResult> {
+    execute(
+        db,
+        TransactTo::Create,
+        init_code,
+        caller,
+        U256::ZERO,
+        TxSettings::default(),
+    )
+}
+
+/// Same as [`run_tx`], but transfers `value` wei from the caller along with the tx.
+pub fn run_tx_with_value(
+    db: &mut InMemoryDB,
+    addr: &Address,
+    calldata: Vec<u8>,
+    caller: &Address,
+    value: U256,
+) -> Result<TxResult> {
+    execute_tx(db, addr, calldata, caller, value, TxSettings::default())
+}
+
+/// Same as [`run_tx`], but meters RISC-V execution with the provided [`GasSchedule`].
+pub fn run_tx_with_gas_schedule(
+    db: &mut InMemoryDB,
+    addr: &Address,
+    calldata: Vec<u8>,
+    caller: &Address,
+    gas_schedule: GasSchedule,
+) -> Result<TxResult> {
+    let settings = TxSettings {
+        gas_schedule,
+        ..Default::default()
+    };
+    execute_tx(db, addr, calldata, caller, U256::ZERO, settings)
+}
+
+/// Same as [`run_tx`], but reports every call that re-enters an address of the active call stack
+/// in [`TxResult::reentrancies`].
+pub fn run_tx_with_reentrancy_detection(
+    db: &mut InMemoryDB,
+    addr: &Address,
+    calldata: Vec<u8>,
+    caller: &Address,
+) -> Result<TxResult> {
+    let settings = TxSettings {
+        detect_reentrancy: true,
+        ..Default::default()
+    };
+    execute_tx(db, addr, calldata, caller, U256::ZERO, settings)
+}
+
+/// Same as [`run_tx`], but RISC-V frames reuse the output of repeated staticcalls (same target and
+/// calldata) instead of running them again, as long as they haven't modified the state since.
+pub fn run_tx_with_static_call_cache(
+    db: &mut InMemoryDB,
+    addr: &Address,
+    calldata: Vec<u8>,
+    caller: &Address,
+) -> Result<TxResult> {
+    let settings = TxSettings {
+        cache_static_calls: true,
+        ..Default::default()
+    };
+    execute_tx(db, addr, calldata, caller, U256::ZERO, settings)
+}
+
+/// Same as [`run_call`], but records every SLOAD and SSTORE of the RISC-V frames in
+/// [`TxResult::storage_accesses`], to debug storage layout mismatches.
+pub fn simulate_call_trace(
+    db: &mut InMemoryDB,
+    addr: &Address,
+    calldata: Vec<u8>,
+    caller: &Address,
+) -> Result<TxResult> {
+    let settings = TxSettings {
+        commit: false,
+        trace_storage: true,
+        ..Default::default()
+    };
+    execute_tx(db, addr, calldata, caller, U256::ZERO, settings)
+}
+
+/// Same as [`run_tx`], but pays for the gas with the provided EIP-1559 [`Eip1559Fees`] instead of
+/// a legacy gas price.
+pub fn run_tx_with_fees(
+    db: &mut InMemoryDB,
+    addr: &Address,
+    calldata: Vec<u8>,
+    caller: &Address,
+    fees: Eip1559Fees,
+) -> Result<TxResult> {
+    let settings = TxSettings {
+        fees: Some(fees),
+        ..Default::default()
+    };
+    execute_tx(db, addr, calldata, caller, U256::ZERO, settings)
+}
+
+/// Same as [`run_tx`], but with an explicit tx gas limit, included in a block with the given gas
+/// limit. Like in the EVM, a tx whose gas limit exceeds the block one is rejected before execution.
+pub fn run_tx_with_gas_limits(
+    db: &mut InMemoryDB,
+    addr: &Address,
+    calldata: Vec<u8>,
+    caller: &Address,
+    gas_limit: u64,
+    block_gas_limit: u64,
+) -> Result<TxResult> {
+    let settings = TxSettings {
+        gas_limit,
+        block_gas_limit: Some(block_gas_limit),
+        ..Default::default()
+    };
+    execute_tx(db, addr, calldata, caller, U256::ZERO, settings)
+}
+
+/// Same as [`run_tx`], but includes the tx in block `block_number` (instead of the genesis block).
+pub fn run_tx_at(
+    db: &mut InMemoryDB,
+    addr: &Address,
+    calldata: Vec<u8>,
+    caller: &Address,
+    block_number: u64,
+) -> Result<TxResult> {
+    let settings = TxSettings {
+        block_number,
+        ..Default::default()
+    };
+    execute_tx(db, addr, calldata, caller, U256::ZERO, settings)
+}
+
+/// Same as [`run_tx`], but in a block with the provided `prevrandao`. Without one, the block is
+/// pre-merge (London), and has the provided `difficulty` instead.
+pub fn run_tx_with_randomness(
+    db: &mut InMemoryDB,
+    addr: &Address,
+    calldata: Vec<u8>,
+    caller: &Address,
+    prevrandao: Option<B256>,
+    difficulty: U256,
+) -> Result<TxResult> {
+    let settings = TxSettings {
+        prevrandao,
+        difficulty,
+        ..Default::default()
+    };
+    execute_tx(db, addr, calldata, caller, U256::ZERO, settings)
+}
+
+/// Same as [`run_tx`], but the resulting state changes are discarded instead of committed.
+pub fn run_call(
+    db: &mut InMemoryDB,
+    addr: &Address,
+    calldata: Vec<u8>,
+    caller: &Address,
+) -> Result<TxResult> {
+    let settings = TxSettings {
+        commit: false,
+        ..Default::default()
+    };
+    execute_tx(db, addr, calldata, caller, U256::ZERO, settings)
+}
+
+/// Same as [`run_call`], but against block `block_number` (e.g. to test time-dependent views).
+///
+/// Only the block env is pinned: the call still reads the current state of the db.
+pub fn run_call_at(
+    db: &mut InMemoryDB,
+    addr: &Address,
+    calldata: Vec<u8>,
+    caller: &Address,
+    block_number: u64,
+) -> Result<TxResult> {
+    let settings = TxSettings {
+        commit: false,
+        block_number,
+        ..Default::default()
+    };
+    execute_tx(db, addr, calldata, caller, U256::ZERO, settings)
+}
+
+/// Runs a read-only call with [`run_call`] and ABI-decodes its output into `T`.
+pub fn query<T>(
+    db: &mut InMemoryDB,
+    addr: &Address,
+    calldata: Vec<u8>,
+    caller: &Address,
+) -> Result<T>
+where
+    T: SolValue + From<<T::SolType as SolType>::RustType>,
+{
+    run_call(db, addr, calldata, caller)?.decode()
+}
+
+/// Fees of an EIP-1559 tx, along with the base fee of the block it is included in
+#[derive(Debug, Clone, Copy)]
+pub struct Eip1559Fees {
+    pub basefee: U256,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+}
+
+/// Settings of a tx run by [`execute_tx`]
+struct TxSettings {
+    gas_schedule: GasSchedule,
+    /// Whether the resulting state changes are committed to the db
+    commit: bool,
+    /// Whether calls re-entering an address of the active call stack are reported
+    detect_reentrancy: bool,
+    /// Whether RISC-V frames cache the output of their staticcalls
+    cache_static_calls: bool,
+    /// Whether the SLOADs and SSTOREs of RISC-V frames are recorded
+    trace_storage: bool,
+    /// EIP-1559 fees of the tx, which otherwise pays a legacy gas price
+    fees: Option<Eip1559Fees>,
+    /// Number of the block including the tx
+    block_number: u64,
+    /// Gas limit of the tx
+    gas_limit: u64,
+    /// Gas limit of the block including the tx, which is unbounded otherwise
+    block_gas_limit: Option<u64>,
+    /// Randomness of the block including the tx, which is pre-merge if `None`
+    prevrandao: Option<B256>,
+    /// Difficulty of the block including the tx, only used pre-merge
+    difficulty: U256,
+}
+
+impl Default for TxSettings {
+    fn default() -> Self {
+        Self {
+            gas_schedule: GasSchedule::load(),
+            commit: true,
+            detect_reentrancy: false,
+            cache_static_calls: false,
+            trace_storage: false,
+            fees: None,
+            block_number: 0,
+            gas_limit: 100_000_000,
+            block_gas_limit: None,
+            prevrandao: Some(B256::ZERO),
+            difficulty: U256::ZERO,
+        }
+    }
+}
+
+fn execute_tx(
+    db: &mut InMemoryDB,
+    addr: &Address,
+    calldata: Vec<u8>,
+    caller: &Address,
+    value: U256,
+    settings: TxSettings,
+) -> Result<TxResult> {
+    execute(
+        db,
+        TransactTo::Call(*addr),
+        calldata,
+        caller,
+        value,
+        settings,
+    )
+}
+
+/// Same as [`execute_tx`], but for CREATE txs as well
+fn execute(
+    db: &mut InMemoryDB,
+    transact_to: TransactTo,
+    calldata: Vec<u8>,
+    caller: &Address,
+    value: U256,
+    settings: TxSettings,
+) -> Result<TxResult> {
+    let TxSettings {
+        gas_schedule,
+        commit,
+        detect_reentrancy,
+        cache_static_calls,
+        trace_storage,
+        fees,
+        block_number,
+        gas_limit,
+        block_gas_limit,
+        prevrandao,
+        difficulty,
+    } = settings;
+    if let Some(block_gas_limit) = block_gas_limit {
+        if gas_limit > block_gas_limit {
+            return Err(Error::GasLimitExceedsBlock {
+                gas_limit,
+                block_gas_limit,
+            });
+        }
+    }
+    let selector = match transact_to {
+        TransactTo::Call(_) => calldata.get(..4).and_then(|bytes| bytes.try_into().ok()),
+        TransactTo::Create => None,
+    };
+    let tracker = detect_reentrancy.then(Rc::<RefCell<ReentrancyTracker>>::default);
+    let tracker_inner = tracker.clone();
+    let storage_trace = trace_storage.then(Rc::<RefCell<Vec<StorageAccess>>>::default);
+    let storage_trace_inner = storage_trace.clone();
+
     let mut evm = Evm::builder()
         .with_db(db)
         .modify_tx_env(|tx| {
             tx.caller = *caller;
-            tx.transact_to = TransactTo::Call(*addr);
+            tx.transact_to = transact_to;
             tx.data = calldata.into();
-            tx.value = U256::from(0);
-            tx.gas_price = U256::from(42);
-            tx.gas_limit = 100_000_000;
+            tx.value = value;
+            tx.gas_price = fees.map_or(U256::from(42), |fees| fees.max_fee_per_gas);
+            tx.gas_priority_fee = fees.map(|fees| fees.max_priority_fee_per_gas);
+            tx.gas_limit = gas_limit;
+        })
+        .modify_block_env(|block| {
+            block.number = U256::from(block_number);
+            if let Some(block_gas_limit) = block_gas_limit {
+                block.gas_limit = U256::from(block_gas_limit);
+            }
+            if let Some(fees) = fees {
+                block.basefee = fees.basefee;
+            }
+            block.prevrandao = prevrandao;
+            block.difficulty = difficulty;
         })
         .modify_cfg_env(|cfg| cfg.limit_contract_code_size = Some(usize::MAX))
-        .append_handler_register(handle_register)
+        // Post-merge blocks must have a `prevrandao`
+        .with_spec_id(if prevrandao.is_some() {
+            SpecId::LATEST
+        } else {
+            SpecId::LONDON
+        })
+        .append_handler_register_box(Box::new(move |handler| {
+            register_handles(
+                handler,
+                gas_schedule,
+                tracker_inner.clone(),
+                storage_trace_inner.clone(),
+                cache_static_calls,
+            )
+        }))
         .build();
 
-    let result = evm.transact_commit()?;
+    let result = if commit {
+        evm.transact_commit()?
+    } else {
+        evm.transact()?.result
+    };
 
     match result {
         ExecutionResult::Success {
@@ -110,28 +482,217 @@ pub fn run_tx(
             gas_used,
             gas_refunded: _,
             logs,
-            output: Output::Call(value),
+            output,
             ..
         } => {
+            let (value, created_address) = match output {
+                Output::Call(value) => (value, None),
+                Output::Create(value, address) => (value, address),
+            };
             debug!("Tx result: {:?}", value);
+            if let Some(selector) = selector {
+                GAS_REPORT.with_borrow_mut(|report| {
+                    if let Some(report) = report {
+                        report.record(selector, gas_used);
+                    }
+                });
+            }
             Ok(TxResult {
                 output: value.into(),
                 logs,
                 gas_used,
                 status: true,
+                created_address,
+                reentrancies: tracker
+                    .map(|tracker| tracker.take().reentrancies)
+                    .unwrap_or_default(),
+                storage_accesses: storage_trace.map(|trace| trace.take()).unwrap_or_default(),
             })
         }
         result => Err(Error::UnexpectedExecResult(result)),
     }
 }
 
+/// Tracks the addresses of the active call stack, to report calls that re-enter any of them
+#[derive(Debug, Default)]
+pub struct ReentrancyTracker {
+    active: Vec<Address>,
+    reentrancies: Vec<Reentrancy>,
+}
+
+impl ReentrancyTracker {
+    /// Pushes the frame of `address`, reporting it if it is already active and `can_reenter`
+    fn enter(&mut self, address: Address, can_reenter: bool) {
+        let depth = self.active.len();
+        if can_reenter && self.active.contains(&address) {
+            warn!("Reentrancy into {} at depth {}", address, depth);
+            self.reentrancies.push(Reentrancy { address, depth });
+        }
+        self.active.push(address);
+    }
+
+    /// Pops the frame on top of the active call stack
+    fn exit(&mut self) {
+        self.active.pop();
+    }
+
+    /// Reentrancies detected so far
+    pub fn reentrancies(&self) -> &[Reentrancy] {
+        &self.reentrancies
+    }
+}
+
 #[derive(Debug)]
 struct RVEmu {
     emu: Emulator,
     created_address: Option<Address>,
+    memory: MemoryUsage,
+    static_calls: StaticCallCache,
+}
+
+/// DRAM usage of a frame, metered in pages of stack and heap.
+///
+/// Contract memory is never freed (the runtime allocator never deallocates, and the stack isn't
+/// cleared when it shrinks), so usage is tracked by the highest address touched in each region:
+/// - heap: the bytes handed out by the allocator, whose counter is read from the DRAM.
+/// - stack: from its top down to the lowest stack pointer seen on the syscalls of the frame.
+///
+/// Contracts which aren't built with `eth-riscv-runtime` don't export a layout, so they aren't
+/// charged for their memory.
+#[derive(Debug, Clone, Copy)]
+struct MemoryUsage {
+    layout: Option<MemoryLayout>,
+    /// Lowest stack pointer seen so far
+    stack_low: Option<u64>,
+}
+
+impl MemoryUsage {
+    fn new(layout: Option<MemoryLayout>) -> Self {
+        Self {
+            layout,
+            stack_low: None,
+        }
+    }
+
+    /// Records the stack pointer, whenever the frame hands control to the host
+    fn record_stack(&mut self, emu: &Emulator) {
+        let sp: u64 = emu.cpu.xregs.read(2);
+        self.stack_low = Some(self.stack_low.map_or(sp, |low| low.min(sp)));
+    }
+
+    /// Returns the number of pages the frame has grown its memory by (stack and heap)
+    fn peak_growth(&self, emu: &mut Emulator) -> Result<u64> {
+        let Some(layout) = self.layout else {
+            return Ok(0);
+        };
+
+        // The allocator counter lives in DRAM, where the contract could write any value
+        let heap = dram_slice(emu, layout.heap, 8)?;
+        let heap = u64::from_le_bytes(heap.try_into().expect("Slice of 8 bytes")).min(DRAM_SIZE);
+        let stack = self
+            .stack_low
+            .map_or(0, |low| layout.stack_top.saturating_sub(low).min(DRAM_SIZE));
+
+        Ok(heap.div_ceil(gas::PAGE_SIZE) + stack.div_ceil(gas::PAGE_SIZE))
+    }
+}
+
+/// Outputs of the successful staticcalls of a frame, by target address and calldata.
+///
+/// Staticcalls can't modify the state, so repeating one returns the same output as long as no
+/// state change (an SSTORE, a call or a create, by any frame) may have reached its target, and it
+/// is given enough gas to run again. Disabled unless requested.
+#[derive(Debug, Default)]
+struct StaticCallCache {
+    enabled: bool,
+    outputs: HashMap<(Address, Bytes), CachedCall>,
+    /// Staticcall which is running in a sub-frame, to be cached once it returns
+    pending: Option<PendingCall>,
+}
+
+#[derive(Debug)]
+struct CachedCall {
+    output: Bytes,
+    /// Gas spent by the caller on the call, including the gas used by its sub-frame
+    gas_used: u64,
+    /// Value of `STATE_CHANGES` when the call ran
+    state: u64,
+}
+
+#[derive(Debug)]
+struct PendingCall {
+    key: (Address, Bytes),
+    /// Gas remaining to the caller before the call
+    gas_before: u64,
+}
+
+impl StaticCallCache {
+    fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the cached output of the staticcall being performed by `emu`, if any. Otherwise,
+    /// the staticcall is kept as pending until its sub-frame returns.
+    fn lookup(&mut self, emu: &mut Emulator, interpreter: &Interpreter) -> Option<Bytes> {
+        if !self.enabled {
+            return None;
+        }
+
+        let a0: u64 = emu.cpu.xregs.read(10);
+        let a1: u64 = emu.cpu.xregs.read(11);
+        let a2: u64 = emu.cpu.xregs.read(12);
+        let addr = Address::from_word(U256::from_limbs([a0, a1, a2, 0]).into());
+        let value: u64 = emu.cpu.xregs.read(13);
+        let args_offset: u64 = emu.cpu.xregs.read(14);
+        let args_size: u64 = emu.cpu.xregs.read(15);
+        let requested_gas: u64 = emu.cpu.xregs.read(16);
+
+        // Invalid staticcalls are left to `execute_call`, which rejects them
+        let calldata = call_input(emu, args_offset, args_size).filter(|_| value == 0)?;
+        let key = (addr, calldata);
+
+        // An output is only reused if the state is unchanged since the call ran, and if the
+        // gas forwarded now covers what the call used (otherwise it could run out of gas)
+        let state = STATE_CHANGES.get();
+        let forwarded = forwarded_gas(interpreter, requested_gas);
+        match self.outputs.get(&key) {
+            Some(cached) if cached.state == state && cached.gas_used <= forwarded => {
+                Some(cached.output.clone())
+            }
+            _ => {
+                self.pending = Some(PendingCall {
+                    key,
+                    gas_before: interpreter.gas.remaining(),
+                });
+                None
+            }
+        }
+    }
+
+    /// Caches the output of the pending staticcall, if it succeeded
+    fn resolve(&mut self, success: bool, interpreter: &Interpreter) {
+        if let Some(PendingCall { key, gas_before }) = self.pending.take() {
+            if success {
+                let cached = CachedCall {
+                    output: interpreter.return_data_buffer.clone(),
+                    gas_used: gas_before.saturating_sub(interpreter.gas.remaining()),
+                    state: STATE_CHANGES.get(),
+                };
+                self.outputs.insert(key, cached);
+            }
+        }
+    }
+}
+
+/// Records a (potential) state change, which invalidates the staticcall outputs cached so far
+fn record_state_change() {
+    STATE_CHANGES.set(STATE_CHANGES.get() + 1);
 }
 
-fn riscv_context(frame: &Frame) -> Option<RVEmu> {
+fn riscv_context(frame: &Frame, cache_static_calls: bool) -> Option<RVEmu> {
     let interpreter = frame.interpreter();
 
     let Some((0xFF, bytecode)) = interpreter.bytecode.split_first() else {
@@ -139,26 +700,38 @@ fn riscv_context(frame: &Frame) -> Option<RVEmu> {
         return None;
     };
 
-    let (code, calldata) = if frame.is_create() {
-        let (code_size, init_code) = bytecode.split_at(4);
-        let Some((0xFF, bytecode)) = init_code.split_first() else {
+    let (code, calldata, code_hash) = if frame.is_create() {
+        // The interpreter bytecode is padded by revm, so the args are parsed from the original one
+        let Some((code, args)) =
+            split_init_code(interpreter.contract.bytecode.original_byte_slice())
+        else {
             warn!("NOT RISCV CONTRACT!");
             return None;
         };
-        let code_size = U32::from_be_slice(code_size).to::<usize>() - 1; // deduct control byte `0xFF`
-        let end_of_args = init_code.len() - 34; // deduct control byte + ignore empty (32 byte) word appended by revm
 
-        (&bytecode[..code_size], &bytecode[code_size..end_of_args])
+        // Init code only runs once, so its ELF image isn't worth caching
+        (code, args, None)
     } else if frame.is_call() {
-        (bytecode, interpreter.contract.input.as_ref())
+        (
+            bytecode,
+            interpreter.contract.input.as_ref(),
+            interpreter.contract.hash,
+        )
     } else {
         todo!("Support EOF")
     };
 
-    match setup_from_elf(code, calldata) {
-        Ok(emu) => Some(RVEmu {
+    let setup = EMULATOR_POOL.with_borrow_mut(|pool| {
+        let image = pool.image(code_hash.map(|code_hash| code_hash.0), code)?;
+        let emu = pool.setup_from_image(&image, calldata)?;
+        Ok::<_, eth_riscv_interpreter::Error>((emu, image.memory_layout()))
+    });
+    match setup {
+        Ok((emu, layout)) => Some(RVEmu {
             emu,
             created_address: None,
+            memory: MemoryUsage::new(layout),
+            static_calls: StaticCallCache::new(cache_static_calls),
         }),
         Err(err) => {
             warn!("Failed to setup from ELF: {err}");
@@ -167,30 +740,95 @@ fn riscv_context(frame: &Frame) -> Option<RVEmu> {
     }
 }
 
+/// Splits R55 init code (`[0xFF][code size][0xFF][code][constructor args]`) into the code of the
+/// deployer and the constructor args, which are everything after the embedded code size
+fn split_init_code(init_code: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (code_size, bytecode) = init_code.strip_prefix(&[0xFF])?.split_at_checked(4)?;
+    let bytecode = bytecode.strip_prefix(&[0xFF])?;
+    let code_size = U32::from_be_slice(code_size).to::<usize>().checked_sub(1)?; // deduct control byte `0xFF`
+
+    bytecode.split_at_checked(code_size)
+}
+
 pub fn handle_register<EXT, DB: Database>(handler: &mut EvmHandler<'_, EXT, DB>) {
+    handle_register_with_gas_schedule(handler, GasSchedule::load())
+}
+
+pub fn handle_register_with_gas_schedule<EXT, DB: Database>(
+    handler: &mut EvmHandler<'_, EXT, DB>,
+    gas_schedule: GasSchedule,
+) {
+    register_handles(handler, gas_schedule, None, None, false)
+}
+
+/// Same as [`handle_register_with_gas_schedule`], but RISC-V frames cache the output of their
+/// staticcalls, see [`run_tx_with_static_call_cache`].
+pub fn handle_register_with_static_call_cache<EXT, DB: Database>(
+    handler: &mut EvmHandler<'_, EXT, DB>,
+    gas_schedule: GasSchedule,
+) {
+    register_handles(handler, gas_schedule, None, None, true)
+}
+
+/// Same as [`handle_register_with_gas_schedule`], but also records into `tracker` every call that
+/// re-enters an address of the active call stack.
+pub fn handle_register_with_reentrancy_tracker<EXT, DB: Database>(
+    handler: &mut EvmHandler<'_, EXT, DB>,
+    gas_schedule: GasSchedule,
+    tracker: Rc<RefCell<ReentrancyTracker>>,
+) {
+    register_handles(handler, gas_schedule, Some(tracker), None, false)
+}
+
+fn register_handles<EXT, DB: Database>(
+    handler: &mut EvmHandler<'_, EXT, DB>,
+    gas_schedule: GasSchedule,
+    tracker: Option<Rc<RefCell<ReentrancyTracker>>>,
+    storage_trace: Option<Rc<RefCell<Vec<StorageAccess>>>>,
+    cache_static_calls: bool,
+) {
     trace!("HANDLE REGISTER");
     let call_stack = Rc::<RefCell<Vec<_>>>::new(RefCell::new(Vec::new()));
 
     // create a riscv context on call frame.
     let call_stack_inner = call_stack.clone();
+    let tracker_inner = tracker.clone();
     let old_handle = handler.execution.call.clone();
     handler.execution.call = Arc::new(move |ctx, inputs| {
+        // delegate calls run in the context of their caller, so they don't re-enter it
+        let is_delegate = matches!(
+            inputs.scheme,
+            CallScheme::DelegateCall | CallScheme::CallCode
+        );
         let result = old_handle(ctx, inputs);
         if let Ok(FrameOrResult::Frame(frame)) = &result {
             trace!("Creating new CALL frame");
-            call_stack_inner.borrow_mut().push(riscv_context(frame));
+            if let Some(tracker) = &tracker_inner {
+                let address = frame.interpreter().contract.target_address;
+                tracker.borrow_mut().enter(address, !is_delegate);
+            }
+            call_stack_inner
+                .borrow_mut()
+                .push(riscv_context(frame, cache_static_calls));
         }
         result
     });
 
     // create a riscv context on create frame.
     let call_stack_inner = call_stack.clone();
+    let tracker_inner = tracker.clone();
     let old_handle = handler.execution.create.clone();
     handler.execution.create = Arc::new(move |ctx, inputs| {
         let result = old_handle(ctx, inputs);
         if let Ok(FrameOrResult::Frame(frame)) = &result {
             trace!("Creating new CREATE frame");
-            call_stack_inner.borrow_mut().push(riscv_context(frame));
+            if let Some(tracker) = &tracker_inner {
+                let address = frame.interpreter().contract.target_address;
+                tracker.borrow_mut().enter(address, true);
+            }
+            call_stack_inner
+                .borrow_mut()
+                .push(riscv_context(frame, cache_static_calls));
         }
         result
     });
@@ -207,7 +845,14 @@ pub fn handle_register<EXT, DB: Database>(handler: &mut EvmHandler<'_, EXT, DB>)
                 depth,
                 frame.interpreter().contract.target_address,
             );
-            execute_riscv(riscv_context, frame.interpreter_mut(), memory, ctx)?
+            execute_riscv(
+                riscv_context,
+                frame.interpreter_mut(),
+                memory,
+                ctx,
+                &gas_schedule,
+                storage_trace.as_deref(),
+            )?
         } else {
             debug!("=== [OLD Handler] ==================--");
             old_handle(frame, memory, instraction_table, ctx)?
@@ -216,11 +861,17 @@ pub fn handle_register<EXT, DB: Database>(handler: &mut EvmHandler<'_, EXT, DB>)
         // if action is return, pop the stack and potentially cache created address.
         if result.is_return() {
             let mut stack = call_stack.borrow_mut();
-            stack.pop();
+            if let Some(Some(rvemu)) = stack.pop() {
+                EMULATOR_POOL.with_borrow_mut(|pool| pool.release(rvemu.emu));
+            }
 
             if let Some(Some(parent)) = stack.last_mut() {
                 parent.created_address = frame.created_address()
             }
+
+            if let Some(tracker) = &tracker {
+                tracker.borrow_mut().exit();
+            }
         }
 
         debug!("=== [Frame-{}] {:#?}", depth, frame.interpreter().gas);
@@ -233,6 +884,8 @@ fn execute_riscv(
     interpreter: &mut Interpreter,
     _shared_memory: &mut SharedMemory,
     host: &mut dyn Host,
+    gas_schedule: &GasSchedule,
+    storage_trace: Option<&RefCell<Vec<StorageAccess>>>,
 ) -> Result<InterpreterAction> {
     trace!(
         "{} RISC-V execution:  PC: {:#x}",
@@ -244,11 +897,31 @@ fn execute_riscv(
         rvemu.emu.cpu.pc,
     );
 
+    let memory = &mut rvemu.memory;
+    let static_calls = &mut rvemu.static_calls;
     let emu = &mut rvemu.emu;
     emu.cpu.is_count = true;
 
-    let return_revert = |interpreter: &mut Interpreter, gas_used: u64| {
-        let _ = interpreter.gas.record_cost(gas_used);
+    // When resuming after a sub-call, revm pushes the call status (1: success, 0: revert) onto
+    // the interpreter stack, which is otherwise unused by RISC-V contracts.
+    if let Ok(status) = interpreter.stack.pop() {
+        let t0: u64 = emu.cpu.xregs.read(5);
+        if let Ok(Syscall::Call | Syscall::StaticCall | Syscall::DelegateCall) =
+            Syscall::try_from(t0 as u8)
+        {
+            debug!("> Call status: {}", status);
+            emu.cpu.xregs.write(10, status.as_limbs()[0]);
+            static_calls.resolve(status == U256::from(1), interpreter);
+        }
+    }
+
+    // Reverts on unexpected errors, only charging the gas of the risc-v instructions executed so far.
+    // The unused gas is left in `interpreter.gas`, so that it's refunded to the caller.
+    let return_revert = |interpreter: &mut Interpreter,
+                         emu: &mut Emulator,
+                         memory: &MemoryUsage|
+     -> Result<InterpreterAction> {
+        syscall_gas!(interpreter, r55_gas_used(emu, memory, gas_schedule)?);
         Ok(InterpreterAction::Return {
             result: InterpreterResult {
                 result: InstructionResult::Revert,
@@ -264,11 +937,12 @@ fn execute_riscv(
         let run_result = emu.start();
         match run_result {
             Err(Exception::EnvironmentCallFromMMode) => {
+                memory.record_stack(emu);
                 let t0: u64 = emu.cpu.xregs.read(5);
 
                 let Ok(syscall) = Syscall::try_from(t0 as u8) else {
                     warn!("Unhandled syscall: {:?}", t0);
-                    return return_revert(interpreter, interpreter.gas.spent());
+                    return return_revert(interpreter, emu, memory);
                 };
                 debug!("[Syscall::{} - {:#04x}]", syscall, t0);
 
@@ -277,19 +951,19 @@ fn execute_riscv(
                         let ret_offset: u64 = emu.cpu.xregs.read(10);
                         let ret_size: u64 = emu.cpu.xregs.read(11);
 
-                        let r55_gas = r55_gas_used(&emu.cpu.inst_counter);
+                        let r55_gas = r55_gas_used(emu, memory, gas_schedule)?;
                         debug!("> Total R55 gas: {}", r55_gas);
 
-                        // RETURN logs the gas of the whole risc-v instruction set
+                        // RETURN logs the gas of the whole risc-v instruction set (and memory)
                         syscall_gas!(interpreter, r55_gas);
 
-                        let data_bytes = dram_slice(emu, ret_offset, ret_size)?;
-                        trace!("> RETURN: {}", Bytes::from(data_bytes.to_vec()));
+                        let output = dram_bytes(emu, ret_offset, ret_size)?;
+                        trace!("> RETURN: {}", output);
 
                         return Ok(InterpreterAction::Return {
                             result: InterpreterResult {
                                 result: InstructionResult::Return,
-                                output: data_bytes.to_vec().into(),
+                                output,
                                 gas: interpreter.gas, // FIXME: gas is not correct
                             },
                         });
@@ -315,6 +989,14 @@ fn execute_riscv(
                                 emu.cpu.xregs.write(11, limbs[1]);
                                 emu.cpu.xregs.write(12, limbs[2]);
                                 emu.cpu.xregs.write(13, limbs[3]);
+                                if let Some(trace) = storage_trace {
+                                    trace.borrow_mut().push(StorageAccess {
+                                        address: interpreter.contract.target_address,
+                                        key,
+                                        value: state_load.data,
+                                        is_store: false,
+                                    });
+                                }
                                 syscall_gas!(
                                     interpreter,
                                     if state_load.is_cold {
@@ -325,11 +1007,16 @@ fn execute_riscv(
                                 );
                             }
                             _ => {
-                                return return_revert(interpreter, interpreter.gas.spent());
+                                return return_revert(interpreter, emu, memory);
                             }
                         }
                     }
                     Syscall::SStore => {
+                        if interpreter.is_static {
+                            return Ok(static_state_change(interpreter));
+                        }
+
+                        record_state_change();
                         let key1: u64 = emu.cpu.xregs.read(10);
                         let key2: u64 = emu.cpu.xregs.read(11);
                         let key3: u64 = emu.cpu.xregs.read(12);
@@ -352,6 +1039,14 @@ fn execute_riscv(
 
                         let result = host.sstore(interpreter.contract.target_address, key, value);
                         if let Some(result) = result {
+                            if let Some(trace) = storage_trace {
+                                trace.borrow_mut().push(StorageAccess {
+                                    address: interpreter.contract.target_address,
+                                    key,
+                                    value,
+                                    is_store: true,
+                                });
+                            }
                             syscall_gas!(
                                 interpreter,
                                 if result.is_cold {
@@ -363,6 +1058,7 @@ fn execute_riscv(
                         }
                     }
                     Syscall::ReturnDataSize => {
+                        syscall_gas!(interpreter, gas::BASE);
                         let size = interpreter.return_data_buffer.len();
                         debug!("> RETURNDATASIZE: {}", size);
                         emu.cpu.xregs.write(10, size as u64);
@@ -370,9 +1066,16 @@ fn execute_riscv(
                     Syscall::ReturnDataCopy => {
                         trace!("> RETURNDATA BUFFER: {}", &interpreter.return_data_buffer);
                         let dest_offset = emu.cpu.xregs.read(10);
-                        let offset = emu.cpu.xregs.read(11) as usize;
-                        let size = emu.cpu.xregs.read(12) as usize;
-                        let data = &interpreter.return_data_buffer.as_ref()[offset..offset + size];
+                        let offset = emu.cpu.xregs.read(11);
+                        let size = emu.cpu.xregs.read(12);
+                        let (Some(range), Some(dest_end)) = (
+                            return_data_range(interpreter.return_data_buffer.len(), offset, size),
+                            dest_offset.checked_add(size),
+                        ) else {
+                            return Ok(return_data_out_of_bounds(interpreter, offset, size));
+                        };
+                        syscall_gas!(interpreter, gas::copy_cost(size));
+                        let data = &interpreter.return_data_buffer.as_ref()[range];
                         debug!(
                             "> RETURNDATACOPY [memory_offset: {}, offset: {}, size: {}]\n{}",
                             dest_offset,
@@ -382,15 +1085,36 @@ fn execute_riscv(
                         );
 
                         // write return data to memory
-                        let return_memory = emu
-                            .cpu
-                            .bus
-                            .get_dram_slice(dest_offset..(dest_offset + size as u64))?;
+                        let return_memory = emu.cpu.bus.get_dram_slice(dest_offset..dest_end)?;
                         return_memory.copy_from_slice(data);
                     }
-                    Syscall::Call => return execute_call(emu, interpreter, host, false),
-                    Syscall::StaticCall => return execute_call(emu, interpreter, host, true),
-                    Syscall::Create => return execute_create(emu, interpreter, host),
+                    Syscall::Call => {
+                        record_state_change();
+                        return execute_call(emu, interpreter, host, false);
+                    }
+                    Syscall::StaticCall => {
+                        let Some(output) = static_calls.lookup(emu, interpreter) else {
+                            return execute_call(emu, interpreter, host, true);
+                        };
+
+                        // Cache hit: resume without a sub-frame, at the cost of a warm call
+                        debug!("> Cached staticcall output: {}", output);
+                        syscall_gas!(interpreter, gas::CALL_BASE);
+                        interpreter.return_data_buffer = output;
+                        emu.cpu.xregs.write(10, 1);
+                    }
+                    Syscall::DelegateCall => {
+                        record_state_change();
+                        return execute_delegate_call(emu, interpreter, host);
+                    }
+                    Syscall::Create => {
+                        if interpreter.is_static {
+                            return Ok(static_state_change(interpreter));
+                        }
+
+                        record_state_change();
+                        return execute_create(emu, interpreter, host);
+                    }
                     Syscall::ReturnCreateAddress => {
                         debug!("> RETURNCREATEDADDRESS: {:?}", &rvemu.created_address);
                         let dest_offset = emu.cpu.xregs.read(10);
@@ -408,18 +1132,38 @@ fn execute_riscv(
                     Syscall::Revert => {
                         let ret_offset: u64 = emu.cpu.xregs.read(10);
                         let ret_size: u64 = emu.cpu.xregs.read(11);
-                        let data_bytes: Vec<u8> = dram_slice(emu, ret_offset, ret_size)?.into();
+                        let output = dram_bytes(emu, ret_offset, ret_size)?;
                         debug!("REVERT > offset: {:#04x}, size: {}", ret_offset, ret_size);
 
+                        // REVERT also logs the gas of the risc-v instructions executed so far
+                        let r55_gas = r55_gas_used(emu, memory, gas_schedule)?;
+                        debug!("> Total R55 gas: {}", r55_gas);
+                        syscall_gas!(interpreter, r55_gas);
+
                         return Ok(InterpreterAction::Return {
                             result: InterpreterResult {
                                 result: InstructionResult::Revert,
-                                output: Bytes::from(data_bytes),
+                                output,
                                 gas: interpreter.gas, // FIXME: gas is not correct
                             },
                         });
                     }
+                    Syscall::Address => {
+                        syscall_gas!(interpreter, gas::BASE);
+                        let address = interpreter.contract.target_address;
+                        // Break address into 3 u64s and write to registers
+                        let address_bytes = address.as_slice();
+                        let first_u64 = u64::from_be_bytes(address_bytes[0..8].try_into()?);
+                        emu.cpu.xregs.write(10, first_u64);
+                        let second_u64 = u64::from_be_bytes(address_bytes[8..16].try_into()?);
+                        emu.cpu.xregs.write(11, second_u64);
+                        let mut padded_bytes = [0u8; 8];
+                        padded_bytes[..4].copy_from_slice(&address_bytes[16..20]);
+                        let third_u64 = u64::from_be_bytes(padded_bytes);
+                        emu.cpu.xregs.write(12, third_u64);
+                    }
                     Syscall::Caller => {
+                        syscall_gas!(interpreter, gas::BASE);
                         let caller = interpreter.contract.caller;
                         // Break address into 3 u64s and write to registers
                         let caller_bytes = caller.as_slice();
@@ -432,6 +1176,31 @@ fn execute_riscv(
                         let third_u64 = u64::from_be_bytes(padded_bytes);
                         emu.cpu.xregs.write(12, third_u64);
                     }
+                    Syscall::Byte | Syscall::Shl | Syscall::Shr | Syscall::Sar => {
+                        syscall_gas!(interpreter, gas::VERYLOW);
+                        let value = U256::from_limbs([
+                            emu.cpu.xregs.read(10),
+                            emu.cpu.xregs.read(11),
+                            emu.cpu.xregs.read(12),
+                            emu.cpu.xregs.read(13),
+                        ]);
+                        let arg = usize::try_from(emu.cpu.xregs.read(14)).unwrap_or(usize::MAX);
+
+                        // Like the EVM opcodes, out-of-range shifts and indexes don't fail
+                        let result = match syscall {
+                            Syscall::Byte if arg < 32 => U256::from(value.byte(31 - arg)),
+                            Syscall::Byte => U256::ZERO,
+                            Syscall::Shl => value.wrapping_shl(arg),
+                            Syscall::Shr => value.wrapping_shr(arg),
+                            _ => value.arithmetic_shr(arg),
+                        };
+
+                        let limbs = result.as_limbs();
+                        emu.cpu.xregs.write(10, limbs[0]);
+                        emu.cpu.xregs.write(11, limbs[1]);
+                        emu.cpu.xregs.write(12, limbs[2]);
+                        emu.cpu.xregs.write(13, limbs[3]);
+                    }
                     Syscall::Keccak256 => {
                         let ret_offset: u64 = emu.cpu.xregs.read(10);
                         let ret_size: u64 = emu.cpu.xregs.read(11);
@@ -449,6 +1218,7 @@ fn execute_riscv(
                         emu.cpu.xregs.write(13, limbs[3]);
                     }
                     Syscall::CallValue => {
+                        syscall_gas!(interpreter, gas::BASE);
                         let value = interpreter.contract.call_value;
                         let limbs = value.into_limbs();
                         emu.cpu.xregs.write(10, limbs[0]);
@@ -476,6 +1246,19 @@ fn execute_riscv(
                         emu.cpu.xregs.write(12, limbs[2]);
                         emu.cpu.xregs.write(13, limbs[3]);
                     }
+                    Syscall::BlockHash => {
+                        let number: u64 = emu.cpu.xregs.read(10);
+                        // The host returns zero for blocks outside of the last 256 ones
+                        let Some(hash) = host.block_hash(number) else {
+                            return return_revert(interpreter, emu, memory);
+                        };
+                        let limbs = U256::from_be_bytes(hash.0).into_limbs();
+                        emu.cpu.xregs.write(10, limbs[0]);
+                        emu.cpu.xregs.write(11, limbs[1]);
+                        emu.cpu.xregs.write(12, limbs[2]);
+                        emu.cpu.xregs.write(13, limbs[3]);
+                        syscall_gas!(interpreter, gas::BLOCKHASH);
+                    }
                     Syscall::Number => {
                         let number = host.env().block.number;
                         let limbs = number.as_limbs();
@@ -484,6 +1267,19 @@ fn execute_riscv(
                         emu.cpu.xregs.write(12, limbs[2]);
                         emu.cpu.xregs.write(13, limbs[3]);
                     }
+                    Syscall::PrevRandao => {
+                        // Post-merge, `prevrandao` replaces `difficulty` (EIP-4399). Pre-merge blocks
+                        // have no `prevrandao`, so like revm's DIFFICULTY opcode, fall back to it.
+                        let block = &host.env().block;
+                        let randao = block
+                            .prevrandao
+                            .map_or(block.difficulty, |randao| randao.into());
+                        let limbs = randao.as_limbs();
+                        emu.cpu.xregs.write(10, limbs[0]);
+                        emu.cpu.xregs.write(11, limbs[1]);
+                        emu.cpu.xregs.write(12, limbs[2]);
+                        emu.cpu.xregs.write(13, limbs[3]);
+                    }
                     Syscall::Timestamp => {
                         let timestamp = host.env().block.timestamp;
                         let limbs = timestamp.as_limbs();
@@ -493,7 +1289,8 @@ fn execute_riscv(
                         emu.cpu.xregs.write(13, limbs[3]);
                     }
                     Syscall::GasPrice => {
-                        let value = host.env().tx.gas_price;
+                        // Under EIP-1559, the price paid depends on the base fee of the block
+                        let value = host.env().effective_gas_price();
                         let limbs = value.as_limbs();
                         emu.cpu.xregs.write(10, limbs[0]);
                         emu.cpu.xregs.write(11, limbs[1]);
@@ -502,6 +1299,7 @@ fn execute_riscv(
                     }
                     Syscall::Origin => {
                         // Syscall::Origin
+                        syscall_gas!(interpreter, gas::BASE);
                         let origin = host.env().tx.caller;
                         // Break address into 3 u64s and write to registers
                         let origin_bytes = origin.as_slice();
@@ -519,33 +1317,28 @@ fn execute_riscv(
                         emu.cpu.xregs.write(12, third_u64);
                     }
                     Syscall::Log => {
+                        // Logs are part of the state, which static frames can't modify
+                        if interpreter.is_static {
+                            return Ok(static_state_change(interpreter));
+                        }
+
                         let data_ptr: u64 = emu.cpu.xregs.read(10);
                         let data_size: u64 = emu.cpu.xregs.read(11);
                         let topics_ptr: u64 = emu.cpu.xregs.read(12);
                         let topics_size: u64 = emu.cpu.xregs.read(13);
 
                         // Read data
-                        let data = if data_size == 0 {
-                            Vec::new()
-                        } else {
-                            let data_slice = emu
-                                .cpu
-                                .bus
-                                .get_dram_slice(data_ptr..(data_ptr + data_size))
-                                .unwrap_or(&mut []);
-                            data_slice.to_vec()
+                        let Some(data) = call_input(emu, data_ptr, data_size) else {
+                            return Ok(out_of_bounds_revert(interpreter, data_ptr, data_size));
                         };
-                        trace!("> LOGS [DATA]: {:?}", Bytes::from(data.clone()));
+                        trace!("> LOGS [DATA]: {:?}", data);
 
-                        // Read topics
-                        let topics_start = topics_ptr;
-                        let topics_end = topics_ptr + topics_size * 32;
-                        let topics_slice = emu
-                            .cpu
-                            .bus
-                            .get_dram_slice(topics_start..topics_end)
-                            .unwrap_or(&mut []);
-                        let topics = topics_slice
+                        // Read topics (an overflowing size can't fit in the DRAM either)
+                        let topics_len = topics_size.checked_mul(32).unwrap_or(u64::MAX);
+                        let Some(topics) = call_input(emu, topics_ptr, topics_len) else {
+                            return Ok(out_of_bounds_revert(interpreter, topics_ptr, topics_len));
+                        };
+                        let topics = topics
                             .chunks(32)
                             .map(B256::from_slice)
                             .collect::<Vec<B256>>();
@@ -554,7 +1347,7 @@ fn execute_riscv(
                         host.log(Log::new_unchecked(
                             interpreter.contract.target_address,
                             topics,
-                            data.into(),
+                            data,
                         ));
                     }
                 }
@@ -565,8 +1358,7 @@ fn execute_riscv(
             }
             Err(e) => {
                 debug!("Execution error: {:#?}", e);
-                syscall_gas!(interpreter, r55_gas_used(&emu.cpu.inst_counter));
-                return return_revert(interpreter, interpreter.gas.spent());
+                return return_revert(interpreter, emu, memory);
             }
         }
     }
@@ -584,59 +1376,189 @@ fn execute_call(
     let addr = Address::from_word(U256::from_limbs([a0, a1, a2, 0]).into());
     let value: u64 = emu.cpu.xregs.read(13);
 
+    // Calls made within a static context are static as well
+    let is_static = is_static || interpreter.is_static;
+
     // Get calldata
     let args_offset: u64 = emu.cpu.xregs.read(14);
     let args_size: u64 = emu.cpu.xregs.read(15);
-    let calldata: Bytes = emu
-        .cpu
-        .bus
-        .get_dram_slice(args_offset..(args_offset + args_size))
-        .unwrap_or(&mut [])
-        .to_vec()
-        .into();
+    let Some(calldata) = call_input(emu, args_offset, args_size) else {
+        return Ok(out_of_bounds_revert(interpreter, args_offset, args_size));
+    };
+
+    // Static calls can't transfer value, as it would modify the state
+    if is_static && value != 0 {
+        warn!("Attempted a static call with value: {}", value);
+        return Ok(static_state_change(interpreter));
+    }
 
     // Calculate gas cost of the call
-    // TODO: check correctness (tried using evm.codes as ref but i'm no gas wizard)
-    // TODO: unsure whether memory expansion cost is missing (should be captured in the risc-v costs)
-    let (empty_account_cost, addr_access_cost) = match host.load_account_delegated(addr) {
-        Some(account) => {
-            if account.is_cold {
-                (0, gas::CALL_NEW_ACCOUNT)
-            } else {
-                (0, gas::CALL_BASE)
-            }
-        }
-        None => (gas::CALL_EMPTY_ACCOUNT, gas::CALL_NEW_ACCOUNT),
-    };
-    let value_cost = if value != 0 { gas::CALL_VALUE } else { 0 };
-    let call_gas_cost = empty_account_cost + addr_access_cost + value_cost;
+    let call_gas_cost = call_gas_cost(host, addr, value);
     syscall_gas!(interpreter, call_gas_cost);
 
     // proactively spend gas limit as the remaining will be refunded (otherwise it underflows)
-    let call_gas_limit = interpreter.gas.remaining();
+    let requested_gas: u64 = emu.cpu.xregs.read(16);
+    let call_gas_limit = forwarded_gas(interpreter, requested_gas);
     syscall_gas!(interpreter, call_gas_limit);
 
+    // Like in the EVM, value transfers get a stipend on top of the forwarded gas, covered by `CALL_VALUE`
+    let call_gas_limit = if value != 0 {
+        call_gas_limit + gas::CALL_STIPEND
+    } else {
+        call_gas_limit
+    };
+
     debug!("> {}Call context:", if is_static { "Static" } else { "" });
     debug!("  - Caller: {}", interpreter.contract.target_address);
     debug!("  - Target Address: {}", addr);
     debug!("  - Value: {}", value);
     debug!("  - Calldata: {:?}", calldata);
     Ok(InterpreterAction::Call {
-        inputs: Box::new(CallInputs {
-            input: calldata,
-            gas_limit: call_gas_limit,
-            target_address: addr,
-            bytecode_address: addr,
-            caller: interpreter.contract.target_address,
-            value: CallValue::Transfer(U256::from(value)),
-            scheme: CallScheme::Call,
+        inputs: Box::new(call_inputs(
+            interpreter.contract.target_address,
+            addr,
+            U256::from(value),
+            calldata,
+            call_gas_limit,
             is_static,
-            is_eof: false,
-            return_memory_offset: 0..0, // handled with RETURNDATACOPY
-        }),
+        )),
+    })
+}
+
+fn execute_delegate_call(
+    emu: &mut Emulator,
+    interpreter: &mut Interpreter,
+    host: &mut dyn Host,
+) -> Result<InterpreterAction> {
+    let a0: u64 = emu.cpu.xregs.read(10);
+    let a1: u64 = emu.cpu.xregs.read(11);
+    let a2: u64 = emu.cpu.xregs.read(12);
+    let addr = Address::from_word(U256::from_limbs([a0, a1, a2, 0]).into());
+
+    // Get calldata
+    let args_offset: u64 = emu.cpu.xregs.read(13);
+    let args_size: u64 = emu.cpu.xregs.read(14);
+    let Some(calldata) = call_input(emu, args_offset, args_size) else {
+        return Ok(out_of_bounds_revert(interpreter, args_offset, args_size));
+    };
+
+    // Calculate gas cost of the call (delegate calls never transfer value)
+    let call_gas_cost = call_gas_cost(host, addr, 0);
+    syscall_gas!(interpreter, call_gas_cost);
+
+    // proactively spend gas limit as the remaining will be refunded (otherwise it underflows)
+    let call_gas_limit = forwarded_gas(interpreter, u64::MAX);
+    syscall_gas!(interpreter, call_gas_limit);
+
+    debug!("> DelegateCall context:");
+    debug!("  - Caller: {}", interpreter.contract.caller);
+    debug!(
+        "  - Target Address: {}",
+        interpreter.contract.target_address
+    );
+    debug!("  - Bytecode Address: {}", addr);
+    debug!("  - Calldata: {:?}", calldata);
+    Ok(InterpreterAction::Call {
+        inputs: Box::new(delegate_call_inputs(
+            interpreter.contract.caller,
+            interpreter.contract.target_address,
+            addr,
+            interpreter.contract.call_value,
+            calldata,
+            call_gas_limit,
+            interpreter.is_static,
+        )),
     })
 }
 
+// TODO: check correctness (tried using evm.codes as ref but i'm no gas wizard)
+// TODO: unsure whether memory expansion cost is missing (should be captured in the risc-v costs)
+fn call_gas_cost(host: &mut dyn Host, addr: Address, value: u64) -> u64 {
+    let (empty_account_cost, addr_access_cost) = match host.load_account_delegated(addr) {
+        Some(account) => {
+            if account.is_cold {
+                (0, gas::CALL_NEW_ACCOUNT)
+            } else {
+                (0, gas::CALL_BASE)
+            }
+        }
+        None => (gas::CALL_EMPTY_ACCOUNT, gas::CALL_NEW_ACCOUNT),
+    };
+    let value_cost = if value != 0 { gas::CALL_VALUE } else { 0 };
+    empty_account_cost + addr_access_cost + value_cost
+}
+
+/// Gas forwarded to a sub-frame requesting `requested` gas, capped to all but one 64th of the
+/// remaining gas (EIP-150). The caller can then handle a sub-frame running out of gas.
+fn forwarded_gas(interpreter: &Interpreter, requested: u64) -> u64 {
+    let remaining = interpreter.gas.remaining();
+    requested.min(remaining - remaining / 64)
+}
+
+/// Builds the inputs of a `CALL` (or `STATICCALL` if `is_static`) from `caller` to `addr`.
+///
+/// The address fields follow the EVM semantics for these schemes:
+/// - `target_address`: account whose storage and balance the callee operates on.
+/// - `bytecode_address`: account whose code is executed. For `CALL` and `STATICCALL` both are
+///   the callee, whereas `DELEGATECALL` and `CALLCODE` would keep the storage of the caller.
+/// - `caller`: the calling contract, which becomes the callee's `msg.sender`.
+///
+/// If `addr` is a precompile, revm routes the call to it. If it is an EOA (no code), the call
+/// succeeds without executing any code.
+fn call_inputs(
+    caller: Address,
+    addr: Address,
+    value: U256,
+    input: Bytes,
+    gas_limit: u64,
+    is_static: bool,
+) -> CallInputs {
+    CallInputs {
+        input,
+        gas_limit,
+        target_address: addr,
+        bytecode_address: addr,
+        caller,
+        value: CallValue::Transfer(value),
+        scheme: if is_static {
+            CallScheme::StaticCall
+        } else {
+            CallScheme::Call
+        },
+        is_static,
+        is_eof: false,
+        return_memory_offset: 0..0, // handled with RETURNDATACOPY
+    }
+}
+
+/// Builds the inputs of a `DELEGATECALL` that runs the code at `addr` in the current frame.
+///
+/// Unlike [`call_inputs`], the callee keeps the context of the delegating contract: its storage
+/// (`target_address`), its `msg.sender` (`caller`) and its `msg.value` (`value`), which is only
+/// apparent, as no value is transferred.
+fn delegate_call_inputs(
+    caller: Address,
+    target: Address,
+    addr: Address,
+    value: U256,
+    input: Bytes,
+    gas_limit: u64,
+    is_static: bool,
+) -> CallInputs {
+    CallInputs {
+        input,
+        gas_limit,
+        target_address: target,
+        bytecode_address: addr,
+        caller,
+        value: CallValue::Apparent(value),
+        scheme: CallScheme::DelegateCall,
+        is_static,
+        is_eof: false,
+        return_memory_offset: 0..0, // handled with RETURNDATACOPY
+    }
+}
+
 fn execute_create(
     emu: &mut Emulator,
     interpreter: &mut Interpreter,
@@ -647,20 +1569,16 @@ fn execute_create(
     // Get initcode
     let args_offset: u64 = emu.cpu.xregs.read(11);
     let args_size: u64 = emu.cpu.xregs.read(12);
-    let init_code: Bytes = emu
-        .cpu
-        .bus
-        .get_dram_slice(args_offset..(args_offset + args_size))
-        .unwrap_or(&mut [])
-        .to_vec()
-        .into();
+    let Some(init_code) = call_input(emu, args_offset, args_size) else {
+        return Ok(out_of_bounds_revert(interpreter, args_offset, args_size));
+    };
 
     // TODO: calculate gas cost properly
     let create_gas_cost = gas::CREATE_BASE;
     syscall_gas!(interpreter, create_gas_cost);
 
     // proactively spend gas limit as the remaining will be refunded (otherwise it underflows)
-    let create_gas_limit = interpreter.gas.remaining();
+    let create_gas_limit = forwarded_gas(interpreter, u64::MAX);
     syscall_gas!(interpreter, create_gas_limit);
 
     debug!("> CREATE CTX:");
@@ -678,6 +1596,82 @@ fn execute_create(
     })
 }
 
+/// Copies the input of a sub-call, create or log out of the RISC-V DRAM.
+///
+/// Returns `None` if `[offset, offset + size)` doesn't fit in the DRAM, rather than copying (or
+/// allocating) an arbitrarily large buffer on behalf of the contract.
+fn call_input(emu: &mut Emulator, offset: u64, size: u64) -> Option<Bytes> {
+    if size == 0 {
+        return Some(Bytes::new());
+    }
+
+    let end = offset.checked_add(size)?;
+    if offset < DRAM_BASE || end > DRAM_BASE + DRAM_SIZE {
+        return None;
+    }
+
+    let data = emu.cpu.bus.get_dram_slice(offset..end).ok()?;
+    Some(Bytes::copy_from_slice(data))
+}
+
+/// Reverts the current frame, as its syscall input lies outside of the RISC-V DRAM
+fn out_of_bounds_revert(interpreter: &Interpreter, offset: u64, size: u64) -> InterpreterAction {
+    warn!(
+        "Syscall input out of DRAM bounds: offset {:#x}, size {}",
+        offset, size
+    );
+    InterpreterAction::Return {
+        result: InterpreterResult {
+            result: InstructionResult::Revert,
+            output: Bytes::new(),
+            gas: interpreter.gas,
+        },
+    }
+}
+
+/// Returns the range of the return data copied by `RETURNDATACOPY`, or `None` if it reaches past
+/// the `len` bytes of the buffer
+fn return_data_range(len: usize, offset: u64, size: u64) -> Option<Range<usize>> {
+    let end = offset.checked_add(size)?;
+    (end <= len as u64).then_some(offset as usize..end as usize)
+}
+
+/// Halts the current frame, as it copied past the end of the return data. Like the EVM (EIP-211),
+/// this consumes all of its gas, rather than reverting.
+fn return_data_out_of_bounds(
+    interpreter: &mut Interpreter,
+    offset: u64,
+    size: u64,
+) -> InterpreterAction {
+    warn!(
+        "RETURNDATACOPY out of bounds: offset {}, size {}, buffer of {} bytes",
+        offset,
+        size,
+        interpreter.return_data_buffer.len()
+    );
+    let _ = interpreter.gas.record_cost(interpreter.gas.remaining());
+    InterpreterAction::Return {
+        result: InterpreterResult {
+            result: InstructionResult::OutOfOffset,
+            output: Bytes::new(),
+            gas: interpreter.gas,
+        },
+    }
+}
+
+/// Halts the current frame, as it attempted to modify the state within a static context
+fn static_state_change(interpreter: &mut Interpreter) -> InterpreterAction {
+    warn!("State change during a static call");
+    let _ = interpreter.gas.record_cost(interpreter.gas.remaining());
+    InterpreterAction::Return {
+        result: InterpreterResult {
+            result: InstructionResult::StateChangeDuringStaticCall,
+            output: Bytes::new(),
+            gas: interpreter.gas,
+        },
+    }
+}
+
 /// Returns RISC-V DRAM slice in a given size range, starts with a given offset
 fn dram_slice(emu: &mut Emulator, ret_offset: u64, ret_size: u64) -> Result<&mut [u8]> {
     if ret_size != 0 {
@@ -690,33 +1684,191 @@ fn dram_slice(emu: &mut Emulator, ret_offset: u64, ret_size: u64) -> Result<&mut
     }
 }
 
-fn r55_gas_used(inst_count: &BTreeMap<String, u64>) -> u64 {
-    let total_cost = inst_count
-        .iter()
-        .map(|(inst_name, count)|
-            // Gas cost = number of instructions * cycles per instruction
-            match inst_name.as_str() {
-                // Gas map to approximate cost of each instruction
-                // References:
-                // http://ithare.com/infographics-operation-costs-in-cpu-clock-cycles/
-                // https://www.evm.codes/?fork=cancun#54
-                // Division and remainder
-                s if s.starts_with("div") || s.starts_with("rem") => count * 25,
-                // Multiplications
-                s if s.starts_with("mul") => count * 5,
-                // Loads
-                "lb" | "lh" | "lw" | "ld" | "lbu" | "lhu" | "lwu" => count * 3, // Cost analagous to `MLOAD`
-                // Stores
-                "sb" | "sh" | "sw" | "sd" | "sc.w" | "sc.d" => count * 3, // Cost analagous to `MSTORE`
-                // Branching
-                "beq" | "bne" | "blt" | "bge" | "bltu" | "bgeu" | "jal" | "jalr" => count * 3,
-                _ => *count, // All other instructions including `add` and `sub`
-        })
-        .sum::<u64>();
+/// Copies the output of a frame out of its DRAM.
+///
+/// The returned bytes are owned, so they outlive the emulator: once the frame returns, its DRAM is
+/// dropped, and sibling frames run on their own emulators.
+fn dram_bytes(emu: &mut Emulator, offset: u64, size: u64) -> Result<Bytes> {
+    Ok(Bytes::copy_from_slice(dram_slice(emu, offset, size)?))
+}
+
+/// Returns the gas of the RISC-V instructions executed so far, and of the memory grown by the frame
+fn r55_gas_used(
+    emu: &mut Emulator,
+    memory: &MemoryUsage,
+    gas_schedule: &GasSchedule,
+) -> Result<u64> {
+    let pages = memory.peak_growth(emu)?;
+    let inst_gas = gas_schedule
+        .cost(&emu.cpu.inst_counter)
+        .saturating_sub(gas::ABI_DECODE_COST);
+    debug!("> Memory growth: {} pages", pages);
+
+    Ok(inst_gas.saturating_add(gas::memory_expansion_cost(pages)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_call_inputs_addresses() {
+        let caller = address!("00000000000000000000000000000000000000C0");
+        let target = address!("00000000000000000000000000000000000000CA");
+
+        for is_static in [false, true] {
+            let inputs = call_inputs(caller, target, U256::ZERO, Bytes::new(), 1_000, is_static);
+
+            // Code and storage both belong to the callee
+            assert_eq!(inputs.bytecode_address, inputs.target_address);
+            assert_eq!(inputs.target_address, target);
+            assert_eq!(inputs.caller, caller);
+            assert_eq!(inputs.is_static, is_static);
+            assert_eq!(
+                inputs.scheme,
+                if is_static {
+                    CallScheme::StaticCall
+                } else {
+                    CallScheme::Call
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_delegate_call_inputs_addresses() {
+        let caller = address!("00000000000000000000000000000000000000C0");
+        let proxy = address!("00000000000000000000000000000000000000DE");
+        let logic = address!("00000000000000000000000000000000000000CA");
+        let value = U256::from(42);
+
+        let inputs = delegate_call_inputs(caller, proxy, logic, value, Bytes::new(), 1_000, false);
+
+        // Code belongs to the logic contract, but storage, sender and value to the proxy frame
+        assert_eq!(inputs.bytecode_address, logic);
+        assert_eq!(inputs.target_address, proxy);
+        assert_eq!(inputs.caller, caller);
+        assert_eq!(inputs.value, CallValue::Apparent(value));
+        assert_eq!(inputs.scheme, CallScheme::DelegateCall);
+    }
 
-    // This is the minimum 'gas used' to ABI decode 'empty' calldata into Rust type arguments. Real calldata will take more gas.
-    // Internalising this would focus gas metering more on the function logic
-    let abi_decode_cost = 9_175_538;
+    #[test]
+    fn test_call_input_bounds() {
+        let mut emu = Emulator::new();
 
-    total_cost - abi_decode_cost
+        assert_eq!(call_input(&mut emu, 0, 0), Some(Bytes::new()));
+        assert_eq!(
+            call_input(&mut emu, DRAM_BASE, 4),
+            Some(Bytes::from([0u8; 4]))
+        );
+
+        // Ranges outside of the DRAM (or overflowing) are rejected
+        assert_eq!(call_input(&mut emu, 0, 4), None);
+        assert_eq!(call_input(&mut emu, DRAM_BASE, DRAM_SIZE + 1), None);
+        assert_eq!(call_input(&mut emu, DRAM_BASE + 4, u64::MAX), None);
+    }
+
+    #[test]
+    fn test_return_data_range() {
+        assert_eq!(return_data_range(0, 0, 0), Some(0..0));
+        assert_eq!(return_data_range(64, 32, 32), Some(32..64));
+
+        // Copies past the end of the return data (or overflowing) are rejected
+        assert_eq!(return_data_range(64, 33, 32), None);
+        assert_eq!(return_data_range(64, 65, 0), None);
+        assert_eq!(return_data_range(64, 32, u64::MAX), None);
+    }
+
+    #[test]
+    fn test_split_init_code() {
+        let code = [0xFF, 0x7F, 0x45, 0x4C, 0x46];
+        let init_code = |args: &[u8]| {
+            let code_size = (code.len() as u32).to_be_bytes();
+            [&[0xFF], &code_size[..], &code, args].concat()
+        };
+
+        // No constructor args
+        let no_args = init_code(&[]);
+        assert_eq!(split_init_code(&no_args), Some((&code[1..], &[][..])));
+
+        // Multi-word args, e.g. `(address, uint256, bool)`
+        let args = (Address::repeat_byte(0xAA), U256::from(42), true).abi_encode_params();
+        assert_eq!(args.len(), 96);
+        let with_args = init_code(&args);
+        assert_eq!(split_init_code(&with_args), Some((&code[1..], &args[..])));
+
+        // Malformed init code: missing control bytes, or a code size beyond its length
+        assert_eq!(split_init_code(&no_args[1..]), None);
+        assert_eq!(split_init_code(&[0xFF, 0, 0, 0, 9, 0xFF]), None);
+        assert_eq!(split_init_code(&[0xFF, 0, 0, 0, 0, 0xFF]), None);
+    }
+
+    #[test]
+    fn test_memory_usage() {
+        let mut emu = Emulator::new();
+        let layout = MemoryLayout {
+            heap: DRAM_BASE + 64,
+            stack_top: DRAM_BASE + 16 * gas::PAGE_SIZE,
+        };
+
+        // Frames without a layout aren't metered
+        let mut memory = MemoryUsage::new(None);
+        memory.record_stack(&emu);
+        assert_eq!(memory.peak_growth(&mut emu).unwrap(), 0);
+
+        let mut memory = MemoryUsage::new(Some(layout));
+        assert_eq!(memory.peak_growth(&mut emu).unwrap(), 0);
+
+        // The heap is metered by the bytes handed out by the allocator
+        let heap = (gas::PAGE_SIZE + 1).to_le_bytes();
+        dram_slice(&mut emu, layout.heap, 8)
+            .unwrap()
+            .copy_from_slice(&heap);
+        assert_eq!(memory.peak_growth(&mut emu).unwrap(), 2);
+
+        // The stack is metered by the lowest stack pointer, even once it shrinks back
+        for sp in [3 * gas::PAGE_SIZE, gas::PAGE_SIZE / 2, 2 * gas::PAGE_SIZE] {
+            emu.cpu.xregs.write(2, layout.stack_top - sp);
+            memory.record_stack(&emu);
+        }
+        assert_eq!(memory.peak_growth(&mut emu).unwrap(), 5);
+
+        // A corrupted heap counter is capped to the DRAM size, and runs out of gas
+        dram_slice(&mut emu, layout.heap, 8)
+            .unwrap()
+            .copy_from_slice(&u64::MAX.to_le_bytes());
+        let pages = DRAM_SIZE.div_ceil(gas::PAGE_SIZE) + 3;
+        assert_eq!(memory.peak_growth(&mut emu).unwrap(), pages);
+        let gas_used = r55_gas_used(&mut emu, &memory, &GasSchedule::default()).unwrap();
+        assert!(gas_used >= gas::memory_expansion_cost(pages));
+    }
+
+    #[test]
+    fn test_reentrancy_tracker() {
+        let (a, b) = (
+            address!("000000000000000000000000000000000000000A"),
+            address!("000000000000000000000000000000000000000B"),
+        );
+        let mut tracker = ReentrancyTracker::default();
+
+        // a -> b -> a (delegate) -> a
+        tracker.enter(a, true);
+        tracker.enter(b, true);
+        tracker.enter(a, false);
+        tracker.enter(a, true);
+        assert_eq!(
+            tracker.reentrancies(),
+            [Reentrancy {
+                address: a,
+                depth: 3
+            }]
+        );
+
+        // Returned frames are no longer active
+        for _ in 0..3 {
+            tracker.exit();
+        }
+        tracker.enter(b, true);
+        assert_eq!(tracker.reentrancies().len(), 1);
+    }
 }
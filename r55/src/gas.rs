@@ -1,4 +1,10 @@
+use serde::Deserialize;
+use std::{collections::BTreeMap, fs, path::PathBuf, sync::OnceLock};
+use tracing::warn;
+
 // Standard EVM operation costs
+pub const BASE: u64 = 2;
+pub const VERYLOW: u64 = 3;
 pub const SLOAD_COLD: u64 = 2100;
 pub const SLOAD_WARM: u64 = 100;
 pub const SSTORE_COLD: u64 = 2200;
@@ -10,9 +16,213 @@ pub const CALL_NEW_ACCOUNT: u64 = 2600;
 pub const CALL_VALUE: u64 = 9000;
 pub const CALL_BASE: u64 = 100;
 
+// Block-related costs
+pub const BLOCKHASH: u64 = 20;
+
 // Create-related costs
 pub const CREATE_BASE: u64 = 32000;
 
+// Memory-related costs, applied (like for the EVM memory) to 32-byte words
+pub const MEMORY_WORD: u64 = 3;
+pub const MEMORY_QUADRATIC_DENOMINATOR: u64 = 512;
+
+// Copy-related costs (e.g. `RETURNDATACOPY`), per 32-byte word copied
+pub const COPY_WORD: u64 = 3;
+
+// Granularity of the DRAM usage metering
+pub const PAGE_SIZE: u64 = 4096;
+
+// This is the minimum 'gas used' to ABI decode 'empty' calldata into Rust type arguments. Real calldata will take more gas.
+// Internalising this would focus gas metering more on the function logic
+pub const ABI_DECODE_COST: u64 = 9_175_538;
+
+/// Per-instruction gas multipliers used to meter RISC-V execution.
+///
+/// Defaults approximate the cost of each instruction in CPU cycles, and can be tuned
+/// through the `[gas]` table of `r55.toml` without recompiling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct GasSchedule {
+    /// Division and remainder (`div*`, `rem*`)
+    pub div_rem: u64,
+    /// Multiplications (`mul*`)
+    pub mul: u64,
+    /// Loads, analogous to `MLOAD`
+    pub load: u64,
+    /// Stores, analogous to `MSTORE`
+    pub store: u64,
+    /// Branches and jumps
+    pub branch: u64,
+    /// All other instructions, including `add` and `sub`
+    pub other: u64,
+}
+
+impl Default for GasSchedule {
+    fn default() -> Self {
+        Self {
+            div_rem: 25,
+            mul: 5,
+            load: 3,
+            store: 3,
+            branch: 3,
+            other: 1,
+        }
+    }
+}
+
+/// Schedule of the closest `r55.toml`, loaded by the first [`GasSchedule::load`]
+static LOADED_SCHEDULE: OnceLock<GasSchedule> = OnceLock::new();
+
+#[derive(Deserialize)]
+struct GasConfig {
+    #[serde(default)]
+    gas: GasSchedule,
+}
+
+impl GasSchedule {
+    /// Returns the gas cost of the executed RISC-V instructions, given their execution counts.
+    pub fn cost(&self, inst_counts: &BTreeMap<String, u64>) -> u64 {
+        inst_counts
+            .iter()
+            .map(|(inst_name, count)|
+                // Gas cost = number of instructions * cycles per instruction
+                match inst_name.as_str() {
+                    // Gas map to approximate cost of each instruction
+                    // References:
+                    // http://ithare.com/infographics-operation-costs-in-cpu-clock-cycles/
+                    // https://www.evm.codes/?fork=cancun#54
+                    // Division and remainder
+                    s if s.starts_with("div") || s.starts_with("rem") => count * self.div_rem,
+                    // Multiplications
+                    s if s.starts_with("mul") => count * self.mul,
+                    // Loads
+                    "lb" | "lh" | "lw" | "ld" | "lbu" | "lhu" | "lwu" => count * self.load, // Cost analagous to `MLOAD`
+                    // Stores
+                    "sb" | "sh" | "sw" | "sd" | "sc.w" | "sc.d" => count * self.store, // Cost analagous to `MSTORE`
+                    // Branching
+                    "beq" | "bne" | "blt" | "bge" | "bltu" | "bgeu" | "jal" | "jalr" => count * self.branch,
+                    _ => count * self.other, // All other instructions including `add` and `sub`
+            })
+            .sum::<u64>()
+    }
+
+    /// Loads the `[gas]` table from the closest `r55.toml`, falling back to the defaults.
+    ///
+    /// The file is only looked up and parsed once, and its schedule is shared by later txs.
+    pub fn load() -> Self {
+        *LOADED_SCHEDULE.get_or_init(|| {
+            let Some(path) = find_config_file() else {
+                return Self::default();
+            };
+
+            match fs::read_to_string(&path).map(|content| Self::from_toml(&content)) {
+                Ok(Ok(schedule)) => schedule,
+                Ok(Err(err)) => {
+                    warn!("Invalid gas schedule in {}: {err}", path.display());
+                    Self::default()
+                }
+                Err(err) => {
+                    warn!("Unable to read {}: {err}", path.display());
+                    Self::default()
+                }
+            }
+        })
+    }
+
+    /// Parses the `[gas]` table of an `r55.toml`, whose missing entries keep their defaults
+    pub fn from_toml(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str::<GasConfig>(content).map(|config| config.gas)
+    }
+}
+
+/// Returns the gas cost of growing the memory of a frame by `pages` DRAM pages.
+///
+/// Follows the quadratic EVM memory cost, so that large allocations get increasingly expensive.
+/// Saturates on overflow, so that the frame runs out of gas.
+pub fn memory_expansion_cost(pages: u64) -> u64 {
+    let words = pages.saturating_mul(PAGE_SIZE / 32);
+    words
+        .saturating_mul(MEMORY_WORD)
+        .saturating_add(words.saturating_mul(words) / MEMORY_QUADRATIC_DENOMINATOR)
+}
+
+/// Returns the gas cost of copying `size` bytes into memory, like the EVM `*COPY` opcodes:
+/// `3 + 3 * ceil(size / 32)`.
+pub fn copy_cost(size: u64) -> u64 {
+    VERYLOW + COPY_WORD * size.div_ceil(32)
+}
+
+/// Gas used by the calls to a method, aggregated over the txs of a [`GasReport`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MethodGas {
+    pub calls: u64,
+    pub total: u64,
+    pub min: u64,
+    pub max: u64,
+}
+
+impl MethodGas {
+    /// Average gas used per call
+    pub fn mean(&self) -> u64 {
+        self.total / self.calls
+    }
+}
+
+/// Gas used by successful calls, keyed by the selector of the called method.
+///
+/// Collected per test thread, between `exec::start_gas_report` and `exec::take_gas_report`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GasReport {
+    methods: BTreeMap<[u8; 4], MethodGas>,
+}
+
+impl GasReport {
+    /// Accounts a call to the method with `selector` that used `gas_used`
+    pub fn record(&mut self, selector: [u8; 4], gas_used: u64) {
+        self.methods
+            .entry(selector)
+            .and_modify(|method| {
+                method.calls += 1;
+                method.total += gas_used;
+                method.min = method.min.min(gas_used);
+                method.max = method.max.max(gas_used);
+            })
+            .or_insert(MethodGas {
+                calls: 1,
+                total: gas_used,
+                min: gas_used,
+                max: gas_used,
+            });
+    }
+
+    /// Gas used by the method with `selector`, if it was called
+    pub fn get(&self, selector: [u8; 4]) -> Option<&MethodGas> {
+        self.methods.get(&selector)
+    }
+
+    /// Iterates over the called methods, ordered by selector
+    pub fn iter(&self) -> impl Iterator<Item = (&[u8; 4], &MethodGas)> {
+        self.methods.iter()
+    }
+
+    /// Returns the method with the highest average gas per call
+    pub fn heaviest(&self) -> Option<([u8; 4], MethodGas)> {
+        self.methods
+            .iter()
+            .max_by_key(|(_, method)| method.mean())
+            .map(|(selector, method)| (*selector, *method))
+    }
+}
+
+/// Finds `r55.toml` in the current directory or its parent directories
+fn find_config_file() -> Option<PathBuf> {
+    let current_dir = std::env::current_dir().ok()?;
+    current_dir
+        .ancestors()
+        .map(|dir| dir.join("r55.toml"))
+        .find(|path| path.exists())
+}
+
 // Macro to handle gas accounting for syscalls.
 // Returns OutOfGas InterpreterResult if gas limit is exceeded.
 #[macro_export]
@@ -41,3 +251,108 @@ macro_rules! syscall_gas {
         trace!("  - Gas spent: {}", $interpreter.gas.spent());
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inst_counts(counts: &[(&str, u64)]) -> BTreeMap<String, u64> {
+        counts
+            .iter()
+            .map(|(name, count)| (name.to_string(), *count))
+            .collect()
+    }
+
+    #[test]
+    fn test_default_schedule_cost() {
+        let counts = inst_counts(&[
+            ("divu", 2),
+            ("remw", 1),
+            ("mul", 4),
+            ("ld", 10),
+            ("sd", 5),
+            ("beq", 3),
+            ("add", 7),
+            ("addi", 8),
+        ]);
+
+        // div/rem: 3 * 25, mul: 4 * 5, loads: 10 * 3, stores: 5 * 3, branches: 3 * 3, others: 15 * 1
+        let expected = 75 + 20 + 30 + 15 + 9 + 15;
+        assert_eq!(GasSchedule::default().cost(&counts), expected);
+    }
+
+    #[test]
+    fn test_custom_schedule_cost() {
+        let counts = inst_counts(&[("div", 1), ("lw", 2), ("sub", 3)]);
+        let schedule = GasSchedule {
+            div_rem: 40,
+            load: 1,
+            other: 2,
+            ..Default::default()
+        };
+
+        assert_eq!(schedule.cost(&counts), 40 + 2 + 6);
+        assert_eq!(schedule.cost(&BTreeMap::new()), 0);
+    }
+
+    #[test]
+    fn test_schedule_from_toml() {
+        let config = r#"
+            src = ["contracts"]
+
+            [gas]
+            div_rem = 40
+            load = 1
+        "#;
+        let expected = GasSchedule {
+            div_rem: 40,
+            load: 1,
+            ..Default::default()
+        };
+        assert_eq!(GasSchedule::from_toml(config).unwrap(), expected);
+
+        // Without a `[gas]` table, the defaults apply
+        assert_eq!(
+            GasSchedule::from_toml("src = [\"contracts\"]").unwrap(),
+            GasSchedule::default()
+        );
+        assert!(GasSchedule::from_toml("[gas]\ndiv_rem = \"fast\"").is_err());
+    }
+
+    #[test]
+    fn test_memory_expansion_cost() {
+        assert_eq!(memory_expansion_cost(0), 0);
+        // 1 page = 128 words: 128 * 3 + 128^2 / 512
+        assert_eq!(memory_expansion_cost(1), 384 + 32);
+        // Growth is quadratic
+        assert!(memory_expansion_cost(64) > 64 * memory_expansion_cost(1));
+        // Overflows saturate, rather than wrapping to a tiny charge
+        assert_eq!(memory_expansion_cost(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn test_copy_cost() {
+        assert_eq!(copy_cost(0), 3);
+        assert_eq!(copy_cost(1), 6);
+        assert_eq!(copy_cost(32), 6);
+        assert_eq!(copy_cost(33), 9);
+        assert_eq!(copy_cost(1024), 3 + 32 * 3);
+    }
+
+    #[test]
+    fn test_gas_report() {
+        let mut report = GasReport::default();
+        report.record([1; 4], 100);
+        report.record([1; 4], 300);
+        report.record([2; 4], 250);
+
+        let method = report.get([1; 4]).unwrap();
+        assert_eq!((method.calls, method.total), (2, 400));
+        assert_eq!((method.min, method.max, method.mean()), (100, 300, 200));
+        assert_eq!(
+            report.heaviest().map(|(selector, _)| selector),
+            Some([2; 4])
+        );
+        assert!(report.get([3; 4]).is_none());
+    }
+}
@@ -1,6 +1,7 @@
 mod error;
 pub mod exec;
 mod gas;
+pub use gas::{GasReport, GasSchedule, MethodGas};
 
 mod generated;
 pub use generated::get_bytecode;
@@ -10,9 +11,10 @@ pub mod test_utils;
 #[cfg(test)]
 mod tests {
     use crate::{
-        exec::{deploy_contract, run_tx},
+        exec::{deploy_contract, run_tx, run_tx_with_gas_schedule},
         get_bytecode,
         test_utils::*,
+        GasSchedule,
     };
 
     use alloy_core::hex::{self, ToHexExt};
@@ -42,6 +44,75 @@ mod tests {
         deploy_contract(db, bytecode, None).unwrap()
     }
 
+    #[test]
+    fn test_get_bytecode_name_normalization() {
+        // Package names are hyphenated, but lookups with underscores work as well
+        let bytecode = get_bytecode("evm-caller");
+        assert!(!bytecode.is_empty(), "Bytecode not found");
+        assert_eq!(get_bytecode("evm_caller"), bytecode);
+
+        assert!(get_bytecode("unknown").is_empty());
+    }
+
+    #[test]
+    fn test_deploy_constructor_args() {
+        let (mut db, _) = setup_erc20(ALICE);
+        let erc20x = setup_erc20x(&mut db);
+
+        // Args matching the ERC20 constructor deploy the token
+        let selector_x_deploy = get_selector_from_sig("x_deploy(address)");
+        let calldata_x_deploy = get_calldata(selector_x_deploy, ALICE.abi_encode());
+        let result = run_tx(&mut db, &erc20x, calldata_x_deploy, &ALICE).unwrap();
+        let (token, _) = <(Address, Address)>::abi_decode(&result.output, true).unwrap();
+        assert!(has_code(&mut db, token), "Token not deployed");
+
+        // Mismatched args revert before deploying anything
+        let selector_x_deploy_mismatched = get_selector_from_sig("x_deploy_mismatched(uint256)");
+        let calldata_x_deploy_mismatched =
+            get_calldata(selector_x_deploy_mismatched, U256::from(1).abi_encode());
+        let err = run_tx(&mut db, &erc20x, calldata_x_deploy_mismatched, &ALICE)
+            .expect_err("Deployment succeeded with mismatched constructor args");
+        assert!(
+            err.matches_string_error("Constructor args mismatch"),
+            "Unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_deployed_contract_nonce() {
+        let (mut db, _) = setup_erc20(ALICE);
+        let erc20x = setup_erc20x(&mut db);
+
+        // Like in the EVM (EIP-161), contracts start with nonce 1
+        let nonce = |db: &mut InMemoryDB, addr| account_info(db, addr).unwrap().nonce;
+        assert_eq!(nonce(&mut db, erc20x), 1);
+
+        // So the first child created by a factory is derived from nonce 1
+        let selector_x_deploy = get_selector_from_sig("x_deploy(address)");
+        let calldata_x_deploy = get_calldata(selector_x_deploy, ALICE.abi_encode());
+        let result = run_tx(&mut db, &erc20x, calldata_x_deploy, &ALICE).unwrap();
+        let (token, _) = <(Address, Address)>::abi_decode(&result.output, true).unwrap();
+        assert_eq!(token, erc20x.create(1));
+
+        assert_eq!(nonce(&mut db, erc20x), 2);
+        assert_eq!(nonce(&mut db, token), 1);
+    }
+
+    #[test]
+    fn test_has_code() {
+        let (mut db, erc20) = setup_erc20(ALICE);
+
+        assert!(has_code(&mut db, erc20), "Deployed contract has no code");
+        assert!(!has_code(&mut db, ALICE), "EOA has code");
+        assert!(account_info(&mut db, ALICE).is_some_and(|info| !info.balance.is_zero()));
+
+        // Unknown accounts don't exist
+        let unknown = Address::repeat_byte(0xff);
+        assert!(account_info(&mut db, unknown).is_none());
+        assert!(!has_code(&mut db, unknown));
+    }
+
     #[test]
     fn test_runtime() {
         let (mut db, erc20) = setup_erc20(ALICE);
@@ -154,6 +225,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_gas_schedule() {
+        let (mut db, erc20) = setup_erc20(ALICE);
+
+        let selector_balance = get_selector_from_sig("balance_of(address)");
+        let calldata_balance = get_calldata(selector_balance, ALICE.abi_encode());
+
+        // Run the same (read-only) function under two different gas schedules
+        let default_schedule = GasSchedule::default();
+        let expensive_schedule = GasSchedule {
+            other: default_schedule.other * 2,
+            ..default_schedule
+        };
+
+        let default_result = run_tx_with_gas_schedule(
+            &mut db,
+            &erc20,
+            calldata_balance.clone(),
+            &ALICE,
+            default_schedule,
+        )
+        .expect("Error executing tx");
+        let expensive_result = run_tx_with_gas_schedule(
+            &mut db,
+            &erc20,
+            calldata_balance,
+            &ALICE,
+            expensive_schedule,
+        )
+        .expect("Error executing tx");
+
+        assert_eq!(
+            default_result.output, expensive_result.output,
+            "Gas schedule must not affect the output"
+        );
+        assert!(
+            expensive_result.gas_used > default_result.gas_used,
+            "Expected more gas with a pricier schedule: {} <= {}",
+            expensive_result.gas_used,
+            default_result.gas_used
+        );
+    }
+
+    #[test]
+    fn test_halt_reason() {
+        let (mut db, erc20) = setup_erc20(ALICE);
+
+        let selector_balance = get_selector_from_sig("balance_of(address)");
+        let calldata_balance = get_calldata(selector_balance, ALICE.abi_encode());
+
+        // Such a schedule exhausts the gas limit of the tx, which halts
+        let prohibitive_schedule = GasSchedule {
+            other: 1_000_000,
+            ..Default::default()
+        };
+        let err = run_tx_with_gas_schedule(
+            &mut db,
+            &erc20,
+            calldata_balance,
+            &ALICE,
+            prohibitive_schedule,
+        )
+        .expect_err("Tx succeeded despite running out of gas");
+
+        let msg = err.to_string();
+        assert!(msg.contains("HALT"), "Halt not reported: {}", msg);
+        assert!(
+            msg.contains("reason: out of gas"),
+            "Unexpected reason: {}",
+            msg
+        );
+    }
+
     #[test]
     fn test_transfer_logs() {
         let (mut db, erc20) = setup_erc20(ALICE);
@@ -210,6 +354,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cross_contract_logs_order() {
+        let (mut db, _) = setup_erc20(ALICE);
+        let erc20x = setup_erc20x(&mut db);
+
+        // Deploy a token owned by ERC20x, so that it can mint
+        let selector_x_deploy = get_selector_from_sig("x_deploy(address)");
+        let calldata_x_deploy = get_calldata(selector_x_deploy, erc20x.abi_encode());
+        let result = run_tx(&mut db, &erc20x, calldata_x_deploy, &ALICE).unwrap();
+        let (token, _) = <(Address, Address)>::abi_decode(&result.output, true).unwrap();
+
+        // call traces: erc20x.x_mint_logged() -> token.mint()
+        let value_mint = U256::from(42e18);
+        let selector = get_selector_from_sig("x_mint_logged(address,uint256,address)");
+        let calldata = get_calldata(selector, (BOB, value_mint, token).abi_encode());
+        let result = run_tx(&mut db, &erc20x, calldata, &ALICE).expect("Error executing tx");
+        assert!(bool::abi_decode(&result.output, true).unwrap(), "Mint failed");
+
+        // Logs are collected in execution order, each one with the address of its emitter
+        let cross_mint = keccak256("CrossMint(address,bool)");
+        let transfer = keccak256("Transfer(address,address,uint256)");
+        let logs: Vec<_> = result
+            .logs
+            .iter()
+            .map(|log| (log.address, log.topics()[0]))
+            .collect();
+        assert_eq!(
+            logs,
+            vec![(erc20x, cross_mint), (token, transfer), (erc20x, cross_mint)]
+        );
+
+        let done = |i: usize| bool::abi_decode(&result.logs[i].data.data, true).unwrap();
+        assert!(!done(0) && done(2), "Incorrect log order");
+        assert_eq!(
+            U256::abi_decode(&result.logs[1].data.data, true).unwrap(),
+            value_mint
+        );
+    }
+
     #[test]
     fn test_storage_layout() {
         let (mut db, erc20) = setup_erc20(ALICE);
@@ -240,10 +423,10 @@ mod tests {
         // EXPECTED STORAGE LAYOUT:
         //
         // pub struct ERC20 {
-        //     total_supply: Slot<U256>,                                Slot: 0
-        //     balances: Mapping<Address, U256>,                        Slot: keccak256(address, 1)
-        //     allowances: Mapping<Address, Mapping<Address, U256>>,    Slot: keccak256(address, keccak256(address, 2))
-        //     owner: Slot<Address>,                                    Slot: 3
+        //     total_supply: Slot<U256>,                                        Slot: 0
+        //     balances: Mapping<Address, Slot<U256>>,                          Slot: keccak256(address, 1)
+        //     allowances: Mapping<Address, Mapping<Address, Slot<U256>>>,      Slot: keccak256(address, keccak256(address, 2))
+        //     owner: Slot<Address>,                                            Slot: 3
         // }
 
         // Assert `total_supply` is set to track the correct slot
@@ -418,6 +601,223 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_wrapped_error_with_cross_contract_call() {
+        let (mut db, erc20) = setup_erc20(ALICE);
+        let erc20x = setup_erc20x(&mut db);
+
+        let selector_x_mint_wrapped =
+            get_selector_from_sig("x_mint_wrapped(address,uint256,address)");
+        let value_mint = U256::from(42e18);
+
+        // ERC20x reverts with its own error
+        let calldata_x_mint_wrapped = get_calldata(
+            selector_x_mint_wrapped,
+            (BOB, value_mint, Address::ZERO).abi_encode(),
+        );
+
+        let zero_token_result = run_tx(&mut db, &erc20x, calldata_x_mint_wrapped, &BOB)
+            .expect_err("Mint transaction succeeded");
+        assert!(
+            zero_token_result.matches_custom_error("ERC20xError::ZeroToken"),
+            "Incorrect error"
+        );
+
+        // ERC20x wraps the error of the ERC20 (not the contract owner), and reverts with the original
+        let calldata_x_mint_wrapped = get_calldata(
+            selector_x_mint_wrapped,
+            (BOB, value_mint, erc20).abi_encode(),
+        );
+
+        let only_owner_result = run_tx(&mut db, &erc20x, calldata_x_mint_wrapped, &BOB)
+            .expect_err("Mint transaction succeeded");
+        assert!(
+            only_owner_result.matches_custom_error("ERC20Error::OnlyOwner"),
+            "Incorrect error"
+        );
+    }
+
+    #[test]
+    fn test_revert_data_forwarding_with_cross_contract_call() {
+        let (mut db, erc20) = setup_erc20(ALICE);
+        let erc20x = setup_erc20x(&mut db);
+
+        // Define fn selectors
+        let selector_mint = get_selector_from_sig("mint(address,uint256)");
+        let selector_approve = get_selector_from_sig("approve(address,uint256)");
+        let selector_balance_of = get_selector_from_sig("balance_of(address)");
+        let selector_allowance = get_selector_from_sig("allowance(address,address)");
+        let selector_x_transfer_from =
+            get_selector_from_sig("x_transfer_from(address,uint256,address)");
+
+        // Mint 42 tokens to Alice
+        let value_mint = U256::from(42e18);
+        let calldata_mint = get_calldata(selector_mint, (ALICE, value_mint).abi_encode());
+
+        let mint_result = run_tx(&mut db, &erc20, calldata_mint, &ALICE).unwrap();
+        assert!(mint_result.status, "Mint transaction failed");
+
+        // Approve ERC20x to spend 7 tokens from Alice
+        let value_approve = U256::from(7e18);
+        let calldata_approve = get_calldata(selector_approve, (erc20x, value_approve).abi_encode());
+
+        let approve_result = run_tx(&mut db, &erc20, calldata_approve, &ALICE).unwrap();
+        assert!(approve_result.status, "Approve transaction failed");
+
+        // Cross-transfer 30 tokens from Alice to Bob. The nested call reverts with
+        // `InsufficientAllowance(7)`, and ERC20x retries with the decoded `max` value.
+        let value_transfer = U256::from(30e18);
+        let calldata_x_transfer_from = get_calldata(
+            selector_x_transfer_from,
+            (ALICE, value_transfer, erc20).abi_encode(),
+        );
+
+        let x_transfer_result =
+            run_tx(&mut db, &erc20x, calldata_x_transfer_from, &BOB).expect("Error executing tx");
+        assert!(
+            x_transfer_result.status,
+            "Cross-transfer from transaction failed"
+        );
+
+        // Only the retried transfer emits a log, and it must use the `max` amount
+        assert_eq!(x_transfer_result.logs.len(), 1, "Incorrect number of logs");
+        let amount = U256::from_be_slice(&x_transfer_result.logs[0].data.data[..32]);
+        assert_eq!(amount, value_approve, "Incorrect retried transfer amount");
+
+        // Check balances and remaining allowance
+        let balance_of = |db: &mut InMemoryDB, owner: Address| {
+            let calldata = get_calldata(selector_balance_of, owner.abi_encode());
+            let output = run_tx(db, &erc20, calldata, &ALICE)
+                .expect("Error executing tx")
+                .output;
+            U256::from_be_slice(output.as_slice())
+        };
+        assert_eq!(balance_of(&mut db, BOB), value_approve, "Incorrect balance");
+        assert_eq!(
+            balance_of(&mut db, ALICE),
+            value_mint - value_approve,
+            "Incorrect balance"
+        );
+
+        let calldata_allowance = get_calldata(selector_allowance, (ALICE, erc20x).abi_encode());
+        let allowance_result = run_tx(&mut db, &erc20, calldata_allowance, &ALICE)
+            .expect("Error executing tx")
+            .output;
+        assert_eq!(
+            U256::from_be_slice(allowance_result.as_slice()),
+            U256::ZERO,
+            "Incorrect allowance"
+        );
+    }
+
+    #[test]
+    fn test_static_call_reads_target_storage() {
+        let (mut db, erc20) = setup_erc20(ALICE);
+        let erc20x = setup_erc20x(&mut db);
+
+        // Mint 42 tokens to Alice
+        let value_mint = U256::from(42e18);
+        let selector_mint = get_selector_from_sig("mint(address,uint256)");
+        let calldata_mint = get_calldata(selector_mint, (ALICE, value_mint).abi_encode());
+
+        let mint_result = run_tx(&mut db, &erc20, calldata_mint, &ALICE).unwrap();
+        assert!(mint_result.status, "Mint transaction failed");
+
+        // Read Alice's balance through ERC20x, which performs a staticcall to the token
+        let selector_x_balance_of = get_selector_from_sig("x_balance_of(address,address)");
+        let calldata_x_balance_of =
+            get_calldata(selector_x_balance_of, (ALICE, erc20).abi_encode());
+
+        let x_balance_result = run_tx(&mut db, &erc20x, calldata_x_balance_of, &ALICE)
+            .expect("Error executing tx")
+            .output;
+        assert_eq!(
+            U256::from_be_slice(x_balance_result.as_slice()),
+            value_mint,
+            "Incorrect balance"
+        );
+
+        // The SLOAD must target the token's storage, not the caller's
+        let balance_slot = get_mapping_slot(ALICE.abi_encode(), U256::from(1));
+        assert_eq!(read_db_slot(&mut db, erc20, balance_slot), value_mint);
+        assert_eq!(read_db_slot(&mut db, erc20x, balance_slot), U256::ZERO);
+    }
+
+    #[test]
+    fn test_string_returning_cross_contract_call() {
+        let (mut db, erc20) = setup_erc20(ALICE);
+        let erc20x = setup_erc20x(&mut db);
+        let name = "R55 Example Token (RISC-V contract)";
+
+        // The name is returned as a dynamic ABI string, spanning two words
+        let selector_name = get_selector_from_sig("name()");
+        let name_result = run_tx(&mut db, &erc20, selector_name.to_vec(), &ALICE)
+            .expect("Error executing tx")
+            .output;
+        assert_eq!(name_result.len(), 32 * 4, "Incorrect string encoding");
+        assert_eq!(String::abi_decode(&name_result, true).unwrap(), name);
+
+        // ERC20x decodes it through the interface, and encodes it back
+        let selector_x_name = get_selector_from_sig("x_name(address)");
+        let calldata_x_name = get_calldata(selector_x_name, erc20.abi_encode());
+        let x_name_result = run_tx(&mut db, &erc20x, calldata_x_name, &ALICE)
+            .expect("Error executing tx")
+            .output;
+        assert_eq!(x_name_result, name_result, "Name not intact");
+    }
+
+    #[test]
+    fn test_return_data_after_reverted_call() {
+        let (mut db, erc20) = setup_erc20(ALICE);
+        let erc20x = setup_erc20x(&mut db);
+
+        // Attempt to cross-mint tokens to Bob (erc20x is not the contract owner)
+        let selector_x_mint_revert_data =
+            get_selector_from_sig("x_mint_revert_data(address,uint256,address)");
+        let calldata = get_calldata(
+            selector_x_mint_revert_data,
+            (BOB, U256::from(1e18), erc20).abi_encode(),
+        );
+
+        // The sub-call reverts, but the caller can still copy its revert data
+        let revert_data = run_tx(&mut db, &erc20x, calldata, &BOB)
+            .expect("Error executing tx")
+            .output;
+
+        let expected_selector = &keccak256("ERC20Error::OnlyOwner")[..4];
+        assert_eq!(
+            revert_data.as_slice(),
+            expected_selector,
+            "Incorrect revert data"
+        );
+    }
+
+    #[test]
+    fn test_return_data_outlives_sibling_call() {
+        let (mut db, erc20) = setup_erc20(ALICE);
+        let erc20x = setup_erc20x(&mut db);
+
+        // Mint 42 tokens to Alice
+        let value_mint = U256::from(42e18);
+        let selector_mint = get_selector_from_sig("mint(address,uint256)");
+        let calldata_mint = get_calldata(selector_mint, (ALICE, value_mint).abi_encode());
+        let mint_result = run_tx(&mut db, &erc20, calldata_mint, &ALICE).unwrap();
+        assert!(mint_result.status, "Mint transaction failed");
+
+        // call traces: erc20x.x_balance_then_mint() -> erc20.balance_of()
+        //                                           -> erc20.mint() (reverts, erc20x is not the owner)
+        let selector = get_selector_from_sig("x_balance_then_mint(address,uint256,address)");
+        let calldata = get_calldata(selector, (ALICE, U256::from(1), erc20).abi_encode());
+        let output = run_tx(&mut db, &erc20x, calldata, &ALICE)
+            .expect("Error executing tx")
+            .output;
+
+        // The output of the first call isn't corrupted by the sibling frame, nor by its revert data
+        let (balance, minted) = <(Bytes, bool)>::abi_decode_params(&output, true).unwrap();
+        assert_eq!(balance.as_ref(), value_mint.abi_encode().as_slice());
+        assert!(!minted, "Mint should fail");
+    }
+
     #[test]
     fn test_string_error() {
         let (mut db, erc20) = setup_erc20(ALICE);
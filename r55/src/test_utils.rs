@@ -1,14 +1,38 @@
-use alloy_core::hex::FromHex;
+use alloy_core::{hex::FromHex, primitives::Keccak256};
 use alloy_primitives::address;
+use r55_compile::compile::{Contract, ContractWithDeps};
 use revm::Database;
 pub use revm::{
-    primitives::{keccak256, ruint::Uint, AccountInfo, Address, Bytecode, Bytes, U256},
+    primitives::{
+        keccak256, ruint::Uint, AccountInfo, Address, Bytecode, Bytes, B256, KECCAK_EMPTY, U256,
+    },
     InMemoryDB,
 };
-use std::{fs, path::Path, sync::Once};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{LazyLock, Mutex, Once},
+};
+
+use crate::{
+    error::{Result, TxResult},
+    exec::deploy_contract,
+    gas::GasReport,
+};
 
 static INIT: Once = Once::new();
 
+// Bytecode compiled by `compile_from_source`, keyed by contract path and hash of its sources
+// (including the bytecode of its dependencies)
+static COMPILED: LazyLock<Mutex<HashMap<(PathBuf, B256), Bytes>>> = LazyLock::new(Default::default);
+
+// Directory of the bytecode embedded by the `contract` macro, relative to the contract manifest
+const BYTECODE_DIR: &str = "../../r55-output-bytecode";
+
 pub const ALICE: Address = address!("000000000000000000000000000000000000000A");
 pub const BOB: Address = address!("000000000000000000000000000000000000000B");
 pub const CAROL: Address = address!("000000000000000000000000000000000000000C");
@@ -40,6 +64,139 @@ pub fn add_contract_to_db(db: &mut InMemoryDB, addr: Address, bytecode: Bytes) {
     db.insert_account_info(addr, account);
 }
 
+/// Account of a state fixture. Numbers are strings, either decimal or `0x`-prefixed hex.
+#[derive(Deserialize)]
+struct FixtureAccount {
+    #[serde(default)]
+    balance: Option<String>,
+    #[serde(default)]
+    nonce: u64,
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    storage: HashMap<String, String>,
+}
+
+/// Bulk-inserts the accounts of a TOML fixture into `db`, to run txs against a pre-populated
+/// state. Each table is an account keyed by its address, whose fields are all optional:
+///
+/// ```toml
+/// ["0x00000000000000000000000000000000000000c0"]
+/// balance = "1000000000000000000"
+/// nonce = 1
+/// code = "0x60006000a000"           # runtime code, inserted as is
+/// storage = { "0x0" = "0x2a" }
+/// ```
+pub fn load_state_fixture<P: AsRef<Path>>(db: &mut InMemoryDB, path: P) {
+    let content = fs::read_to_string(path).expect("Unable to load state fixture from path");
+    let accounts: HashMap<String, FixtureAccount> =
+        toml::from_str(&content).expect("Unable to parse state fixture");
+    let parse_u256 = |value: &str| U256::from_str(value).expect("Invalid fixture number");
+
+    for (addr, account) in accounts {
+        let addr = Address::from_str(&addr).expect("Invalid fixture address");
+
+        let balance = account.balance.as_deref().map_or(U256::ZERO, parse_u256);
+        let mut info = AccountInfo::from_balance(balance);
+        info.nonce = account.nonce;
+        if let Some(code) = account.code {
+            let code = Bytes::from_hex(&code).expect("Invalid fixture code");
+            info.code_hash = keccak256(&code);
+            info.code = Some(Bytecode::new_raw(code));
+        }
+        db.insert_account_info(addr, info);
+
+        for (slot, value) in &account.storage {
+            db.insert_account_storage(addr, parse_u256(slot), parse_u256(value))
+                .expect("Unable to insert fixture storage");
+        }
+    }
+}
+
+/// Returns the info (balance, nonce and code) of `addr`, or `None` if the account doesn't exist
+pub fn account_info(db: &mut InMemoryDB, addr: Address) -> Option<AccountInfo> {
+    db.basic(addr).expect("Unable to read account info")
+}
+
+/// Returns whether `addr` holds code, i.e. it is a deployed contract rather than an EOA
+pub fn has_code(db: &mut InMemoryDB, addr: Address) -> bool {
+    account_info(db, addr).is_some_and(|info| info.code_hash != KECCAK_EMPTY)
+}
+
+/// Synthetic hash of block `number`, as seeded by [`seed_block_hashes`]
+pub fn synthetic_block_hash(number: u64) -> B256 {
+    keccak256(format!("block {}", number))
+}
+
+/// Seeds the hashes of the 256 blocks preceding `number` (the ones reachable by `BLOCKHASH`), as
+/// if the chain had been running until then
+pub fn seed_block_hashes(db: &mut InMemoryDB, number: u64) {
+    for n in number.saturating_sub(256)..number {
+        db.block_hashes
+            .insert(U256::from(n), synthetic_block_hash(n));
+    }
+}
+
+/// Address of a contract deployed with `CREATE2` (EIP-1014):
+/// `keccak256(0xff ++ deployer ++ salt ++ keccak256(init_code))[12..]`
+pub fn create2_address(deployer: Address, salt: B256, init_code_hash: B256) -> Address {
+    let mut preimage = Vec::with_capacity(85);
+    preimage.push(0xff);
+    preimage.extend_from_slice(deployer.as_slice());
+    preimage.extend_from_slice(salt.as_slice());
+    preimage.extend_from_slice(init_code_hash.as_slice());
+
+    Address::from_slice(&keccak256(preimage)[12..])
+}
+
+/// Asserts that `result` used `expected` gas, give or take `tolerance_pct` percent.
+///
+/// R55 gas is metered by instruction count, which shifts slightly with compiler changes. Hence,
+/// gas regressions are better caught with a tolerance than by matching the exact amount.
+pub fn assert_gas_within(result: &TxResult, expected: u64, tolerance_pct: u64) {
+    let diff = result.gas_used.abs_diff(expected);
+    assert!(
+        diff * 100 <= expected * tolerance_pct,
+        "Gas used ({}) not within {}% of {} (off by {})",
+        result.gas_used,
+        tolerance_pct,
+        expected,
+        diff
+    );
+}
+
+/// Formats the gas used by the `#[bench]` methods of a contract, given their signatures.
+///
+/// Methods that weren't called are listed with no calls, and those not in `signatures` are
+/// skipped, so that the table only compares the benchmarked methods.
+pub fn gas_report_table(report: &GasReport, signatures: &[&str]) -> String {
+    let width = signatures.iter().map(|sig| sig.len()).max().unwrap_or(0);
+    let mut table = format!(
+        "{:<width$} | {:>5} | {:>10} | {:>10} | {:>10}\n",
+        "method", "calls", "min", "mean", "max"
+    );
+    for sig in signatures {
+        let row = match report.get(get_selector_from_sig(sig)) {
+            Some(method) => writeln!(
+                table,
+                "{:<width$} | {:>5} | {:>10} | {:>10} | {:>10}",
+                sig,
+                method.calls,
+                method.min,
+                method.mean(),
+                method.max
+            ),
+            None => writeln!(
+                table,
+                "{:<width$} | {:>5} | {:>10} | {:>10} | {:>10}",
+                sig, 0, "-", "-", "-"
+            ),
+        };
+        row.expect("Writing to a String never fails");
+    }
+    table
+}
+
 pub fn get_selector_from_sig(sig: &str) -> [u8; 4] {
     keccak256(sig)[0..4]
         .try_into()
@@ -66,6 +223,83 @@ pub fn read_db_slot(db: &mut InMemoryDB, contract: Address, slot: U256) -> U256
         .expect("Unable to read storge slot")
 }
 
+/// Compiles the contract at `path` (the directory of its `Cargo.toml`), like `r55-compile` does.
+///
+/// Bytecode is cached by path and sources, so that a contract is only compiled once across tests,
+/// unless its sources (or those of its dependencies) change in the meantime. Contract dependencies
+/// are compiled first, and written to the bytecode directory that their dependents embed from.
+pub fn compile_from_source<P: AsRef<Path>>(path: P) -> Bytes {
+    let path = path.as_ref().canonicalize().expect("Invalid contract path");
+    let contract =
+        ContractWithDeps::try_from(&path.join("Cargo.toml")).expect("Not an R55 contract");
+
+    let mut hasher = Keccak256::new();
+    hasher.update(hash_sources(&path));
+    for dep in &contract.deps {
+        let bytecode = compile_from_source(&dep.path);
+        write_dependency(&path, &dep.name.package, &bytecode);
+        hasher.update(keccak256(&bytecode));
+    }
+
+    let key = (path, hasher.finalize());
+    if let Some(bytecode) = COMPILED.lock().unwrap().get(&key) {
+        return bytecode.clone();
+    }
+
+    let (bytecode, _) = Contract::from(contract)
+        .compile_r55()
+        .expect("Unable to compile contract");
+
+    let bytecode = Bytes::from(bytecode);
+    COMPILED.lock().unwrap().insert(key, bytecode.clone());
+    bytecode
+}
+
+// Writes the bytecode of a dependency to `<package>.bin`, where the `contract` macro of the
+// dependent at `path` embeds it from. Unchanged files are left as is, so cargo doesn't rebuild.
+fn write_dependency(path: &Path, package: &str, bytecode: &[u8]) {
+    let bytecode_dir = path.join(BYTECODE_DIR);
+    fs::create_dir_all(&bytecode_dir).expect("Unable to create bytecode directory");
+
+    let bin_path = bytecode_dir.join(format!("{}.bin", package));
+    if fs::read(&bin_path).is_ok_and(|current| current == bytecode) {
+        return;
+    }
+    fs::write(bin_path, bytecode).expect("Unable to write dependency bytecode");
+}
+
+/// Compiles (see [`compile_from_source`]) and deploys the contract at `path`
+pub fn deploy_from_source<P: AsRef<Path>>(
+    db: &mut InMemoryDB,
+    path: P,
+    encoded_args: Option<Vec<u8>>,
+) -> Result<Address> {
+    deploy_contract(db, compile_from_source(path), encoded_args)
+}
+
+// Hash of the manifest and sources of a contract, so that edits invalidate its cached bytecode
+fn hash_sources(path: &Path) -> B256 {
+    let mut files = vec![path.join("Cargo.toml")];
+    let mut dirs = vec![path.join("src")];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(dir).expect("Unable to read contract sources") {
+            let entry = entry.expect("Unable to read contract sources").path();
+            if entry.is_dir() {
+                dirs.push(entry);
+            } else {
+                files.push(entry);
+            }
+        }
+    }
+    files.sort();
+
+    let mut hasher = Keccak256::new();
+    for file in files {
+        hasher.update(fs::read(file).expect("Unable to read contract sources"));
+    }
+    hasher.finalize()
+}
+
 pub fn load_bytecode_from_file<P: AsRef<Path>>(path: P) -> Bytes {
     let content = fs::read_to_string(path).expect("Unable to load bytecode from path");
     let trimmed = content.trim().trim_start_matches("0x");
@@ -0,0 +1,48 @@
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolValue;
+use r55::{
+    exec::{deploy_contract, query, run_tx},
+    get_bytecode,
+    test_utils::{
+        add_balance_to_db, get_calldata, get_selector_from_sig, initialize_logger, ALICE, BOB,
+        CAROL,
+    },
+};
+use revm::InMemoryDB;
+
+#[test]
+fn test_batch_tuple_array() {
+    initialize_logger();
+    let mut db = InMemoryDB::default();
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+    let batch = deploy_contract(&mut db, get_bytecode("batch"), None).unwrap();
+    let dave = Address::repeat_byte(0xD);
+
+    // `Vec<(Address, U256)>` args are exposed as `(address,uint256)[]`
+    let transfers = vec![
+        (BOB, U256::from(10e18)),
+        (CAROL, U256::from(20e18)),
+        (dave, U256::from(30e18)),
+    ];
+    let selector_batch = get_selector_from_sig("batch((address,uint256)[])");
+    let calldata_batch = get_calldata(selector_batch, (transfers.clone(),).abi_encode_params());
+    let result = run_tx(&mut db, &batch, calldata_batch, &ALICE).expect("Error executing tx");
+    assert_eq!(
+        U256::abi_decode(&result.output, true).unwrap(),
+        U256::from(3)
+    );
+
+    // Every pair was decoded and credited
+    let selector_amount_of = get_selector_from_sig("amount_of(address)");
+    for (recipient, amount) in transfers {
+        let calldata = get_calldata(selector_amount_of, recipient.abi_encode());
+        let credited: U256 =
+            query(&mut db, &batch, calldata, &ALICE).expect("Error executing query");
+        assert_eq!(credited, amount, "Incorrect amount of {}", recipient);
+    }
+
+    let selector_total = get_selector_from_sig("total()");
+    let total: U256 =
+        query(&mut db, &batch, selector_total.to_vec(), &ALICE).expect("Error executing query");
+    assert_eq!(total, U256::from(60e18), "Incorrect total");
+}
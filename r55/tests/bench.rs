@@ -0,0 +1,66 @@
+use alloy_sol_types::SolValue;
+use r55::{
+    exec::{deploy_contract, run_tx, start_gas_report, take_gas_report},
+    get_bytecode,
+    test_utils::{
+        add_balance_to_db, gas_report_table, get_calldata, get_selector_from_sig,
+        initialize_logger, ALICE, U256,
+    },
+};
+use revm::InMemoryDB;
+
+// Methods tagged with `#[bench]` in the bench example
+const BENCHED: [&str; 3] = ["store(uint256)", "fill(uint64)", "load()"];
+
+#[test]
+fn test_gas_report() {
+    initialize_logger();
+    let mut db = InMemoryDB::default();
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+    let bench = deploy_contract(&mut db, get_bytecode("bench"), None).unwrap();
+
+    let [store, fill, load] = BENCHED.map(get_selector_from_sig);
+    let value_at = get_selector_from_sig("value_at(uint256)");
+
+    start_gas_report();
+    for i in 1..=3_u64 {
+        let calldata = get_calldata(store, U256::from(i).abi_encode());
+        run_tx(&mut db, &bench, calldata, &ALICE).expect("Error executing tx");
+
+        let calldata = get_calldata(fill, 8_u64.abi_encode());
+        run_tx(&mut db, &bench, calldata, &ALICE).expect("Error executing tx");
+
+        run_tx(&mut db, &bench, load.to_vec(), &ALICE).expect("Error executing tx");
+    }
+    let report = take_gas_report();
+
+    // Every call of each method is accounted, with the gas it used
+    for selector in [store, fill, load] {
+        let method = report
+            .get(selector)
+            .expect("Method missing from the report");
+        assert_eq!(method.calls, 3);
+        assert!(method.min > 0, "No gas recorded");
+        assert!(method.min <= method.mean() && method.mean() <= method.max);
+        assert!(method.total >= 3 * method.min);
+    }
+    // Methods that weren't called are left out
+    assert!(report.get(value_at).is_none());
+    assert_eq!(report.iter().count(), 3);
+
+    // Writing 8 slots outweighs a single write or read
+    assert_eq!(report.heaviest().map(|(selector, _)| selector), Some(fill));
+
+    // The table has a row per benched method, with its number of calls
+    let table = gas_report_table(&report, &BENCHED);
+    println!("{table}");
+    assert_eq!(table.lines().count(), 1 + BENCHED.len());
+    for (sig, row) in BENCHED.iter().zip(table.lines().skip(1)) {
+        assert!(row.starts_with(sig), "Missing row for {sig}");
+        assert_eq!(row.split('|').nth(1).map(str::trim), Some("3"));
+    }
+
+    // Txs outside of a report aren't collected
+    run_tx(&mut db, &bench, load.to_vec(), &ALICE).expect("Error executing tx");
+    assert!(take_gas_report().get(load).is_none());
+}
@@ -0,0 +1,182 @@
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolValue;
+use r55::{
+    exec::{deploy_contract, run_tx},
+    get_bytecode,
+    test_utils::{
+        add_balance_to_db, get_calldata, get_selector_from_sig, initialize_logger, read_db_slot,
+        ALICE,
+    },
+};
+use revm::InMemoryDB;
+
+fn bounds_setup() -> (InMemoryDB, Address) {
+    initialize_logger();
+    let mut db = InMemoryDB::default();
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+
+    // Deploy contract
+    let bytecode = get_bytecode("bounds");
+    let bounds = deploy_contract(&mut db, bytecode, None).unwrap();
+
+    (db, bounds)
+}
+
+fn set(db: &mut InMemoryDB, bounds: Address, sig: &str, value: U256) {
+    let calldata = get_calldata(get_selector_from_sig(sig), value.abi_encode());
+    let result = run_tx(db, &bounds, calldata, &ALICE).expect("Error executing tx");
+    assert!(result.status, "Set transaction failed");
+}
+
+fn check(db: &mut InMemoryDB, bounds: Address, sig: &str) -> bool {
+    let output = run_tx(db, &bounds, get_selector_from_sig(sig).to_vec(), &ALICE)
+        .expect("Error executing tx")
+        .output;
+    bool::abi_decode(&output, true).unwrap()
+}
+
+#[test]
+fn test_slot_ordering() {
+    let (mut db, bounds) = bounds_setup();
+
+    // Floor below ceiling
+    set(&mut db, bounds, "set_floor(uint256)", U256::from(10));
+    set(&mut db, bounds, "set_ceiling(uint256)", U256::from(20));
+    assert!(
+        check(&mut db, bounds, "is_below()"),
+        "Floor should be below"
+    );
+    assert!(!check(&mut db, bounds, "is_equal()"), "Slots should differ");
+
+    // Floor above ceiling
+    set(&mut db, bounds, "set_floor(uint256)", U256::from(30));
+    assert!(
+        !check(&mut db, bounds, "is_below()"),
+        "Floor should be above"
+    );
+    assert!(!check(&mut db, bounds, "is_equal()"), "Slots should differ");
+
+    // Floor equal to ceiling
+    set(&mut db, bounds, "set_ceiling(uint256)", U256::from(30));
+    assert!(
+        !check(&mut db, bounds, "is_below()"),
+        "Floor should be equal"
+    );
+    assert!(
+        check(&mut db, bounds, "is_equal()"),
+        "Slots should be equal"
+    );
+}
+
+#[test]
+fn test_runtime_assert_reverts() {
+    let (mut db, bounds) = bounds_setup();
+
+    // A valid range satisfies the invariant
+    let selector_set_range = get_selector_from_sig("set_range(uint256,uint256)");
+    let calldata = get_calldata(
+        selector_set_range,
+        (U256::from(10), U256::from(20)).abi_encode(),
+    );
+    run_tx(&mut db, &bounds, calldata, &ALICE).expect("Error executing tx");
+
+    // Bounds is built with debug assertions, so a violated invariant reverts
+    let calldata = get_calldata(
+        selector_set_range,
+        (U256::from(30), U256::from(20)).abi_encode(),
+    );
+    let err = run_tx(&mut db, &bounds, calldata, &ALICE).expect_err("Tx succeeded");
+    assert!(
+        err.matches_string_error("Floor above ceiling"),
+        "Incorrect error"
+    );
+}
+
+#[test]
+fn test_revert_fmt() {
+    let (mut db, bounds) = bounds_setup();
+
+    set(&mut db, bounds, "set_ceiling(uint256)", U256::from(20));
+    set(&mut db, bounds, "raise_floor(uint256)", U256::from(20));
+
+    // The revert message interpolates both values
+    let calldata = get_calldata(
+        get_selector_from_sig("raise_floor(uint256)"),
+        U256::from(1e18).abi_encode(),
+    );
+    let err = run_tx(&mut db, &bounds, calldata, &ALICE).expect_err("Tx succeeded");
+    assert!(
+        err.matches_string_error("Floor 1000000000000000000 above ceiling 20"),
+        "Incorrect error: {}",
+        err
+    );
+}
+
+#[test]
+fn test_cfg_gated_method() {
+    let (mut db, bounds) = bounds_setup();
+
+    set(&mut db, bounds, "set_ceiling(uint256)", U256::from(20));
+
+    // Bounds is built without the `admin` feature, so `reset()` isn't dispatched at all: its
+    // selector reverts without data, like an unknown one
+    let err = run_tx(
+        &mut db,
+        &bounds,
+        get_selector_from_sig("reset()").to_vec(),
+        &ALICE,
+    )
+    .expect_err("Gated method should be unreachable");
+    assert!(err.matches_string_error(""), "Incorrect error: {}", err);
+    assert_eq!(read_db_slot(&mut db, bounds, U256::from(1)), U256::from(20));
+}
+
+#[test]
+fn test_consuming_method() {
+    let (mut db, bounds) = bounds_setup();
+
+    set(&mut db, bounds, "set_floor(uint256)", U256::from(10));
+    set(&mut db, bounds, "set_ceiling(uint256)", U256::from(25));
+
+    // `close(self)` is dispatched like a mutable method, and its writes persist
+    let result = run_tx(
+        &mut db,
+        &bounds,
+        get_selector_from_sig("close()").to_vec(),
+        &ALICE,
+    )
+    .expect("Error executing tx");
+    assert_eq!(
+        U256::abi_decode(&result.output, true).unwrap(),
+        U256::from(15)
+    );
+    assert!(
+        check(&mut db, bounds, "is_equal()"),
+        "Slots should be cleared"
+    );
+    assert_eq!(read_db_slot(&mut db, bounds, U256::from(1)), U256::ZERO);
+}
+
+#[test]
+fn test_debug_panic_location() {
+    let (mut db, bounds) = bounds_setup();
+
+    set(&mut db, bounds, "set_floor(uint256)", U256::from(30));
+    set(&mut db, bounds, "set_ceiling(uint256)", U256::from(20));
+
+    // Bounds is built with `debug-panics`, so the revert data carries the panic location
+    let err = run_tx(
+        &mut db,
+        &bounds,
+        get_selector_from_sig("width()").to_vec(),
+        &ALICE,
+    )
+    .expect_err("Tx succeeded");
+    let err = err.to_string();
+    assert!(
+        err.contains("panicked at src/lib.rs:"),
+        "No location: {}",
+        err
+    );
+    assert!(err.contains("Floor above ceiling"), "No message: {}", err);
+}
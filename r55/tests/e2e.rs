@@ -3,11 +3,20 @@ use alloy_sol_types::SolValue;
 use r55::{
     exec::{deploy_contract, run_tx},
     get_bytecode,
-    test_utils::{add_balance_to_db, get_selector_from_sig, initialize_logger},
+    test_utils::{
+        account_info, add_balance_to_db, compile_from_source, deploy_from_source, get_calldata,
+        get_selector_from_sig, initialize_logger,
+    },
 };
+use r55_compile::compile::{Contract, ContractWithDeps};
 use revm::InMemoryDB;
+use std::path::Path;
 use tracing::{debug, error, info};
 
+const ERC20_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../examples/erc20");
+const BOUNDS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../examples/bounds");
+const ERC20X_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../examples/erc20x");
+
 #[test]
 fn erc20() {
     initialize_logger();
@@ -172,3 +181,69 @@ fn erc20x() {
         }
     }
 }
+
+#[test]
+fn deploy_from_source_cache() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+    let alice: Address = address!("000000000000000000000000000000000000000A");
+    add_balance_to_db(&mut db, alice, 1e18 as u64);
+
+    // Both contracts are compiled on demand, and deploy like their prebuilt bytecode
+    let erc20 = deploy_from_source(&mut db, ERC20_PATH, Some(alice.abi_encode())).unwrap();
+    let bounds = deploy_from_source(&mut db, BOUNDS_PATH, None).unwrap();
+    assert_ne!(erc20, bounds);
+
+    let selector_total_supply = get_selector_from_sig("total_supply()").to_vec();
+    let res = run_tx(&mut db, &erc20, selector_total_supply, &alice).expect("Error executing tx");
+    assert_eq!(U256::from_be_slice(&res.output), U256::ZERO);
+
+    let selector_is_equal = get_selector_from_sig("is_equal()").to_vec();
+    let res = run_tx(&mut db, &bounds, selector_is_equal, &alice).expect("Error executing tx");
+    assert!(bool::abi_decode(&res.output, true).unwrap());
+
+    // The second deploy of the same sources hits the cache, sharing the compiled bytecode
+    let cached = compile_from_source(ERC20_PATH);
+    assert_eq!(cached.as_ptr(), compile_from_source(ERC20_PATH).as_ptr());
+    let erc20_2 = deploy_from_source(&mut db, ERC20_PATH, Some(alice.abi_encode())).unwrap();
+    assert_ne!(erc20, erc20_2);
+
+    // Dependencies are compiled first, so erc20x embeds the freshly compiled erc20 bytecode
+    let erc20x = deploy_from_source(&mut db, ERC20X_PATH, None).unwrap();
+    let selector_x_deploy = get_selector_from_sig("x_deploy(address)");
+    let calldata = get_calldata(selector_x_deploy, alice.abi_encode());
+    let res = run_tx(&mut db, &erc20x, calldata, &alice).expect("Error executing tx");
+    let (token, _) = <(Address, Address)>::abi_decode(&res.output, true).unwrap();
+
+    let code_hash = |db: &mut InMemoryDB, addr| account_info(db, addr).unwrap().code_hash;
+    assert_eq!(code_hash(&mut db, token), code_hash(&mut db, erc20));
+    assert_eq!(
+        compile_from_source(ERC20X_PATH).as_ptr(),
+        compile_from_source(ERC20X_PATH).as_ptr()
+    );
+}
+
+#[test]
+fn deployed_code_size() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+    let alice: Address = address!("000000000000000000000000000000000000000A");
+    add_balance_to_db(&mut db, alice, 1e18 as u64);
+
+    let contract = ContractWithDeps::try_from(&Path::new(ERC20_PATH).join("Cargo.toml"))
+        .expect("Not an R55 contract");
+    let (bytecode, code_size) = Contract::from(contract)
+        .compile_r55()
+        .expect("Unable to compile contract");
+
+    // The size reported from the runtime ELF is the size of the code stored on deployment
+    let erc20 = deploy_contract(&mut db, Bytes::from(bytecode), Some(alice.abi_encode())).unwrap();
+    let code = account_info(&mut db, erc20)
+        .and_then(|info| info.code)
+        .expect("No code deployed");
+    assert_eq!(code_size.size, code.original_bytes().len());
+    assert_eq!(code.original_bytes()[0], 0xff);
+    assert!(!code_size.exceeds_limit());
+}
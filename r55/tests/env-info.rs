@@ -0,0 +1,424 @@
+use alloy_primitives::{b256, keccak256, Address, Bytes, B256, U256};
+use alloy_sol_types::SolValue;
+use r55::{
+    exec::{
+        deploy_contract, run_call_at, run_tx, run_tx_at, run_tx_with_fees, run_tx_with_gas_limits,
+        run_tx_with_randomness, Eip1559Fees,
+    },
+    get_bytecode,
+    test_utils::{
+        add_balance_to_db, get_calldata, get_selector_from_sig, initialize_logger,
+        seed_block_hashes, synthetic_block_hash, ALICE, BOB,
+    },
+};
+use revm::InMemoryDB;
+
+fn env_info_setup() -> (InMemoryDB, Address) {
+    initialize_logger();
+    let mut db = InMemoryDB::default();
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+
+    // Deploy contract
+    let bytecode = get_bytecode("env_info");
+    let env_info = deploy_contract(&mut db, bytecode, None).unwrap();
+
+    (db, env_info)
+}
+
+#[test]
+fn test_difficulty_is_prevrandao_alias() {
+    let (mut db, env_info) = env_info_setup();
+
+    let selector_prevrandao = get_selector_from_sig("prevrandao()");
+    let selector_difficulty = get_selector_from_sig("difficulty()");
+    let mut randomness = |selector: [u8; 4], prevrandao, difficulty| {
+        let output = run_tx_with_randomness(
+            &mut db,
+            &env_info,
+            selector.to_vec(),
+            &ALICE,
+            prevrandao,
+            difficulty,
+        )
+        .expect("Error executing tx")
+        .output;
+        U256::from_be_slice(&output)
+    };
+
+    // Post-merge, both return the `prevrandao` of the block, not its difficulty
+    let prevrandao = b256!("00000000000000000000000000000000000000000000000000000000000c0ffe");
+    let difficulty = U256::from(0x1d1f_u64);
+    for selector in [selector_prevrandao, selector_difficulty] {
+        assert_eq!(
+            randomness(selector, Some(prevrandao), difficulty),
+            U256::from_be_bytes(prevrandao.0),
+            "`difficulty()` must match `prevrandao()`"
+        );
+    }
+
+    // Pre-merge blocks have no `prevrandao`, so both return the difficulty instead
+    for selector in [selector_prevrandao, selector_difficulty] {
+        assert_eq!(randomness(selector, None, difficulty), difficulty);
+    }
+}
+
+#[test]
+fn test_keccak_b256_event_topic() {
+    let (mut db, env_info) = env_info_setup();
+
+    let selector_hash = get_selector_from_sig("hash(bytes)");
+    let signature = Bytes::from_static(b"Transfer(address,address,uint256)");
+    let calldata_hash = get_calldata(selector_hash, (signature,).abi_encode_params());
+    let hash_result = run_tx(&mut db, &env_info, calldata_hash, &ALICE)
+        .expect("Error executing tx")
+        .output;
+
+    assert_eq!(
+        B256::from_slice(&hash_result),
+        b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"),
+        "Incorrect `Transfer` event topic"
+    );
+}
+
+#[test]
+fn test_native_integer_args() {
+    let (mut db, env_info) = env_info_setup();
+
+    // `truncate(u64) -> u32` is exposed as `truncate(uint64) returns (uint32)`
+    let selector_truncate = get_selector_from_sig("truncate(uint64)");
+    let calldata_truncate = get_calldata(selector_truncate, 0x1_0000_002a_u64.abi_encode());
+    let truncate_result = run_tx(&mut db, &env_info, calldata_truncate, &ALICE)
+        .expect("Error executing tx")
+        .output;
+
+    assert_eq!(
+        u32::abi_decode(&truncate_result, true).unwrap(),
+        42,
+        "Incorrect truncated value"
+    );
+}
+
+#[test]
+fn test_cached_sender_and_origin() {
+    let (mut db, env_info) = env_info_setup();
+    add_balance_to_db(&mut db, BOB, 1e18 as u64);
+
+    // Consecutive frames (possibly on reused emulators) don't share the cached addresses
+    for caller in [ALICE, BOB] {
+        let senders = run_tx(
+            &mut db,
+            &env_info,
+            get_selector_from_sig("senders()").to_vec(),
+            &caller,
+        )
+        .expect("Error executing tx")
+        .output;
+
+        assert_eq!(
+            <(Address, Address, Address)>::abi_decode(&senders, true).unwrap(),
+            (caller, caller, caller),
+            "Inconsistent sender reads"
+        );
+    }
+}
+
+#[test]
+fn test_caller_syscall_gas() {
+    let (mut db, env_info) = env_info_setup();
+
+    let mut maybe_sender = |read: bool| {
+        let calldata = get_calldata(
+            get_selector_from_sig("maybe_sender(bool)"),
+            read.abi_encode(),
+        );
+        run_tx(&mut db, &env_info, calldata, &ALICE).expect("Error executing tx")
+    };
+    let with_read = maybe_sender(true);
+    let without_read = maybe_sender(false);
+    assert_eq!(with_read.decode::<Address>().unwrap(), ALICE);
+
+    // Reading the caller costs the base gas of the syscall, on top of a few instructions
+    let delta = with_read.gas_used - without_read.gas_used;
+    assert!(
+        (2..1_000).contains(&delta),
+        "Unexpected gas of the caller syscall: {}",
+        delta
+    );
+}
+
+#[test]
+fn test_address_u256_conversions() {
+    let (mut db, env_info) = env_info_setup();
+    let addr = Address::repeat_byte(0xab);
+
+    let calldata = get_calldata(get_selector_from_sig("to_u256(address)"), addr.abi_encode());
+    let output = run_tx(&mut db, &env_info, calldata, &ALICE)
+        .expect("Error executing tx")
+        .output;
+    let value = U256::abi_decode(&output, true).unwrap();
+    assert_eq!(value, U256::from_be_slice(addr.as_slice()));
+
+    // The high 12 bytes are masked off when converting back
+    for value in [value, value | (U256::MAX << 160)] {
+        let calldata = get_calldata(
+            get_selector_from_sig("to_address(uint256)"),
+            value.abi_encode(),
+        );
+        let output = run_tx(&mut db, &env_info, calldata, &ALICE)
+            .expect("Error executing tx")
+            .output;
+        assert_eq!(Address::abi_decode(&output, true).unwrap(), addr);
+    }
+}
+
+#[test]
+fn test_effective_gas_price() {
+    let (mut db, env_info) = env_info_setup();
+    let selector = get_selector_from_sig("gas_price()");
+
+    // Legacy txs pay their gas price
+    let output = run_tx(&mut db, &env_info, selector.to_vec(), &ALICE)
+        .expect("Error executing tx")
+        .output;
+    assert_eq!(U256::abi_decode(&output, true).unwrap(), U256::from(42));
+
+    // EIP-1559 txs pay `min(max_fee, base_fee + priority_fee)`
+    for (max_fee, effective) in [(20, 15), (12, 12)] {
+        let fees = Eip1559Fees {
+            basefee: U256::from(10),
+            max_fee_per_gas: U256::from(max_fee),
+            max_priority_fee_per_gas: U256::from(5),
+        };
+        let output = run_tx_with_fees(&mut db, &env_info, selector.to_vec(), &ALICE, fees)
+            .expect("Error executing tx")
+            .output;
+        assert_eq!(
+            U256::abi_decode(&output, true).unwrap(),
+            U256::from(effective),
+            "Incorrect effective gas price"
+        );
+    }
+}
+
+#[test]
+fn test_u256_to_string() {
+    let (mut db, env_info) = env_info_setup();
+
+    for value in [
+        U256::ZERO,
+        U256::from(1),
+        U256::from(1_000_000_007),
+        U256::MAX,
+    ] {
+        let calldata = get_calldata(
+            get_selector_from_sig("to_decimal(uint256)"),
+            value.abi_encode(),
+        );
+        let output = run_tx(&mut db, &env_info, calldata, &ALICE)
+            .expect("Error executing tx")
+            .output;
+        assert_eq!(
+            String::abi_decode(&output, true).unwrap(),
+            value.to_string()
+        );
+    }
+}
+
+#[test]
+fn test_parse_u256() {
+    let (mut db, env_info) = env_info_setup();
+    let parse = |db: &mut InMemoryDB, value: &str| {
+        let calldata = get_calldata(
+            get_selector_from_sig("from_decimal_or_hex(string)"),
+            value.abi_encode(),
+        );
+        run_tx(db, &env_info, calldata, &ALICE)
+            .map(|res| U256::abi_decode(&res.output, true).unwrap())
+    };
+
+    for value in ["255", "0xff", "0XFF", "0x00ff"] {
+        assert_eq!(parse(&mut db, value).unwrap(), U256::from(255), "{}", value);
+    }
+    assert_eq!(parse(&mut db, &U256::MAX.to_string()).unwrap(), U256::MAX);
+
+    // Invalid input reverts
+    let overflow = format!("0x1{}", "0".repeat(64));
+    for value in ["", "0x", "-1", " 255", "0xfg", "25_5", overflow.as_str()] {
+        assert!(
+            parse(&mut db, value).is_err(),
+            "Parsed invalid input {:?}",
+            value
+        );
+    }
+}
+
+#[test]
+fn test_incremental_keccak() {
+    let (mut db, env_info) = env_info_setup();
+
+    let parts = vec![
+        Bytes::from_static(b"Mail(address from,address to,string contents)"),
+        Bytes::new(),
+        Bytes::from(ALICE.into_word().to_vec()),
+        Bytes::from(BOB.into_word().to_vec()),
+    ];
+    let concatenation = Bytes::from(parts.concat());
+
+    let calldata = get_calldata(
+        get_selector_from_sig("hash_parts(bytes[])"),
+        (parts,).abi_encode_params(),
+    );
+    let incremental = run_tx(&mut db, &env_info, calldata, &ALICE)
+        .expect("Error executing tx")
+        .output;
+
+    let calldata = get_calldata(
+        get_selector_from_sig("hash(bytes)"),
+        (concatenation.clone(),).abi_encode_params(),
+    );
+    let single_shot = run_tx(&mut db, &env_info, calldata, &ALICE)
+        .expect("Error executing tx")
+        .output;
+
+    assert_eq!(incremental, single_shot, "Incremental hash mismatch");
+    assert_eq!(B256::from_slice(&incremental), keccak256(&concatenation));
+}
+
+#[test]
+fn test_block_hash_history() {
+    let (mut db, env_info) = env_info_setup();
+    seed_block_hashes(&mut db, 300);
+
+    // Only the hashes of the last 256 blocks are available
+    for (number, expected) in [
+        (299, synthetic_block_hash(299)),
+        (250, synthetic_block_hash(250)),
+        (44, synthetic_block_hash(44)),
+        (43, B256::ZERO),
+        (10, B256::ZERO),
+        (300, B256::ZERO),
+    ] {
+        let calldata = get_calldata(
+            get_selector_from_sig("block_hash(uint64)"),
+            number.abi_encode(),
+        );
+        let output = run_tx_at(&mut db, &env_info, calldata, &ALICE, 300)
+            .expect("Error executing tx")
+            .output;
+        assert_eq!(
+            B256::abi_decode(&output, true).unwrap(),
+            expected,
+            "Incorrect hash of block {}",
+            number
+        );
+    }
+}
+
+#[test]
+fn test_pinned_block_call() {
+    let (mut db, env_info) = env_info_setup();
+
+    let calldata = get_calldata(
+        get_selector_from_sig("is_unlocked(uint64)"),
+        100_u64.abi_encode(),
+    );
+    for (block_number, expected) in [(0, false), (99, false), (100, true), (1_000, true)] {
+        let output = run_call_at(&mut db, &env_info, calldata.clone(), &ALICE, block_number)
+            .expect("Error executing call")
+            .output;
+        assert_eq!(
+            bool::abi_decode(&output, true).unwrap(),
+            expected,
+            "Incorrect lock state at block {}",
+            block_number
+        );
+    }
+}
+
+#[test]
+fn test_block_gas_limit() {
+    let (mut db, env_info) = env_info_setup();
+
+    let block_gas_limit = 30_000_000;
+    let calldata = get_selector_from_sig("block_gas_limit()").to_vec();
+
+    // Txs within the block gas limit are included, and can read it
+    let result = run_tx_with_gas_limits(
+        &mut db,
+        &env_info,
+        calldata.clone(),
+        &ALICE,
+        1_000_000,
+        block_gas_limit,
+    )
+    .expect("Error executing tx");
+    assert_eq!(
+        u64::abi_decode(&result.output, true).unwrap(),
+        block_gas_limit
+    );
+
+    // Txs exceeding it are rejected before execution
+    let err = run_tx_with_gas_limits(
+        &mut db,
+        &env_info,
+        calldata,
+        &ALICE,
+        block_gas_limit + 1,
+        block_gas_limit,
+    )
+    .expect_err("Tx exceeding the block gas limit succeeded");
+    assert_eq!(
+        err.to_string(),
+        "Tx gas limit 30000001 exceeds the block gas limit 30000000"
+    );
+}
+
+#[test]
+fn test_bit_op_syscalls() {
+    let (mut db, env_info) = env_info_setup();
+
+    // Negative (as two's complement) values exercise the sign extension of `sar`
+    let negative = U256::from_be_bytes(
+        b256!("8001020304050607080910111213141516171819202122232425262728293031").0,
+    );
+    let positive = negative >> 1;
+
+    for value in [negative, positive] {
+        for (shift, index) in [
+            (0, 0),
+            (8, 1),
+            (130, 17),
+            (255, 31),
+            (256, 32),
+            (u64::MAX, 64),
+        ] {
+            let calldata = get_calldata(
+                get_selector_from_sig("bit_ops(uint256,uint64,uint64)"),
+                (value, shift, index).abi_encode(),
+            );
+            let output = run_tx(&mut db, &env_info, calldata, &ALICE)
+                .expect("Error executing tx")
+                .output;
+
+            // Matches alloy's software implementation
+            let shift = shift as usize;
+            let expected_byte = if index < 32 {
+                U256::from(value.byte(31 - index as usize))
+            } else {
+                U256::ZERO
+            };
+            assert_eq!(
+                <(U256, U256, U256, U256)>::abi_decode(&output, true).unwrap(),
+                (
+                    value.wrapping_shl(shift),
+                    value.wrapping_shr(shift),
+                    value.arithmetic_shr(shift),
+                    expected_byte,
+                ),
+                "Mismatch for shift {} and index {}",
+                shift,
+                index
+            );
+        }
+    }
+}
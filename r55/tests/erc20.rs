@@ -1,11 +1,11 @@
-use alloy_primitives::{Address, B256, U256};
+use alloy_primitives::{keccak256, Address, B256, U256};
 use alloy_sol_types::SolValue;
 use r55::{
-    exec::{deploy_contract, run_tx},
+    exec::{deploy_contract, query, run_tx, simulate_call_trace},
     get_bytecode,
     test_utils::{
-        add_balance_to_db, get_calldata, get_selector_from_sig, initialize_logger, ALICE, BOB,
-        CAROL,
+        add_balance_to_db, assert_gas_within, get_calldata, get_mapping_slot,
+        get_selector_from_sig, initialize_logger, read_db_slot, ALICE, BOB, CAROL,
     },
 };
 use revm::InMemoryDB;
@@ -53,6 +53,49 @@ fn test_erc20_deployment() {
     );
 }
 
+#[test]
+fn test_erc20_query() {
+    let ERC20Setup {
+        mut db,
+        token,
+        owner,
+    } = erc20_setup(ALICE);
+
+    // Mint some tokens, so that the total supply is non-zero
+    let mint_amount = U256::from(42e18);
+    let selector_mint = get_selector_from_sig("mint(address,uint256)");
+    let calldata_mint = get_calldata(selector_mint, (BOB, mint_amount).abi_encode());
+    run_tx(&mut db, &token, calldata_mint, &owner).expect("Error executing tx");
+
+    // Read-only calls decode straight into the requested type
+    let selector_total_supply = get_selector_from_sig("total_supply()");
+    let total_supply: U256 = query(&mut db, &token, selector_total_supply.to_vec(), &ALICE)
+        .expect("Error executing query");
+    assert_eq!(total_supply, mint_amount, "Incorrect total supply");
+
+    let selector_owner = get_selector_from_sig("owner()");
+    let queried_owner: Address =
+        query(&mut db, &token, selector_owner.to_vec(), &ALICE).expect("Error executing query");
+    assert_eq!(queried_owner, owner, "Incorrect owner");
+}
+
+#[test]
+fn test_erc20_deployment_zero_owner() {
+    initialize_logger();
+    let mut db = InMemoryDB::default();
+
+    // Deploying with the zero address as owner must revert with a typed error
+    let constructor = Address::ZERO.abi_encode();
+    let bytecode = get_bytecode("erc20");
+    let err =
+        deploy_contract(&mut db, bytecode, Some(constructor)).expect_err("Deployment should fail");
+
+    assert!(
+        err.matches_custom_error("ERC20Error::ZeroAddress"),
+        "Incorrect error signature"
+    );
+}
+
 #[test]
 fn test_erc20_mint() {
     let ERC20Setup {
@@ -84,6 +127,95 @@ fn test_erc20_mint() {
     );
 }
 
+#[test]
+fn test_erc20_decode_output() {
+    let ERC20Setup {
+        mut db,
+        token,
+        owner,
+    } = erc20_setup(ALICE);
+
+    let mint_amount = U256::from(100e18);
+    let selector_mint = get_selector_from_sig("mint(address,uint256)");
+    let calldata_mint = get_calldata(selector_mint, (owner, mint_amount).abi_encode());
+    run_tx(&mut db, &token, calldata_mint, &owner).expect("Error executing tx");
+
+    // A balance query returns a single word, decoded as is
+    let selector_balance = get_selector_from_sig("balance_of(address)");
+    let calldata_balance = get_calldata(selector_balance, owner.abi_encode());
+    let balance_result =
+        run_tx(&mut db, &token, calldata_balance, &owner).expect("Error executing tx");
+
+    assert_eq!(balance_result.output_len(), 32);
+    assert!(!balance_result.is_empty_output());
+    assert_eq!(
+        balance_result.decode::<U256>().unwrap(),
+        mint_amount,
+        "Incorrect balance"
+    );
+}
+
+#[test]
+fn test_erc20_mapping_slots_do_not_collide() {
+    let ERC20Setup {
+        mut db,
+        token,
+        owner,
+    } = erc20_setup(ALICE);
+
+    // `total_supply` (slot 0) and `balance_of` (mapping id 1) are adjacent fields
+    let mint_amount = U256::from(100e18);
+    let selector_mint = get_selector_from_sig("mint(address,uint256)");
+    let calldata_mint = get_calldata(selector_mint, (BOB, mint_amount).abi_encode());
+
+    let mint_result = run_tx(&mut db, &token, calldata_mint, &owner).expect("Error executing tx");
+    assert!(mint_result.status, "Mint transaction failed");
+
+    // The balance is stored at the keccak-derived slot, away from the sequential slots
+    let balance_slot = get_mapping_slot(BOB.abi_encode(), U256::from(1));
+    assert!(balance_slot > U256::from(3), "Mapping entry collides");
+    assert_eq!(read_db_slot(&mut db, token, balance_slot), mint_amount);
+
+    // The mapping id slot is never written, and the direct slots keep their own values
+    assert_eq!(read_db_slot(&mut db, token, U256::from(0)), mint_amount);
+    assert_eq!(read_db_slot(&mut db, token, U256::from(1)), U256::ZERO);
+    assert_eq!(read_db_slot(&mut db, token, U256::from(2)), U256::ZERO);
+    assert_eq!(
+        read_db_slot(&mut db, token, U256::from(3)),
+        U256::from_be_bytes(owner.into_word().0)
+    );
+}
+
+#[test]
+fn test_erc20_mapping_slots_match_solidity() {
+    let ERC20Setup {
+        mut db,
+        token,
+        owner,
+    } = erc20_setup(ALICE);
+
+    // Mint tokens to Bob, and let him approve Carol
+    let mint_amount = U256::from(100e18);
+    let selector_mint = get_selector_from_sig("mint(address,uint256)");
+    let calldata_mint = get_calldata(selector_mint, (BOB, mint_amount).abi_encode());
+    run_tx(&mut db, &token, calldata_mint, &owner).expect("Error executing tx");
+
+    let approve_amount = U256::from(7e18);
+    let selector_approve = get_selector_from_sig("approve(address,uint256)");
+    let calldata_approve = get_calldata(selector_approve, (CAROL, approve_amount).abi_encode());
+    run_tx(&mut db, &token, calldata_approve, &BOB).expect("Error executing tx");
+
+    // Solidity: `balance_of[BOB]` lives at `keccak256(abi.encode(BOB, 1))`
+    let balance_slot: U256 = keccak256((BOB, U256::from(1)).abi_encode()).into();
+    assert_eq!(read_db_slot(&mut db, token, balance_slot), mint_amount);
+
+    // Solidity: `allowance_of[BOB][CAROL]` lives at
+    // `keccak256(abi.encode(CAROL, keccak256(abi.encode(BOB, 2))))`
+    let inner_slot = keccak256((BOB, U256::from(2)).abi_encode());
+    let allowance_slot: U256 = keccak256((CAROL, inner_slot).abi_encode()).into();
+    assert_eq!(read_db_slot(&mut db, token, allowance_slot), approve_amount);
+}
+
 #[test]
 fn test_erc20_transfer() {
     let ERC20Setup {
@@ -360,6 +492,83 @@ fn test_erc20_mint_unauthorized() {
     );
 }
 
+#[test]
+fn test_erc20_unknown_selector() {
+    let ERC20Setup {
+        mut db,
+        token,
+        owner: _,
+    } = erc20_setup(ALICE);
+
+    // Like contracts without a fallback, unknown selectors revert without any data
+    let selector_unknown = get_selector_from_sig("unknown(uint256)");
+    let calldata_unknown = get_calldata(selector_unknown, U256::from(1).abi_encode());
+    for calldata in [calldata_unknown, vec![0xab, 0xcd]] {
+        let err = run_tx(&mut db, &token, calldata, &ALICE)
+            .expect_err("Unknown selector succeeded when it should fail");
+        assert!(err.matches_string_error(""), "Unexpected revert: {}", err);
+    }
+}
+
+#[test]
+fn test_erc20_revert_gas_used() {
+    let ERC20Setup {
+        mut db,
+        token,
+        owner,
+    } = erc20_setup(ALICE);
+
+    let mint_amount = U256::from(1e18);
+    let selector_mint = get_selector_from_sig("mint(address,uint256)");
+    let calldata_mint = get_calldata(selector_mint, (CAROL, mint_amount).abi_encode());
+
+    // Unauthorized mints revert, but still report the gas they consumed
+    let err = run_tx(&mut db, &token, calldata_mint.clone(), &BOB)
+        .expect_err("Mint transaction succeeded when it should fail");
+    let revert_gas = err.gas_used().expect("Reverted tx has no gas used");
+    assert!(revert_gas > 21_000, "Revert gas excludes execution costs");
+
+    // Reverting early is cheaper than a successful mint
+    let mint_result = run_tx(&mut db, &token, calldata_mint, &owner).expect("Error executing tx");
+    assert!(revert_gas < mint_result.gas_used, "Implausible revert gas");
+}
+
+#[test]
+fn test_erc20_mint_gas_baseline() {
+    let mint = |to: Address, amount: U256| {
+        let ERC20Setup {
+            mut db,
+            token,
+            owner,
+        } = erc20_setup(ALICE);
+        let selector_mint = get_selector_from_sig("mint(address,uint256)");
+        let calldata_mint = get_calldata(selector_mint, (to, amount).abi_encode());
+        run_tx(&mut db, &token, calldata_mint, &owner).expect("Error executing tx")
+    };
+
+    // Baseline recorded from a reference mint on a fresh token
+    let baseline = mint(BOB, U256::from(1e18)).gas_used;
+
+    // The same mint, to another recipient and with another amount, stays within 5%
+    assert_gas_within(&mint(CAROL, U256::from(42)), baseline, 5);
+}
+
+#[test]
+#[should_panic(expected = "not within 5%")]
+fn test_erc20_mint_gas_regression() {
+    let ERC20Setup {
+        mut db,
+        token,
+        owner,
+    } = erc20_setup(ALICE);
+    let selector_mint = get_selector_from_sig("mint(address,uint256)");
+    let calldata_mint = get_calldata(selector_mint, (BOB, U256::from(1e18)).abi_encode());
+    let result = run_tx(&mut db, &token, calldata_mint, &owner).expect("Error executing tx");
+
+    // A baseline 10% below the gas used is a regression
+    assert_gas_within(&result, result.gas_used * 9 / 10, 5);
+}
+
 #[test]
 fn test_erc20_zero_address_checks() {
     let ERC20Setup {
@@ -504,3 +713,101 @@ fn test_erc20_self_transfer() {
         "Incorrect error signature"
     );
 }
+
+#[test]
+fn test_erc20_storage_trace() {
+    let ERC20Setup {
+        mut db,
+        token,
+        owner,
+    } = erc20_setup(ALICE);
+
+    let mint_amount = U256::from(42e18);
+    let selector_mint = get_selector_from_sig("mint(address,uint256)");
+    let calldata_mint = get_calldata(selector_mint, (BOB, mint_amount).abi_encode());
+
+    let result =
+        simulate_call_trace(&mut db, &token, calldata_mint, &owner).expect("Error executing tx");
+    let stores: Vec<_> = result
+        .storage_accesses
+        .iter()
+        .filter(|access| access.address == token && access.is_store)
+        .map(|access| (access.key, access.value))
+        .collect();
+
+    // `total_supply` (slot 0) and Bob's entry of `balance_of` (mapping id 1) are written
+    let balance_slot = get_mapping_slot(BOB.abi_encode(), U256::from(1));
+    assert!(
+        stores.contains(&(balance_slot, mint_amount)),
+        "Balance not stored"
+    );
+    assert!(
+        stores.contains(&(U256::ZERO, mint_amount)),
+        "Total supply not stored"
+    );
+
+    // The owner check loads slot 3 first
+    let first_load = &result.storage_accesses[0];
+    assert!(!first_load.is_store);
+    assert_eq!(first_load.key, U256::from(3));
+    assert_eq!(first_load.value, U256::from_be_bytes(owner.into_word().0));
+
+    // Simulations don't commit any state change, nor trace by default
+    assert_eq!(read_db_slot(&mut db, token, balance_slot), U256::ZERO);
+    let result = run_tx(
+        &mut db,
+        &token,
+        get_selector_from_sig("total_supply()").to_vec(),
+        &ALICE,
+    )
+    .expect("Error executing tx");
+    assert!(result.storage_accesses.is_empty());
+}
+
+#[test]
+fn test_erc20_renounce_ownership() {
+    let ERC20Setup {
+        mut db,
+        token,
+        owner,
+    } = erc20_setup(ALICE);
+
+    // Transferring the ownership to the zero address is rejected, to prevent accidental renounces
+    let selector_transfer_ownership = get_selector_from_sig("transfer_ownership(address)");
+    let calldata_transfer_ownership =
+        get_calldata(selector_transfer_ownership, Address::ZERO.abi_encode());
+    let err = run_tx(&mut db, &token, calldata_transfer_ownership, &owner)
+        .expect_err("Transfer to the zero address succeeded when it should fail");
+    assert!(
+        err.matches_custom_error("ERC20Error::ZeroAddress"),
+        "Incorrect error signature"
+    );
+
+    // Only the owner can renounce
+    let selector_renounce = get_selector_from_sig("renounce_ownership()");
+    let err = run_tx(&mut db, &token, selector_renounce.to_vec(), &BOB)
+        .expect_err("Renounce succeeded when it should fail");
+    assert!(
+        err.matches_custom_error("ERC20Error::OnlyOwner"),
+        "Incorrect error signature"
+    );
+
+    let result =
+        run_tx(&mut db, &token, selector_renounce.to_vec(), &owner).expect("Error executing tx");
+    assert!(result.status, "Renounce transaction failed");
+
+    let selector_owner = get_selector_from_sig("owner()");
+    let new_owner: Address =
+        query(&mut db, &token, selector_owner.to_vec(), &ALICE).expect("Error executing query");
+    assert_eq!(new_owner, Address::ZERO, "Ownership not renounced");
+
+    // Owner-only methods can't be called anymore, not even by the former owner
+    let selector_mint = get_selector_from_sig("mint(address,uint256)");
+    let calldata_mint = get_calldata(selector_mint, (BOB, U256::from(1e18)).abi_encode());
+    let err = run_tx(&mut db, &token, calldata_mint, &owner)
+        .expect_err("Mint succeeded when it should fail");
+    assert!(
+        err.matches_custom_error("ERC20Error::OnlyOwner"),
+        "Incorrect error signature"
+    );
+}
@@ -53,6 +53,24 @@ fn test_erc721_deployment() {
     );
 }
 
+#[test]
+fn test_erc721_deployment_reverts() {
+    initialize_logger();
+    let mut db = InMemoryDB::default();
+
+    // A reverted constructor must not yield a contract address
+    let constructor = Address::ZERO.abi_encode();
+    let bytecode = get_bytecode("erc721");
+    let err =
+        deploy_contract(&mut db, bytecode, Some(constructor)).expect_err("Deployment should fail");
+
+    // The error carries the revert reason of the constructor
+    assert!(
+        err.matches_string_error("Owner can't be the zero address"),
+        "Incorrect revert reason"
+    );
+}
+
 #[test]
 fn test_erc721_mint() {
     let ERC721Setup {
@@ -191,6 +209,46 @@ fn test_erc721_set_approval_for_all() {
     assert_eq!(is_approved_result[31], 1, "Incorrect approval status");
 }
 
+#[test]
+fn test_erc721_set_approval_for_all_bool_encoding() {
+    let ERC721Setup {
+        mut db,
+        token,
+        owner,
+    } = erc721_setup(ALICE);
+    let (token_owner, operator) = (BOB, CAROL);
+
+    let selector_set_approval = get_selector_from_sig("set_approval_for_all(address,bool)");
+    let selector_is_approved = get_selector_from_sig("is_approved_for_all(address,address)");
+    let calldata_is_approved =
+        get_calldata(selector_is_approved, (token_owner, operator).abi_encode());
+
+    // Canonical `true` and `false` words are accepted
+    for approved in [true, false] {
+        let calldata = get_calldata(selector_set_approval, (operator, approved).abi_encode());
+        run_tx(&mut db, &token, calldata, &token_owner).expect("Error executing tx");
+
+        let is_approved_result = run_tx(&mut db, &token, calldata_is_approved.clone(), &owner)
+            .expect("Error executing tx")
+            .output;
+        assert_eq!(
+            is_approved_result[31], approved as u8,
+            "Incorrect approval status"
+        );
+    }
+
+    // A non-canonical bool word (0x02) is rejected by the strict decoding
+    let mut args = (operator, true).abi_encode();
+    args[63] = 2;
+    let calldata = get_calldata(selector_set_approval, args);
+    run_tx(&mut db, &token, calldata, &token_owner).expect_err("Tx succeeded");
+
+    let is_approved_result = run_tx(&mut db, &token, calldata_is_approved, &owner)
+        .expect("Error executing tx")
+        .output;
+    assert_eq!(is_approved_result[31], 0, "Approval status was modified");
+}
+
 #[test]
 fn test_erc721_mint_already_exists() {
     let ERC721Setup {
@@ -371,3 +429,48 @@ fn test_erc721_query_non_existent_token() {
         "Incorrect error signature"
     );
 }
+
+#[test]
+fn test_erc721_renounce_ownership() {
+    let ERC721Setup {
+        mut db,
+        token,
+        owner,
+    } = erc721_setup(ALICE);
+
+    // Transferring the ownership to the zero address is rejected, to prevent accidental renounces
+    let selector_transfer_ownership = get_selector_from_sig("transfer_ownership(address)");
+    let calldata_transfer_ownership =
+        get_calldata(selector_transfer_ownership, Address::ZERO.abi_encode());
+    let err = run_tx(&mut db, &token, calldata_transfer_ownership, &owner)
+        .expect_err("Transfer to the zero address succeeded when it should fail");
+    assert!(
+        err.matches_custom_error("ERC721Error::ZeroAddress"),
+        "Incorrect error signature"
+    );
+
+    let selector_renounce = get_selector_from_sig("renounce_ownership()");
+    let result =
+        run_tx(&mut db, &token, selector_renounce.to_vec(), &owner).expect("Error executing tx");
+    assert!(result.status, "Renounce transaction failed");
+
+    let selector_owner = get_selector_from_sig("owner()");
+    let owner_result = run_tx(&mut db, &token, selector_owner.to_vec(), &ALICE)
+        .expect("Error executing tx")
+        .output;
+    assert_eq!(
+        Address::from_word(B256::from_slice(owner_result.as_slice())),
+        Address::ZERO,
+        "Ownership not renounced"
+    );
+
+    // No more tokens can be minted, not even by the former owner
+    let selector_mint = get_selector_from_sig("mint(address,uint256)");
+    let calldata_mint = get_calldata(selector_mint, (BOB, U256::from(1)).abi_encode());
+    let err = run_tx(&mut db, &token, calldata_mint, &owner)
+        .expect_err("Mint succeeded when it should fail");
+    assert!(
+        err.matches_custom_error("ERC721Error::OnlyOwner"),
+        "Incorrect error signature"
+    );
+}
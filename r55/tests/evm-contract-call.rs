@@ -1,19 +1,43 @@
 use alloy_primitives::{Bytes, U256};
 use alloy_sol_types::SolValue;
 use r55::{
-    exec::{deploy_contract, run_tx},
+    exec::{
+        deploy_contract, run_create_tx, run_tx, run_tx_with_reentrancy_detection,
+        run_tx_with_static_call_cache, run_tx_with_value,
+    },
     get_bytecode,
     test_utils::{
-        add_balance_to_db, get_selector_from_sig, initialize_logger, load_bytecode_from_file,
+        account_info, add_balance_to_db, create2_address, get_calldata, get_selector_from_sig,
+        has_code, initialize_logger, load_bytecode_from_file, load_state_fixture, read_db_slot,
+        ALICE, BOB, KECCAK_EMPTY,
     },
 };
 use revm::{
-    primitives::{address, Address},
+    primitives::{address, b256, keccak256, Address, B256},
     InMemoryDB,
 };
 use tracing::{debug, error, info};
 
 const EVM_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/simple-evm-contract.txt");
+const FORWARDER_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/forwarder-evm-contract.txt"
+);
+const CREATE2_FACTORY_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/create2-factory-evm-contract.txt"
+);
+const SINK_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/sink-evm-contract.txt");
+const STATE_FIXTURE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/state-fixture.toml");
+const BUBBLER_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/bubbler-evm-contract.txt"
+);
+const REVERTER_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/reverter-evm-contract.txt"
+);
+const ECHO_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/echo-evm-contract.txt");
 
 // ------------------------------------------------------------------------------------------------
 //    SIMPLE EVM CONTRACT
@@ -191,3 +215,838 @@ fn evm_call() {
         }
     };
 }
+
+#[test]
+fn evm_create_tx() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+
+    // A top-level CREATE tx, rather than a call, reports the address of the new contract
+    let init_code = load_bytecode_from_file(EVM_PATH).to_vec();
+    let res = run_create_tx(&mut db, init_code, &ALICE).expect("Error executing tx");
+    let evm = res.created_address.expect("No created address");
+    assert_eq!(evm, ALICE.create(0));
+    assert!(has_code(&mut db, evm), "Created contract has no code");
+    assert!(!res.is_empty_output(), "No runtime code returned");
+
+    // The created contract is callable like any deployed one
+    let selector_get = get_selector_from_sig("get()");
+    let res = run_tx(&mut db, &evm, selector_get.to_vec(), &ALICE).expect("Error executing tx");
+    assert_eq!(U256::from_be_slice(&res.output), U256::ZERO);
+    assert_eq!(res.created_address, None);
+}
+
+#[test]
+fn evm_call_raw_output() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+
+    let bytecode_evm = load_bytecode_from_file(EVM_PATH);
+    let bytecode_r55 = get_bytecode("evm_caller");
+    let evm = deploy_contract(&mut db, bytecode_evm, None).unwrap();
+    let r55 = deploy_contract(&mut db, bytecode_r55, None).unwrap();
+
+    let selector_set = get_selector_from_sig("set(uint256)");
+    let selector_x_get_raw = get_selector_from_sig("x_get_raw(address)");
+
+    let alice: Address = address!("000000000000000000000000000000000000000A");
+    add_balance_to_db(&mut db, alice, 1e18 as u64);
+
+    // Set value in the EVM contract
+    let value_set = U256::from(7e18);
+    let mut complete_calldata_set = selector_set.to_vec();
+    complete_calldata_set.append(&mut value_set.abi_encode());
+    run_tx(&mut db, &evm, complete_calldata_set, &alice).expect("Error executing tx");
+
+    // call traces: r55.x_get_raw() -> evm.get()
+    let mut complete_calldata_x_get_raw = selector_x_get_raw.to_vec();
+    complete_calldata_x_get_raw.append(&mut evm.abi_encode());
+    let res =
+        run_tx(&mut db, &r55, complete_calldata_x_get_raw, &alice).expect("Error executing tx");
+
+    // Raw `Bytes` are returned verbatim, without being ABI-encoded again
+    assert_eq!(res.output.len(), 32, "Raw output was re-encoded");
+    assert_eq!(
+        U256::from_be_bytes::<32>(res.output.as_slice().try_into().unwrap()),
+        value_set
+    );
+}
+
+#[test]
+fn evm_call_reentrancy() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+
+    let bytecode_evm = load_bytecode_from_file(EVM_PATH);
+    let evm = deploy_contract(&mut db, bytecode_evm, None).unwrap();
+    let r55 = deploy_contract(&mut db, get_bytecode("evm_caller"), None).unwrap();
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+    let calldata_x_set = get_calldata(
+        get_selector_from_sig("x_set(address,uint256)"),
+        (evm, U256::from(1e18)).abi_encode(),
+    );
+
+    // call traces: r55.x_set() -> evm.set()
+    let res = run_tx_with_reentrancy_detection(&mut db, &r55, calldata_x_set.clone(), &ALICE)
+        .expect("Error executing tx");
+    assert!(res.reentrancies.is_empty(), "False reentrancy reported");
+
+    // call traces: evm.rawCall() -> r55.x_set() -> evm.set()
+    let calldata_raw_call = get_calldata(
+        get_selector_from_sig("rawCall((address,bytes))"),
+        (r55, Bytes::from(calldata_x_set)).abi_encode(),
+    );
+    let res = run_tx_with_reentrancy_detection(&mut db, &evm, calldata_raw_call.clone(), &ALICE)
+        .expect("Error executing tx");
+    assert_eq!(res.reentrancies.len(), 1, "Reentrancy not reported");
+    assert_eq!(res.reentrancies[0].address, evm);
+    assert_eq!(res.reentrancies[0].depth, 2);
+
+    // Detection is opt-in
+    let res = run_tx(&mut db, &evm, calldata_raw_call, &ALICE).expect("Error executing tx");
+    assert!(res.reentrancies.is_empty());
+}
+
+#[test]
+fn evm_call_oversized_calldata() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+
+    let bytecode_evm = load_bytecode_from_file(EVM_PATH);
+    let evm = deploy_contract(&mut db, bytecode_evm, None).unwrap();
+    let r55 = deploy_contract(&mut db, get_bytecode("evm_caller"), None).unwrap();
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+    let selector_x_call = get_selector_from_sig("x_call_with_size(address,uint64)");
+
+    // call traces: r55.x_call_with_size() -> evm.fallback() (reverts on the unknown selector)
+    let calldata = get_calldata(selector_x_call, (evm, 4_u64).abi_encode_params());
+    let res = run_tx(&mut db, &r55, calldata, &ALICE).expect("Error executing tx");
+    assert!(!bool::abi_decode(&res.output, true).unwrap());
+
+    // Calldata that doesn't fit in the DRAM reverts the caller, without being copied
+    for size in [1_u64 << 40, u64::MAX] {
+        let calldata = get_calldata(selector_x_call, (evm, size).abi_encode_params());
+        let err = run_tx(&mut db, &r55, calldata, &ALICE)
+            .expect_err("Oversized call succeeded when it should fail");
+        assert!(err.matches_string_error(""), "Unexpected error: {:?}", err);
+    }
+}
+
+#[test]
+fn evm_call_static_call_cache() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+
+    let bytecode_evm = load_bytecode_from_file(EVM_PATH);
+    let evm = deploy_contract(&mut db, bytecode_evm, None).unwrap();
+    let r55 = deploy_contract(&mut db, get_bytecode("evm_caller"), None).unwrap();
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+    let value = U256::from(3e18);
+    let calldata_set = get_calldata(get_selector_from_sig("set(uint256)"), value.abi_encode());
+    run_tx(&mut db, &evm, calldata_set, &ALICE).expect("Error executing tx");
+
+    // call traces: r55.x_get_twice() -> evm.get() -> evm.get()
+    let calldata_x_get_twice = get_calldata(
+        get_selector_from_sig("x_get_twice(address)"),
+        evm.abi_encode(),
+    );
+    let uncached =
+        run_tx(&mut db, &r55, calldata_x_get_twice.clone(), &ALICE).expect("Error executing tx");
+    // call traces: r55.x_get_twice() -> evm.get()
+    let cached = run_tx_with_static_call_cache(&mut db, &r55, calldata_x_get_twice, &ALICE)
+        .expect("Error executing tx");
+
+    // The second staticcall is served from the cache, without running a frame for `evm.get()`
+    assert_eq!(cached.output, uncached.output);
+    assert_eq!(
+        <(U256, U256)>::abi_decode(&cached.output, true).unwrap(),
+        (value, value)
+    );
+    assert!(
+        cached.gas_used < uncached.gas_used,
+        "Staticcall not cached: {} vs {}",
+        cached.gas_used,
+        uncached.gas_used
+    );
+
+    // Modifying the state invalidates the cached outputs
+    let new_value = U256::from(5e18);
+    let calldata_x_get_set_get = get_calldata(
+        get_selector_from_sig("x_get_set_get(address,uint256)"),
+        (evm, new_value).abi_encode(),
+    );
+    let res = run_tx_with_static_call_cache(&mut db, &r55, calldata_x_get_set_get, &ALICE)
+        .expect("Error executing tx");
+    assert_eq!(
+        <(U256, U256)>::abi_decode(&res.output, true).unwrap(),
+        (value, new_value)
+    );
+
+    // Cached outputs aren't reused by calls forwarding less gas than the original one used
+    // call traces: r55.x_get_then_capped() -> evm.get() -> evm.get()
+    for (gas, expected) in [(100_u64, (true, false)), (100_000, (true, true))] {
+        let calldata = get_calldata(
+            get_selector_from_sig("x_get_then_capped(address,uint64)"),
+            (evm, gas).abi_encode_params(),
+        );
+        let res = run_tx_with_static_call_cache(&mut db, &r55, calldata, &ALICE)
+            .expect("Error executing tx");
+        assert_eq!(
+            <(bool, bool)>::abi_decode(&res.output, true).unwrap(),
+            expected,
+            "Unexpected outcome with {} gas",
+            gas
+        );
+    }
+}
+
+#[test]
+fn evm_call_revert_refunds_gas() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+
+    let bytecode_evm = load_bytecode_from_file(EVM_PATH);
+    let evm = deploy_contract(&mut db, bytecode_evm, None).unwrap();
+    let r55 = deploy_contract(&mut db, get_bytecode("evm_caller"), None).unwrap();
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+
+    // Sub-calls are given all the remaining gas, which is pre-spent by the caller. Calls which
+    // revert early must refund it, rather than consuming most of the tx gas limit (100M).
+    let unknown_selector = Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]);
+    for target in [evm, r55] {
+        // call traces: r55.x_send() -> (evm|r55).fallback() (reverts on the unknown selector)
+        let calldata = get_calldata(
+            get_selector_from_sig("x_send(address,bytes)"),
+            (target, unknown_selector.clone()).abi_encode_params(),
+        );
+        let res = run_tx(&mut db, &r55, calldata, &ALICE).expect("Error executing tx");
+        assert!(
+            !bool::abi_decode(&res.output, true).unwrap(),
+            "Sub-call didn't revert"
+        );
+        assert!(
+            res.gas_used < 50_000_000,
+            "Gas of the reverted sub-call to {} wasn't refunded: {}",
+            target,
+            res.gas_used
+        );
+    }
+}
+
+#[test]
+fn evm_call_out_of_gas() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+
+    let bytecode_evm = load_bytecode_from_file(EVM_PATH);
+    let evm = deploy_contract(&mut db, bytecode_evm, None).unwrap();
+    let r55 = deploy_contract(&mut db, get_bytecode("evm_caller"), None).unwrap();
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+
+    let selector_get = get_selector_from_sig("x_get(address)");
+    let selector_set = get_selector_from_sig("x_set_with_gas(address,uint256,uint64)");
+    let value = U256::from(42);
+
+    // call traces: r55.x_set_with_gas() -> evm.set() (out of gas, as SSTORE costs 22100)
+    let calldata_set = get_calldata(selector_set, (evm, value, 10_000_u64).abi_encode_params());
+    let res = run_tx(&mut db, &r55, calldata_set, &ALICE).expect("Error executing tx");
+    assert!(
+        !bool::abi_decode(&res.output, true).unwrap(),
+        "Sub-call didn't run out of gas"
+    );
+
+    // The parent survives the failed sub-call, whose changes are discarded
+    let calldata_get = get_calldata(selector_get, evm.abi_encode());
+    let res = run_tx(&mut db, &r55, calldata_get.clone(), &ALICE).expect("Error executing tx");
+    assert_eq!(U256::from_be_slice(&res.output), U256::ZERO);
+
+    // call traces: r55.x_set_with_gas() -> evm.set()
+    let calldata_set = get_calldata(selector_set, (evm, value, 100_000_u64).abi_encode_params());
+    let res = run_tx(&mut db, &r55, calldata_set, &ALICE).expect("Error executing tx");
+    assert!(
+        bool::abi_decode(&res.output, true).unwrap(),
+        "Sub-call failed"
+    );
+
+    let res = run_tx(&mut db, &r55, calldata_get, &ALICE).expect("Error executing tx");
+    assert_eq!(U256::from_be_slice(&res.output), value);
+}
+
+#[test]
+fn evm_call_codeless_target() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+
+    let r55 = deploy_contract(&mut db, get_bytecode("evm_caller"), None).unwrap();
+    let codeless = address!("000000000000000000000000000000000000c0de");
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+
+    // Like in the EVM, calls to addresses without code succeed with empty output
+    let mut call = |sig: &str, args: Vec<u8>| {
+        let calldata = get_calldata(get_selector_from_sig(sig), args);
+        run_tx(&mut db, &r55, calldata, &ALICE)
+            .expect("Error executing tx")
+            .output
+    };
+
+    // call traces: r55.x_set() -> codeless.set()
+    let output = call(
+        "x_set(address,uint256)",
+        (codeless, U256::from(42)).abi_encode_params(),
+    );
+    assert!(output.is_empty());
+
+    // call traces: r55.x_(static_)send() -> codeless
+    for sig in ["x_send(address,bytes)", "x_static_send(address,bytes)"] {
+        let output = call(sig, (codeless, Bytes::new()).abi_encode_params());
+        assert!(bool::abi_decode(&output, true).unwrap(), "{} failed", sig);
+    }
+
+    // call traces: r55.x_get_raw() -> codeless.get()
+    let output = call("x_get_raw(address)", codeless.abi_encode());
+    assert!(Bytes::abi_decode(&output, true).unwrap().is_empty());
+
+    // The empty output can't be decoded as `uint256`, which the caller handles gracefully
+    // call traces: r55.x_get_or_default() -> codeless.get()
+    let output = call("x_get_or_default(address)", codeless.abi_encode());
+    assert_eq!(U256::abi_decode(&output, true).unwrap(), U256::ZERO);
+}
+
+#[test]
+fn evm_call_empty_output_vs_revert() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+
+    let bytecode_evm = load_bytecode_from_file(EVM_PATH);
+    let evm = deploy_contract(&mut db, bytecode_evm, None).unwrap();
+    let r55 = deploy_contract(&mut db, get_bytecode("evm_caller"), None).unwrap();
+    let r55_weth = deploy_contract(&mut db, get_bytecode("weth"), None).unwrap();
+    let codeless = address!("000000000000000000000000000000000000c0de");
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+
+    // call traces: r55.x_try_get() -> target.get()
+    let mut try_get = |target: Address| {
+        let calldata = get_calldata(
+            get_selector_from_sig("x_try_get(address)"),
+            target.abi_encode(),
+        );
+        let res = run_tx(&mut db, &r55, calldata, &ALICE).expect("Error executing tx");
+        <(bool, bool)>::abi_decode(&res.output, true).unwrap()
+    };
+
+    // (succeeded, decoded)
+    assert_eq!(try_get(evm), (true, true));
+    // Code-less targets succeed with an empty output, which can't be decoded
+    assert_eq!(try_get(codeless), (true, false));
+    // WETH has no `get()`, so the staticcall reverts
+    assert_eq!(try_get(r55_weth), (false, false));
+}
+
+#[test]
+fn evm_call_static_log() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+
+    let r55 = deploy_contract(&mut db, get_bytecode("evm_caller"), None).unwrap();
+    let logger = deploy_contract(&mut db, get_bytecode("memory"), None).unwrap();
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+
+    let log_at = Bytes::from(get_calldata(
+        get_selector_from_sig("log_at(uint64,uint64)"),
+        (0x8000_0000_u64, 4_u64).abi_encode_params(),
+    ));
+    let touch = Bytes::from(get_calldata(
+        get_selector_from_sig("touch(uint64)"),
+        1_u64.abi_encode(),
+    ));
+
+    // call traces: r55.x_(static_)send() -> logger.(log_at|touch)()
+    let mut send = |sig: &str, data: &Bytes| {
+        let calldata = get_calldata(
+            get_selector_from_sig(sig),
+            (logger, data.clone()).abi_encode_params(),
+        );
+        let res = run_tx(&mut db, &r55, calldata, &ALICE).expect("Error executing tx");
+        (bool::abi_decode(&res.output, true).unwrap(), res.logs.len())
+    };
+
+    // Logs can only be emitted outside of static frames
+    assert_eq!(send("x_send(address,bytes)", &log_at), (true, 1));
+    assert_eq!(send("x_static_send(address,bytes)", &log_at), (false, 0));
+    assert_eq!(send("x_static_send(address,bytes)", &touch), (true, 0));
+}
+
+#[test]
+fn evm_call_static_state_change() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+
+    let r55 = deploy_contract(&mut db, get_bytecode("evm_caller"), None).unwrap();
+    let target = deploy_contract(&mut db, get_bytecode("memory"), None).unwrap();
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+    add_balance_to_db(&mut db, target, 1e18 as u64);
+
+    let key = U256::from(1);
+    let store_at = Bytes::from(get_calldata(
+        get_selector_from_sig("store_at(uint256,uint256)"),
+        (key, U256::from(42)).abi_encode_params(),
+    ));
+    let send_wei = Bytes::from(get_calldata(
+        get_selector_from_sig("send_wei(address)"),
+        BOB.abi_encode(),
+    ));
+
+    // call traces: r55.x_(static_)send() -> target.(store_at|send_wei)()
+    let mut send = |sig: &str, data: &Bytes| {
+        let calldata = get_calldata(
+            get_selector_from_sig(sig),
+            (target, data.clone()).abi_encode_params(),
+        );
+        let res = run_tx(&mut db, &r55, calldata, &ALICE).expect("Error executing tx");
+        bool::abi_decode(&res.output, true).unwrap()
+    };
+
+    // Storage writes and value transfers halt static frames
+    assert!(!send("x_static_send(address,bytes)", &store_at));
+    assert!(!send("x_static_send(address,bytes)", &send_wei));
+
+    // Outside of static frames, both go through
+    assert!(send("x_send(address,bytes)", &send_wei));
+    assert_eq!(read_db_slot(&mut db, target, key), U256::ZERO);
+    assert!(send("x_send(address,bytes)", &store_at));
+    assert_eq!(read_db_slot(&mut db, target, key), U256::from(42));
+}
+
+// ------------------------------------------------------------------------------------------------
+//    FORWARDER EVM CONTRACT (hand-assembled)
+// ------------------------------------------------------------------------------------------------
+//    Forwards `msg.value` to the address in the first calldata word (with empty calldata), and
+//    returns the success flag of the call instead of bubbling up reverts:
+//
+//    CALL(GAS, CALLDATALOAD(0), CALLVALUE, 0, 0, 0, 0)
+//    MSTORE(0, success)
+//    RETURN(0, 32)
+// ------------------------------------------------------------------------------------------------
+
+fn balance(db: &mut InMemoryDB, addr: Address) -> U256 {
+    db.load_account(addr)
+        .expect("Unable to load account")
+        .info
+        .balance
+}
+
+#[test]
+fn evm_value_transfer() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+
+    let bytecode_forwarder = load_bytecode_from_file(FORWARDER_PATH);
+    let forwarder = deploy_contract(&mut db, bytecode_forwarder, None).unwrap();
+    let r55_caller = deploy_contract(&mut db, get_bytecode("evm_caller"), None).unwrap();
+    let r55_weth = deploy_contract(&mut db, get_bytecode("weth"), None).unwrap();
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+    let value = U256::from(1e16);
+
+    // call traces: evm.forward{value}() -> r55_weth.receive{value}()
+    let res = run_tx_with_value(&mut db, &forwarder, r55_weth.abi_encode(), &ALICE, value)
+        .expect("Error executing tx");
+    assert_eq!(U256::from_be_slice(&res.output), U256::from(1));
+    assert_eq!(balance(&mut db, r55_weth), value, "R55 contract not funded");
+    assert_eq!(balance(&mut db, forwarder), U256::ZERO);
+
+    // call traces: r55_caller.x_send{value}() -> evm.forward{value}() -> BOB
+    let bob_balance = balance(&mut db, BOB);
+    let calldata_x_send = get_calldata(
+        get_selector_from_sig("x_send(address,bytes)"),
+        (forwarder, Bytes::from(BOB.abi_encode())).abi_encode_params(),
+    );
+    let res = run_tx_with_value(&mut db, &r55_caller, calldata_x_send, &ALICE, value)
+        .expect("Error executing tx");
+    assert!(
+        bool::abi_decode(&res.output, true).unwrap(),
+        "Value transfer failed"
+    );
+    assert_eq!(balance(&mut db, BOB), bob_balance + value, "EOA not funded");
+    assert_eq!(balance(&mut db, r55_caller), U256::ZERO);
+    assert_eq!(balance(&mut db, forwarder), U256::ZERO);
+
+    // call traces: evm.forward{value}() -> r55_caller (no `receive`, reverts)
+    let res = run_tx_with_value(&mut db, &forwarder, r55_caller.abi_encode(), &ALICE, value)
+        .expect("Error executing tx");
+    assert_eq!(
+        U256::from_be_slice(&res.output),
+        U256::ZERO,
+        "Call should fail"
+    );
+    assert_eq!(
+        balance(&mut db, r55_caller),
+        U256::ZERO,
+        "Value not rolled back"
+    );
+    assert_eq!(
+        balance(&mut db, forwarder),
+        value,
+        "Value not kept by the caller"
+    );
+}
+
+// ------------------------------------------------------------------------------------------------
+//    SINK EVM CONTRACT (hand-assembled)
+// ------------------------------------------------------------------------------------------------
+//    Accepts any call, logging an empty event (375 gas, within the 2300 gas stipend):
+//
+//    LOG0(0, 0)
+//    STOP
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn evm_send_eth_stipend() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+
+    let sink = deploy_contract(&mut db, load_bytecode_from_file(SINK_PATH), None).unwrap();
+    let r55_caller = deploy_contract(&mut db, get_bytecode("evm_caller"), None).unwrap();
+    let r55_weth = deploy_contract(&mut db, get_bytecode("weth"), None).unwrap();
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+    let value = U256::from(1e16);
+    let selector_x_send_eth = get_selector_from_sig("x_send_eth(address)");
+
+    // call traces: r55_caller.x_send_eth{value}() -> sink (no gas forwarded, logs within the stipend)
+    let calldata = get_calldata(selector_x_send_eth, sink.abi_encode());
+    let res = run_tx_with_value(&mut db, &r55_caller, calldata, &ALICE, value)
+        .expect("Error executing tx");
+    assert!(bool::abi_decode(&res.output, true).unwrap(), "Send failed");
+    assert_eq!(res.logs.len(), 1, "Recipient didn't run");
+    assert_eq!(balance(&mut db, sink), value, "Recipient not funded");
+
+    // call traces: r55_caller.x_send_eth{value}() -> r55_weth.receive() (SSTORE, out of gas)
+    let calldata = get_calldata(selector_x_send_eth, r55_weth.abi_encode());
+    let res = run_tx_with_value(&mut db, &r55_caller, calldata, &ALICE, value)
+        .expect("Send failure should be handled by the caller");
+    assert!(
+        !bool::abi_decode(&res.output, true).unwrap(),
+        "Send should run out of gas"
+    );
+    assert_eq!(
+        balance(&mut db, r55_weth),
+        U256::ZERO,
+        "Value not rolled back"
+    );
+    assert_eq!(
+        balance(&mut db, r55_caller),
+        value,
+        "Value not kept by the caller"
+    );
+}
+
+#[test]
+fn evm_msg_value_per_frame() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+
+    let r55_a = deploy_contract(&mut db, get_bytecode("evm_caller"), None).unwrap();
+    let r55_b = deploy_contract(&mut db, get_bytecode("evm_caller"), None).unwrap();
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+    let (value, forwarded) = (U256::from(1e16), U256::from(4e15));
+    let selector_split = get_selector_from_sig("x_split_value(address,uint256,bytes)");
+
+    // call traces: r55_a.x_split_value{value}() -> r55_b.x_split_value{forwarded}() -> BOB
+    let calldata_b = get_calldata(
+        selector_split,
+        (BOB, U256::ZERO, Bytes::new()).abi_encode_params(),
+    );
+    let calldata_a = get_calldata(
+        selector_split,
+        (r55_b, forwarded, Bytes::from(calldata_b)).abi_encode_params(),
+    );
+    let res =
+        run_tx_with_value(&mut db, &r55_a, calldata_a, &ALICE, value).expect("Error executing tx");
+
+    // Each frame reads its own `msg.value`: the tx value in A, and the forwarded part in B
+    let (received_a, output_a) = <(U256, Bytes)>::abi_decode(&res.output, true).unwrap();
+    let (received_b, output_b) = <(U256, Bytes)>::abi_decode(&output_a, true).unwrap();
+    assert_eq!(received_a, value, "Incorrect msg.value in A");
+    assert_eq!(received_b, forwarded, "Incorrect msg.value in B");
+    assert!(output_b.is_empty());
+
+    assert_eq!(balance(&mut db, r55_a), value - forwarded);
+    assert_eq!(balance(&mut db, r55_b), forwarded);
+}
+
+#[test]
+fn evm_value_transfer_rollback() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+
+    let bytecode_evm = load_bytecode_from_file(EVM_PATH);
+    let evm = deploy_contract(&mut db, bytecode_evm, None).unwrap();
+    let r55_caller = deploy_contract(&mut db, get_bytecode("evm_caller"), None).unwrap();
+    let r55_weth = deploy_contract(&mut db, get_bytecode("weth"), None).unwrap();
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+    let value = U256::from(1e16);
+    let unknown_selector = Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]);
+
+    for (i, target) in [evm, r55_weth].into_iter().enumerate() {
+        // call traces: r55_caller.x_send{value}() -> (evm|r55_weth).fallback{value}() (reverts)
+        let calldata_x_send = get_calldata(
+            get_selector_from_sig("x_send(address,bytes)"),
+            (target, unknown_selector.clone()).abi_encode_params(),
+        );
+        let res = run_tx_with_value(&mut db, &r55_caller, calldata_x_send, &ALICE, value)
+            .expect("Error executing tx");
+
+        // The caller handles the failure and keeps the value, without paying for the pre-spent gas
+        assert!(
+            !bool::abi_decode(&res.output, true).unwrap(),
+            "Call should fail"
+        );
+        assert!(
+            res.gas_used < 50_000_000,
+            "Gas not refunded: {}",
+            res.gas_used
+        );
+        assert_eq!(
+            balance(&mut db, target),
+            U256::ZERO,
+            "Value not rolled back"
+        );
+        assert_eq!(
+            balance(&mut db, r55_caller),
+            value * U256::from(i + 1),
+            "Value not kept by the caller"
+        );
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+//    CREATE2 FACTORY EVM CONTRACT (hand-assembled)
+// ------------------------------------------------------------------------------------------------
+//    Deterministic deployment proxy: deploys the init code in `calldata[32..]` with `CREATE2`,
+//    using the first calldata word as salt, and returns the 20 bytes of the new address:
+//
+//    CALLDATACOPY(0, 32, CALLDATASIZE - 32)
+//    addr := CREATE2(CALLVALUE, 0, CALLDATASIZE - 32, CALLDATALOAD(0))
+//    if iszero(addr) { REVERT(0, 0) }
+//    MSTORE(0, addr)
+//    RETURN(12, 20)
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn evm_create2_address() {
+    initialize_logger();
+
+    // Reference vector from EIP-1014
+    let deployer = address!("deadbeef00000000000000000000000000000000");
+    assert_eq!(
+        create2_address(deployer, B256::ZERO, keccak256([0x00])),
+        address!("B928f69Bb1D91Cd65274e3c79d8986362984fDA3")
+    );
+
+    let mut db = InMemoryDB::default();
+
+    let bytecode_factory = load_bytecode_from_file(CREATE2_FACTORY_PATH);
+    let factory = deploy_contract(&mut db, bytecode_factory, None).unwrap();
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+
+    let init_code = load_bytecode_from_file(EVM_PATH);
+    let salt = b256!("0000000000000000000000000000000000000000000000000000000000000055");
+    let predicted = create2_address(factory, salt, keccak256(&init_code));
+    assert!(account_info(&mut db, predicted).is_none());
+
+    // call traces: factory.deploy() -> CREATE2(init_code)
+    let calldata = [salt.as_slice(), &init_code].concat();
+    let res = run_tx(&mut db, &factory, calldata, &ALICE).expect("Error executing tx");
+    assert_eq!(Address::from_slice(&res.output), predicted);
+
+    let info = account_info(&mut db, predicted).expect("Contract not deployed");
+    assert_ne!(
+        info.code_hash, KECCAK_EMPTY,
+        "Deployed contract has no code"
+    );
+}
+
+// ------------------------------------------------------------------------------------------------
+//    BUBBLER & REVERTER EVM CONTRACTS (hand-assembled)
+// ------------------------------------------------------------------------------------------------
+//    The bubbler calls the address in the first calldata word with `calldata[32..]`, and returns
+//    or reverts with the output of the call:
+//
+//    CALLDATACOPY(0, 32, CALLDATASIZE - 32)
+//    success := CALL(GAS, CALLDATALOAD(0), 0, 0, CALLDATASIZE - 32, 0, 0)
+//    RETURNDATACOPY(0, 0, RETURNDATASIZE)
+//    if success { RETURN(0, RETURNDATASIZE) }
+//    REVERT(0, RETURNDATASIZE)
+//
+//    The reverter reverts with its calldata:
+//
+//    CALLDATACOPY(0, 0, CALLDATASIZE)
+//    REVERT(0, CALLDATASIZE)
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn evm_revert_reason_round_trip() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+
+    let bubbler = deploy_contract(&mut db, load_bytecode_from_file(BUBBLER_PATH), None).unwrap();
+    let reverter = deploy_contract(&mut db, load_bytecode_from_file(REVERTER_PATH), None).unwrap();
+    let r55 = deploy_contract(&mut db, get_bytecode("evm_caller"), None).unwrap();
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+
+    // `Error(string)` revert, as emitted by `require(false, "Inner call reverted")`
+    let reason = String::from("Inner call reverted").abi_encode();
+    let revert_data = get_calldata(get_selector_from_sig("Error(string)"), reason.clone());
+    let calldata_x_call = get_calldata(
+        get_selector_from_sig("x_call_or_bubble(address,bytes)"),
+        (reverter, Bytes::from(revert_data)).abi_encode_params(),
+    );
+
+    // call traces: r55.x_call_or_bubble() -> reverter (reverts)
+    let err = run_tx(&mut db, &r55, calldata_x_call.clone(), &ALICE)
+        .expect_err("R55 frame should revert");
+    assert!(
+        err.matches_custom_error_with_args("Error(string)", reason.clone()),
+        "Revert reason not bubbled up by the R55 frame"
+    );
+
+    // call traces: bubbler.call() -> r55.x_call_or_bubble() -> reverter (reverts)
+    let calldata_bubbler = [r55.into_word().as_slice(), &calldata_x_call].concat();
+    let err =
+        run_tx(&mut db, &bubbler, calldata_bubbler, &ALICE).expect_err("EVM caller should revert");
+    assert!(
+        err.matches_custom_error_with_args("Error(string)", reason),
+        "Revert reason not intact across the R55 frame"
+    );
+}
+
+#[test]
+fn evm_call_from_state_fixture() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+    load_state_fixture(&mut db, STATE_FIXTURE_PATH);
+
+    let bubbler = address!("00000000000000000000000000000000000000b1");
+    let bubbler_2 = address!("00000000000000000000000000000000000000b2");
+    let storage = address!("0000000000000000000000000000000000000051");
+    let reverter = address!("00000000000000000000000000000000000000e0");
+    let sink = address!("00000000000000000000000000000000000000d0");
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+    assert_eq!(balance(&mut db, bubbler), U256::from(1e18));
+
+    // Storage is pre-populated as well
+    let selector_get = get_selector_from_sig("get()");
+    let res = run_tx(&mut db, &storage, selector_get.to_vec(), &ALICE).expect("Error executing tx");
+    assert_eq!(U256::from_be_slice(&res.output), U256::from(7));
+
+    // call traces: bubbler.call() -> bubbler_2.call() -> storage.set()
+    let calldata_set = get_calldata(
+        get_selector_from_sig("set(uint256)"),
+        U256::from(42).abi_encode(),
+    );
+    let calldata = [
+        bubbler_2.into_word().as_slice(),
+        storage.into_word().as_slice(),
+        &calldata_set,
+    ]
+    .concat();
+    run_tx(&mut db, &bubbler, calldata, &ALICE).expect("Error executing tx");
+    let res = run_tx(&mut db, &storage, selector_get.to_vec(), &ALICE).expect("Error executing tx");
+    assert_eq!(U256::from_be_slice(&res.output), U256::from(42));
+
+    // call traces: bubbler.call() -> sink (logs)
+    let res =
+        run_tx(&mut db, &bubbler, sink.into_word().to_vec(), &ALICE).expect("Error executing tx");
+    assert_eq!(res.logs.len(), 1);
+    assert_eq!(res.logs[0].address, sink);
+
+    // call traces: bubbler.call() -> bubbler_2.call() -> reverter (reverts)
+    let calldata = [
+        bubbler_2.into_word().as_slice(),
+        reverter.into_word().as_slice(),
+        b"reverted",
+    ]
+    .concat();
+    let err = run_tx(&mut db, &bubbler, calldata, &ALICE).expect_err("Call should revert");
+    assert!(err.matches_string_error("reverted"));
+}
+
+// ------------------------------------------------------------------------------------------------
+//    ECHO EVM CONTRACT (hand-assembled)
+// ------------------------------------------------------------------------------------------------
+//    Returns its calldata:
+//
+//    CALLDATACOPY(0, 0, CALLDATASIZE)
+//    RETURN(0, CALLDATASIZE)
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn evm_call_return_data_copy_gas() {
+    initialize_logger();
+
+    let mut db = InMemoryDB::default();
+
+    let echo = deploy_contract(&mut db, load_bytecode_from_file(ECHO_PATH), None).unwrap();
+    let r55 = deploy_contract(&mut db, get_bytecode("evm_caller"), None).unwrap();
+
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+
+    // call traces: r55.x_call_or_bubble() -> echo (returns `size` bytes)
+    let mut echo_data = |size: usize| {
+        let data = Bytes::from(vec![0xab; size]);
+        let calldata = get_calldata(
+            get_selector_from_sig("x_call_or_bubble(address,bytes)"),
+            (echo, data.clone()).abi_encode_params(),
+        );
+        let res = run_tx(&mut db, &r55, calldata, &ALICE).expect("Error executing tx");
+        let output = Bytes::abi_decode(&res.output, true).expect("Invalid output");
+        assert_eq!(output, data, "Return data not copied intact");
+        res.gas_used
+    };
+
+    let small = echo_data(32);
+    let large = echo_data(4096);
+
+    // `RETURNDATACOPY` costs 3 gas per copied word on top of everything else that grows with the
+    // size of the data (calldata, memory, instructions)
+    let copy_words = (4096 - 32) / 32;
+    assert!(
+        large - small >= 3 * copy_words,
+        "Gas doesn't scale with the copied return data (small: {}, large: {})",
+        small,
+        large
+    );
+}
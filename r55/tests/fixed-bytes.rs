@@ -0,0 +1,112 @@
+use alloy_primitives::{b256, fixed_bytes, keccak256, Address, B256, U256};
+use alloy_sol_types::SolValue;
+use r55::{
+    exec::{deploy_contract, run_tx},
+    get_bytecode,
+    test_utils::{
+        add_balance_to_db, get_calldata, get_selector_from_sig, initialize_logger, read_db_slot,
+        ALICE,
+    },
+};
+use revm::InMemoryDB;
+
+fn fixed_bytes_setup() -> (InMemoryDB, Address) {
+    initialize_logger();
+    let mut db = InMemoryDB::default();
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+
+    // Deploy contract
+    let bytecode = get_bytecode("fixed_bytes");
+    let fixed_bytes = deploy_contract(&mut db, bytecode, None).unwrap();
+
+    (db, fixed_bytes)
+}
+
+#[test]
+fn test_fixed_bytes_args() {
+    let (mut db, fixed_bytes) = fixed_bytes_setup();
+
+    // `B256` args are exposed as `bytes32`
+    let hash = keccak256("r55");
+    let calldata = get_calldata(
+        get_selector_from_sig("store_hash(bytes32)"),
+        hash.abi_encode(),
+    );
+    run_tx(&mut db, &fixed_bytes, calldata, &ALICE).expect("Error executing tx");
+
+    let hash_result = run_tx(
+        &mut db,
+        &fixed_bytes,
+        get_selector_from_sig("hash()").to_vec(),
+        &ALICE,
+    )
+    .expect("Error executing tx")
+    .output;
+    assert_eq!(B256::abi_decode(&hash_result, true).unwrap(), hash);
+
+    // `B32` args are exposed as `bytes4`
+    let selector = fixed_bytes!("deadbeef");
+    let calldata = get_calldata(
+        get_selector_from_sig("store_selector(bytes4)"),
+        selector.abi_encode(),
+    );
+    run_tx(&mut db, &fixed_bytes, calldata, &ALICE).expect("Error executing tx");
+
+    let selector_result = run_tx(
+        &mut db,
+        &fixed_bytes,
+        get_selector_from_sig("selector()").to_vec(),
+        &ALICE,
+    )
+    .expect("Error executing tx")
+    .output;
+    assert_eq!(
+        B256::from_slice(&selector_result),
+        b256!("deadbeef00000000000000000000000000000000000000000000000000000000"),
+        "`bytes4` must be left-aligned in the ABI word"
+    );
+}
+
+#[test]
+fn test_fixed_bytes_storage_layout() {
+    let (mut db, fixed_bytes) = fixed_bytes_setup();
+
+    let (hash, selector, tag) = (
+        keccak256("r55"),
+        fixed_bytes!("deadbeef"),
+        fixed_bytes!("2a"),
+    );
+    for (sig, args) in [
+        ("store_hash(bytes32)", hash.abi_encode()),
+        ("store_selector(bytes4)", selector.abi_encode()),
+        ("store_tag(bytes1)", tag.abi_encode()),
+    ] {
+        let calldata = get_calldata(get_selector_from_sig(sig), args);
+        run_tx(&mut db, &fixed_bytes, calldata, &ALICE).expect("Error executing tx");
+    }
+
+    // Like Solidity, `bytesN` values are right-aligned in their slot
+    assert_eq!(
+        read_db_slot(&mut db, fixed_bytes, U256::from(0)),
+        U256::from_be_bytes(hash.0)
+    );
+    assert_eq!(
+        read_db_slot(&mut db, fixed_bytes, U256::from(1)),
+        U256::from(0xdeadbeef_u64)
+    );
+    assert_eq!(
+        read_db_slot(&mut db, fixed_bytes, U256::from(2)),
+        U256::from(0x2a)
+    );
+
+    // Values round-trip back into their (left-aligned) ABI encoding
+    let tag_result = run_tx(
+        &mut db,
+        &fixed_bytes,
+        get_selector_from_sig("tag()").to_vec(),
+        &ALICE,
+    )
+    .expect("Error executing tx")
+    .output;
+    assert_eq!(tag_result, tag.abi_encode());
+}
@@ -0,0 +1,70 @@
+use alloy_primitives::Address;
+use alloy_sol_types::SolValue;
+use r55::{
+    exec::{deploy_contract, run_tx},
+    get_bytecode,
+    test_utils::{
+        add_balance_to_db, get_calldata, get_selector_from_sig, initialize_logger, ALICE,
+    },
+};
+use revm::InMemoryDB;
+
+fn memory_setup() -> (InMemoryDB, Address) {
+    initialize_logger();
+    let mut db = InMemoryDB::default();
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+
+    // Deploy contract
+    let bytecode = get_bytecode("memory");
+    let memory = deploy_contract(&mut db, bytecode, None).unwrap();
+
+    (db, memory)
+}
+
+#[test]
+fn test_memory_growth_gas() {
+    let (mut db, memory) = memory_setup();
+
+    // Same instructions, but the heavy call spreads its writes over a page each
+    let mut touch = |stride: u64| {
+        let calldata = get_calldata(get_selector_from_sig("touch(uint64)"), stride.abi_encode());
+        let result = run_tx(&mut db, &memory, calldata, &ALICE).expect("Error executing tx");
+        assert_eq!(u64::abi_decode(&result.output, true).unwrap(), 64);
+        result.gas_used
+    };
+    let light_gas = touch(1);
+    let heavy_gas = touch(4096);
+
+    // 63 extra pages are charged on top of the (identical) instructions gas
+    assert!(
+        heavy_gas > light_gas + 100_000,
+        "Memory growth isn't charged: heavy {} vs light {}",
+        heavy_gas,
+        light_gas
+    );
+}
+
+#[test]
+fn test_log_out_of_bounds() {
+    let (mut db, memory) = memory_setup();
+
+    let mut log_at = |data_ptr: u64, data_size: u64| {
+        let calldata = get_calldata(
+            get_selector_from_sig("log_at(uint64,uint64)"),
+            (data_ptr, data_size).abi_encode_params(),
+        );
+        run_tx(&mut db, &memory, calldata, &ALICE)
+    };
+
+    // Logging the calldata (at the DRAM base) works
+    let result = log_at(0x8000_0000, 4).expect("Error executing tx");
+    assert!(result.status);
+    assert_eq!(result.logs.len(), 1);
+    assert_eq!(result.logs[0].data.data.len(), 4);
+
+    // Ranges outside of the DRAM revert, rather than emitting an empty log
+    for (data_ptr, data_size) in [(0x10, 32), (0x8000_0000, 1 << 40), (u64::MAX - 4, 32)] {
+        let err = log_at(data_ptr, data_size).expect_err("Out of bounds log succeeded");
+        assert!(err.matches_string_error(""), "Unexpected error: {:?}", err);
+    }
+}
@@ -0,0 +1,71 @@
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolValue;
+use r55::{
+    exec::{deploy_contract, query, run_tx},
+    get_bytecode,
+    test_utils::{
+        add_balance_to_db, get_calldata, get_selector_from_sig, initialize_logger, ALICE, BOB,
+    },
+};
+use revm::InMemoryDB;
+
+fn registry_setup() -> (InMemoryDB, Address) {
+    initialize_logger();
+    let mut db = InMemoryDB::default();
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+
+    // Deploy contract
+    let bytecode = get_bytecode("non_zero");
+    let registry = deploy_contract(&mut db, bytecode, None).unwrap();
+
+    (db, registry)
+}
+
+#[test]
+fn test_non_zero_params() {
+    let (mut db, registry) = registry_setup();
+    let id = U256::from(7);
+
+    // `#[non_zero(to, error = ..)]` rejects the zero address with the configured error
+    let selector_assign = get_selector_from_sig("assign(uint256,address)");
+    let calldata_assign = get_calldata(selector_assign, (id, Address::ZERO).abi_encode());
+    let err = run_tx(&mut db, &registry, calldata_assign, &ALICE)
+        .expect_err("Assignment to the zero address succeeded when it should fail");
+    assert!(
+        err.matches_custom_error("RegistryError::ZeroAddress"),
+        "Incorrect error signature"
+    );
+
+    // Non-zero addresses go through
+    let calldata_assign = get_calldata(selector_assign, (id, ALICE).abi_encode());
+    run_tx(&mut db, &registry, calldata_assign, &ALICE).expect("Error executing tx");
+
+    // Without an error, the revert names the offending param. The check precedes the method
+    // body, which would otherwise revert with `NotOwner`
+    let selector_reassign = get_selector_from_sig("reassign(uint256,address,address)");
+    for (from, to, message) in [
+        (ALICE, Address::ZERO, "Zero address: to"),
+        (Address::ZERO, BOB, "Zero address: from"),
+    ] {
+        let calldata = get_calldata(selector_reassign, (U256::from(8), from, to).abi_encode());
+        let err = run_tx(&mut db, &registry, calldata, &ALICE)
+            .expect_err("Reassignment with the zero address succeeded when it should fail");
+        assert!(
+            err.matches_string_error(message),
+            "Unexpected revert: {}",
+            err
+        );
+    }
+
+    let calldata_reassign = get_calldata(selector_reassign, (id, ALICE, BOB).abi_encode());
+    run_tx(&mut db, &registry, calldata_reassign, &ALICE).expect("Error executing tx");
+    let selector_owner_of = get_selector_from_sig("owner_of(uint256)");
+    let owner: Address = query(
+        &mut db,
+        &registry,
+        get_calldata(selector_owner_of, id.abi_encode()),
+        &ALICE,
+    )
+    .unwrap();
+    assert_eq!(owner, BOB);
+}
@@ -0,0 +1,330 @@
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_sol_types::SolValue;
+use r55::{
+    exec::{deploy_contract, run_tx},
+    get_bytecode,
+    test_utils::{
+        add_balance_to_db, get_calldata, get_mapping_slot, get_selector_from_sig,
+        initialize_logger, keccak256, read_db_slot, ALICE, BOB,
+    },
+};
+use revm::InMemoryDB;
+
+fn packed_setup() -> (InMemoryDB, Address) {
+    initialize_logger();
+    let mut db = InMemoryDB::default();
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+
+    // Deploy contract
+    let bytecode = get_bytecode("packed");
+    let packed = deploy_contract(&mut db, bytecode, None).unwrap();
+
+    (db, packed)
+}
+
+fn get(db: &mut InMemoryDB, packed: Address, sig: &str) -> Vec<u8> {
+    run_tx(db, &packed, get_selector_from_sig(sig).to_vec(), &ALICE)
+        .expect("Error executing tx")
+        .output
+        .to_vec()
+}
+
+#[test]
+fn test_tuple_single_slot() {
+    let (mut db, packed) = packed_setup();
+
+    let (first, second) = (u64::MAX - 1, 42_u64);
+    let calldata = get_calldata(
+        get_selector_from_sig("set_pair(uint64,uint64)"),
+        (first, second).abi_encode_params(),
+    );
+    let result = run_tx(&mut db, &packed, calldata, &ALICE).expect("Error executing tx");
+    assert!(result.status, "Set transaction failed");
+
+    // Like Solidity, the first member takes the lowest-order bytes of the slot
+    assert_eq!(
+        read_db_slot(&mut db, packed, U256::ZERO),
+        (U256::from(second) << 64) | U256::from(first)
+    );
+    assert_eq!(read_db_slot(&mut db, packed, U256::from(1)), U256::ZERO);
+
+    // Both members are read back from the single slot
+    assert_eq!(
+        <(u64, u64)>::abi_decode(&get(&mut db, packed, "pair()"), true).unwrap(),
+        (first, second)
+    );
+    assert_eq!(
+        u64::abi_decode(&get(&mut db, packed, "first()"), true).unwrap(),
+        first
+    );
+    assert_eq!(
+        u64::abi_decode(&get(&mut db, packed, "second()"), true).unwrap(),
+        second
+    );
+}
+
+#[test]
+fn test_array_consecutive_slots() {
+    let (mut db, packed) = packed_setup();
+
+    let words = [1, 2, 3, 4].map(|i| U256::MAX - U256::from(i));
+    let last = U256::from(42);
+    let calldata = get_calldata(
+        get_selector_from_sig("set_words(uint256[4],uint256)"),
+        (words, last).abi_encode_params(),
+    );
+    let result = run_tx(&mut db, &packed, calldata, &ALICE).expect("Error executing tx");
+    assert!(result.status, "Set transaction failed");
+
+    // `words` is allocated slot 1, so each element lives at base + i
+    for (i, word) in words.iter().enumerate() {
+        assert_eq!(read_db_slot(&mut db, packed, U256::from(1 + i)), *word);
+    }
+    assert_eq!(read_db_slot(&mut db, packed, U256::from(5)), last);
+
+    assert_eq!(
+        <[U256; 4]>::abi_decode(&get(&mut db, packed, "words()"), true).unwrap(),
+        words
+    );
+}
+
+#[test]
+fn test_mapping_slot_preimage_log() {
+    let (mut db, packed) = packed_setup();
+
+    let amount = U256::from(7);
+    let calldata = get_calldata(
+        get_selector_from_sig("set_balance(address,uint256)"),
+        (BOB, amount).abi_encode(),
+    );
+    let result = run_tx(&mut db, &packed, calldata, &ALICE).expect("Error executing tx");
+    assert!(result.status, "Set transaction failed");
+
+    // `balances` is allocated the slot after `last`, which seeds the keccak of its entries
+    let mapping_id = U256::from(6);
+    let slot = get_mapping_slot(BOB.abi_encode(), mapping_id);
+    assert_eq!(read_db_slot(&mut db, packed, slot), amount);
+
+    // The runtime logs the preimage (`key ++ id`), with the resulting slot as its topic
+    let mut preimage = BOB.abi_encode();
+    preimage.extend_from_slice(&mapping_id.to_be_bytes::<32>());
+
+    assert_eq!(result.logs.len(), 1);
+    assert_eq!(result.logs[0].topics(), [B256::from(slot)]);
+    assert_eq!(result.logs[0].data.data, Bytes::from(preimage));
+}
+
+#[test]
+fn test_mapping_modify() {
+    let (mut db, packed) = packed_setup();
+    let slot = get_mapping_slot(BOB.abi_encode(), U256::from(6));
+
+    for _ in 0..2 {
+        let calldata = get_calldata(
+            get_selector_from_sig("add_balance(address,uint256)"),
+            (BOB, U256::from(5)).abi_encode(),
+        );
+        let result = run_tx(&mut db, &packed, calldata, &ALICE).expect("Error executing tx");
+        assert!(result.status, "Add transaction failed");
+
+        // The read and the write share a single key derivation (one preimage log)
+        assert_eq!(result.logs.len(), 1);
+        assert_eq!(result.logs[0].topics(), [B256::from(slot)]);
+    }
+
+    assert_eq!(read_db_slot(&mut db, packed, slot), U256::from(10));
+}
+
+#[test]
+fn test_unified_storage_trait() {
+    let (mut db, packed) = packed_setup();
+
+    let last = U256::from(42);
+    let calldata = get_calldata(
+        get_selector_from_sig("set_words(uint256[4],uint256)"),
+        ([U256::ZERO; 4], last).abi_encode_params(),
+    );
+    run_tx(&mut db, &packed, calldata, &ALICE).expect("Error executing tx");
+
+    // `move_last` is generic over the unified storage trait, taking `Slot<U256>` as the
+    // source and the `Mapping<Address, Slot<U256>>` entry as the destination
+    let calldata = get_calldata(
+        get_selector_from_sig("move_last(address)"),
+        BOB.abi_encode(),
+    );
+    let result = run_tx(&mut db, &packed, calldata, &ALICE).expect("Error executing tx");
+    assert!(result.status, "Move transaction failed");
+
+    let slot = get_mapping_slot(BOB.abi_encode(), U256::from(6));
+    assert_eq!(read_db_slot(&mut db, packed, U256::from(5)), U256::ZERO);
+    assert_eq!(read_db_slot(&mut db, packed, slot), last);
+
+    // Reads through either kind of storage decode the same value type
+    assert_eq!(
+        U256::abi_decode(&get(&mut db, packed, "last()"), true).unwrap(),
+        U256::ZERO
+    );
+    let calldata = get_calldata(
+        get_selector_from_sig("balance_of(address)"),
+        BOB.abi_encode(),
+    );
+    let result = run_tx(&mut db, &packed, calldata, &ALICE).expect("Error executing tx");
+    assert_eq!(U256::abi_decode(&result.output, true).unwrap(), last);
+
+    // Reads don't log preimages, so they can also go through staticcalls
+    assert!(result.logs.is_empty());
+}
+
+#[test]
+fn test_observable_slot_log() {
+    let (mut db, packed) = packed_setup();
+
+    // `observed` is allocated the slot after `balances`
+    let slot = U256::from(7);
+    let topic = keccak256("SlotChanged(uint256,uint256,uint256)");
+
+    let mut old = U256::ZERO;
+    for value in [U256::from(3), U256::from(5)] {
+        let calldata = get_calldata(
+            get_selector_from_sig("set_observed(uint256)"),
+            value.abi_encode(),
+        );
+        let result = run_tx(&mut db, &packed, calldata, &ALICE).expect("Error executing tx");
+        assert!(result.status, "Set transaction failed");
+        assert_eq!(read_db_slot(&mut db, packed, slot), value);
+
+        // Each write logs the old and new values, with the slot as indexed topic
+        assert_eq!(result.logs.len(), 1);
+        assert_eq!(result.logs[0].topics(), [topic, B256::from(slot)]);
+        assert_eq!(
+            result.logs[0].data.data,
+            Bytes::from((old, value).abi_encode_params())
+        );
+        old = value;
+    }
+
+    assert_eq!(
+        U256::abi_decode(&get(&mut db, packed, "observed()"), true).unwrap(),
+        old
+    );
+}
+
+#[test]
+fn test_namespaced_slot() {
+    let (mut db, packed) = packed_setup();
+
+    // ERC-7201 base slot, far beyond the sequential slots of the storage struct
+    let id = U256::from_be_bytes(keccak256("r55.packed.namespaced").0) - U256::from(1);
+    let slot = U256::from_be_bytes(keccak256(id.to_be_bytes::<32>()).0) & !U256::from(0xff);
+    assert!(slot > U256::from(u64::MAX));
+
+    let value = U256::from(42);
+    let calldata = get_calldata(
+        get_selector_from_sig("set_namespaced(uint256)"),
+        value.abi_encode(),
+    );
+    let result = run_tx(&mut db, &packed, calldata, &ALICE).expect("Error executing tx");
+    assert!(result.status, "Set transaction failed");
+
+    assert_eq!(read_db_slot(&mut db, packed, slot), value);
+    assert_eq!(
+        U256::abi_decode(&get(&mut db, packed, "namespaced()"), true).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_counter_sequential_ids() {
+    let (mut db, packed) = packed_setup();
+    let selector_allocate = get_selector_from_sig("allocate_id()");
+
+    // Each call returns the pre-increment value, so ids never repeat
+    let ids: Vec<U256> = (0..3)
+        .map(|_| {
+            let result = run_tx(&mut db, &packed, selector_allocate.to_vec(), &ALICE)
+                .expect("Error executing tx");
+            U256::abi_decode(&result.output, true).unwrap()
+        })
+        .collect();
+    assert_eq!(ids, vec![U256::ZERO, U256::from(1), U256::from(2)]);
+    assert_eq!(
+        U256::abi_decode(&get(&mut db, packed, "next_id()"), true).unwrap(),
+        U256::from(3)
+    );
+
+    // `next_id` follows `observed` in the storage layout
+    let counter_slot = U256::from(8);
+    assert_eq!(read_db_slot(&mut db, packed, counter_slot), U256::from(3));
+
+    // The last id can still be allocated, but the counter can't wrap around to zero
+    db.insert_account_storage(packed, counter_slot, U256::MAX - U256::from(1))
+        .unwrap();
+    let result =
+        run_tx(&mut db, &packed, selector_allocate.to_vec(), &ALICE).expect("Error executing tx");
+    assert_eq!(
+        U256::abi_decode(&result.output, true).unwrap(),
+        U256::MAX - U256::from(1)
+    );
+
+    let err = run_tx(&mut db, &packed, selector_allocate.to_vec(), &ALICE)
+        .expect_err("Counter overflow should revert");
+    assert!(
+        err.matches_string_error("Counter overflow"),
+        "Incorrect error"
+    );
+    assert_eq!(read_db_slot(&mut db, packed, counter_slot), U256::MAX);
+}
+
+#[test]
+fn test_storage_vec_swap_remove() {
+    let (mut db, packed) = packed_setup();
+
+    let members: Vec<Address> = (1..=4).map(Address::repeat_byte).collect();
+    for member in &members {
+        let calldata = get_calldata(
+            get_selector_from_sig("add_member(address)"),
+            member.abi_encode(),
+        );
+        run_tx(&mut db, &packed, calldata, &ALICE).expect("Error executing tx");
+    }
+
+    // Removing a middle member moves the last one into its slot
+    let calldata = get_calldata(
+        get_selector_from_sig("remove_member(uint64)"),
+        1_u64.abi_encode(),
+    );
+    let result = run_tx(&mut db, &packed, calldata, &ALICE).expect("Error executing tx");
+    assert_eq!(
+        Address::abi_decode(&result.output, true).unwrap(),
+        members[1]
+    );
+    assert_eq!(
+        Vec::<Address>::abi_decode(&get(&mut db, packed, "members()"), true).unwrap(),
+        vec![members[0], members[3], members[2]]
+    );
+
+    // Solidity layout: the length at the field slot, the elements from its hash onwards
+    let length_slot = U256::from(9);
+    let first_slot: U256 = keccak256(length_slot.to_be_bytes::<32>()).into();
+    assert_eq!(read_db_slot(&mut db, packed, length_slot), U256::from(3));
+    assert_eq!(
+        read_db_slot(&mut db, packed, first_slot + U256::from(1)),
+        U256::from_be_slice(members[3].as_slice())
+    );
+    // The slot of the popped last element is cleared
+    assert_eq!(
+        read_db_slot(&mut db, packed, first_slot + U256::from(3)),
+        U256::ZERO
+    );
+
+    // Out of bounds removals revert
+    let calldata = get_calldata(
+        get_selector_from_sig("remove_member(uint64)"),
+        3_u64.abi_encode(),
+    );
+    let err = run_tx(&mut db, &packed, calldata, &ALICE).expect_err("Tx succeeded");
+    assert!(
+        err.matches_string_error("Index out of bounds"),
+        "Incorrect error"
+    );
+}
@@ -0,0 +1,68 @@
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolValue;
+use r55::{
+    exec::{deploy_contract, run_tx, run_tx_with_value},
+    get_bytecode,
+    test_utils::{
+        account_info, add_balance_to_db, get_calldata, get_selector_from_sig, initialize_logger,
+        ALICE, BOB,
+    },
+};
+use revm::InMemoryDB;
+
+fn payable_token_setup() -> (InMemoryDB, Address) {
+    initialize_logger();
+    let mut db = InMemoryDB::default();
+
+    // Fund user accounts with some ETH
+    for user in [ALICE, BOB] {
+        add_balance_to_db(&mut db, user, 1e18 as u64);
+    }
+
+    // Deploy contract
+    let bytecode = get_bytecode("payable_token");
+    let token = deploy_contract(&mut db, bytecode, None).unwrap();
+
+    (db, token)
+}
+
+#[test]
+fn test_payable_mint() {
+    let (mut db, token) = payable_token_setup();
+    let value = U256::from(1e16);
+    let contract_balance = |db: &mut InMemoryDB| account_info(db, token).unwrap().balance;
+    assert_eq!(contract_balance(&mut db), U256::ZERO);
+
+    // Paying into `mint` credits the value to the payer, and keeps the ETH in the contract
+    let selector_mint = get_selector_from_sig("mint(address,uint256)");
+    let calldata_mint = get_calldata(selector_mint, (BOB, U256::from(1e18)).abi_encode());
+    let result = run_tx_with_value(&mut db, &token, calldata_mint.clone(), &ALICE, value)
+        .expect("Error executing tx");
+    assert!(result.status, "Payable mint failed");
+    assert_eq!(
+        contract_balance(&mut db),
+        value,
+        "Contract balance not increased"
+    );
+
+    let selector_deposit = get_selector_from_sig("deposit_of(address)");
+    let calldata_deposit = get_calldata(selector_deposit, ALICE.abi_encode());
+    let deposit = run_tx(&mut db, &token, calldata_deposit.clone(), &ALICE)
+        .expect("Error executing tx")
+        .output;
+    assert_eq!(U256::from_be_slice(&deposit), value, "Incorrect deposit");
+
+    // Without any value, the payable mint still goes through, and doesn't credit anything
+    run_tx(&mut db, &token, calldata_mint, &ALICE).expect("Error executing tx");
+    let deposit = run_tx(&mut db, &token, calldata_deposit, &ALICE)
+        .expect("Error executing tx")
+        .output;
+    assert_eq!(U256::from_be_slice(&deposit), value, "Incorrect deposit");
+
+    // Non-payable methods still reject any value
+    let selector_burn = get_selector_from_sig("burn(uint256)");
+    let calldata_burn = get_calldata(selector_burn, U256::from(1).abi_encode());
+    run_tx_with_value(&mut db, &token, calldata_burn, &BOB, value)
+        .expect_err("Non-payable method accepted value");
+    assert_eq!(contract_balance(&mut db), value);
+}
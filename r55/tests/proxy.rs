@@ -0,0 +1,46 @@
+use alloy_primitives::{Address, Bytes};
+use alloy_sol_types::SolValue;
+use r55::{
+    exec::{deploy_contract, run_tx},
+    get_bytecode,
+    test_utils::{
+        add_balance_to_db, get_calldata, get_selector_from_sig, initialize_logger, ALICE,
+    },
+};
+use revm::InMemoryDB;
+
+fn proxy_setup() -> (InMemoryDB, Address, Address) {
+    initialize_logger();
+    let mut db = InMemoryDB::default();
+    add_balance_to_db(&mut db, ALICE, 1e18 as u64);
+
+    // Deploy contracts
+    let proxy = deploy_contract(&mut db, get_bytecode("proxy"), None).unwrap();
+    let logic = deploy_contract(&mut db, get_bytecode("env_info"), None).unwrap();
+
+    (db, proxy, logic)
+}
+
+#[test]
+fn test_delegatecall_preserves_msg_sender() {
+    let (mut db, proxy, logic) = proxy_setup();
+
+    // call traces: ALICE -> proxy.delegate() -(delegatecall)-> logic.sender()
+    let selector_sender = get_selector_from_sig("sender()");
+    let selector_delegate = get_selector_from_sig("delegate(address,bytes)");
+    let calldata_delegate = get_calldata(
+        selector_delegate,
+        (logic, Bytes::from(selector_sender.to_vec())).abi_encode_params(),
+    );
+
+    let delegate_result = run_tx(&mut db, &proxy, calldata_delegate, &ALICE)
+        .expect("Error executing tx")
+        .output;
+
+    // The logic contract sees the proxy's caller, not the proxy itself
+    assert_eq!(
+        Address::abi_decode(&delegate_result, true).unwrap(),
+        ALICE,
+        "Incorrect msg_sender in delegatecall frame"
+    );
+}
@@ -0,0 +1,63 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::SolValue;
+use r55::{
+    exec::{deploy_contract, query, run_tx},
+    get_bytecode,
+    test_utils::{
+        add_balance_to_db, get_calldata, get_selector_from_sig, initialize_logger, ALICE, BOB,
+    },
+};
+use revm::InMemoryDB;
+
+fn relayer_setup(owner: Address) -> (InMemoryDB, Address) {
+    initialize_logger();
+    let mut db = InMemoryDB::default();
+
+    // Fund user accounts with some ETH
+    for user in [ALICE, BOB] {
+        add_balance_to_db(&mut db, user, 1e18 as u64);
+    }
+
+    // Deploy contract
+    let bytecode = get_bytecode("relayer");
+    let relayer = deploy_contract(&mut db, bytecode, Some(owner.abi_encode())).unwrap();
+
+    (db, relayer)
+}
+
+#[test]
+fn test_relayer_execute() {
+    let (mut db, relayer) = relayer_setup(ALICE);
+
+    let selector_set = get_selector_from_sig("set(uint256)");
+    let selector_execute = get_selector_from_sig("execute(bytes)");
+    let execute = |value: U256| {
+        let calldata_set = get_calldata(selector_set, value.abi_encode());
+        get_calldata(
+            selector_execute,
+            (Bytes::from(calldata_set),).abi_encode_params(),
+        )
+    };
+
+    // The forwarded call returns its own output, ABI-encoded as `bytes`
+    let value = U256::from(42);
+    let result = run_tx(&mut db, &relayer, execute(value), &ALICE).expect("Error executing tx");
+    let output = Bytes::abi_decode(&result.output, true).expect("Output is not `bytes`");
+    assert!(bool::abi_decode(&output, true).unwrap());
+
+    // The relayed call runs with the relayer as `msg.sender`
+    let selector_value_of = get_selector_from_sig("value_of(address)");
+    let calldata_value_of = get_calldata(selector_value_of, ALICE.abi_encode());
+    let stored: U256 = query(&mut db, &relayer, calldata_value_of, &ALICE).unwrap();
+    assert_eq!(stored, value, "Relayed call not applied");
+
+    // ... or reverts with its own error
+    let err = run_tx(&mut db, &relayer, execute(U256::ZERO), &ALICE)
+        .expect_err("Relayed call succeeded when it should fail");
+    assert!(err.matches_custom_error("RelayerError::ZeroValue"));
+
+    // Untrusted relayers can't forward any call
+    let err = run_tx(&mut db, &relayer, execute(value), &BOB)
+        .expect_err("Untrusted relayer succeeded when it should fail");
+    assert!(err.matches_string_error("Untrusted relayer"));
+}
@@ -0,0 +1,125 @@
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolValue;
+use r55::{
+    exec::{deploy_contract, run_tx, run_tx_with_reentrancy_detection},
+    get_bytecode,
+    test_utils::{
+        add_balance_to_db, get_calldata, get_selector_from_sig, initialize_logger, ALICE,
+    },
+};
+use revm::InMemoryDB;
+
+fn self_caller_setup(owner: Address) -> (InMemoryDB, Address) {
+    initialize_logger();
+    let mut db = InMemoryDB::default();
+    add_balance_to_db(&mut db, owner, 1e18 as u64);
+
+    // Deploy contract
+    let bytecode = get_bytecode("self_caller");
+    let contract = deploy_contract(&mut db, bytecode, Some(owner.abi_encode())).unwrap();
+
+    (db, contract)
+}
+
+#[test]
+fn test_self_call() {
+    let (mut db, contract) = self_caller_setup(ALICE);
+
+    let mint_amount = U256::from(42e18);
+    let selector_self_mint = get_selector_from_sig("self_mint(uint256)");
+    let calldata_self_mint = get_calldata(selector_self_mint, mint_amount.abi_encode());
+
+    // The self-call is a new frame, where the contract itself is the `msg_sender`
+    let err = run_tx(&mut db, &contract, calldata_self_mint.clone(), &ALICE)
+        .expect_err("Self-mint succeeded when it should fail");
+    assert!(
+        err.matches_custom_error("SelfCallerError::OnlyOwner"),
+        "Incorrect error signature"
+    );
+
+    let selector_set_owner = get_selector_from_sig("set_owner(address)");
+    let calldata_set_owner = get_calldata(selector_set_owner, contract.abi_encode());
+    run_tx(&mut db, &contract, calldata_set_owner, &ALICE).expect("Error executing tx");
+
+    let result =
+        run_tx(&mut db, &contract, calldata_self_mint, &ALICE).expect("Error executing tx");
+    assert!(result.status, "Self-mint transaction failed");
+
+    // The reentrant frame reads the storage of the contract
+    let selector_self_total_supply = get_selector_from_sig("self_total_supply()");
+    let res = run_tx_with_reentrancy_detection(
+        &mut db,
+        &contract,
+        selector_self_total_supply.to_vec(),
+        &ALICE,
+    )
+    .expect("Error executing tx");
+    assert_eq!(
+        U256::abi_decode(&res.output, true).unwrap(),
+        mint_amount,
+        "Incorrect total supply"
+    );
+    assert_eq!(res.reentrancies.len(), 1, "Self-call not reported");
+    assert_eq!(res.reentrancies[0].address, contract);
+    assert_eq!(res.reentrancies[0].depth, 1);
+}
+
+#[test]
+fn test_call_through_own_interface() {
+    let (mut db, contract) = self_caller_setup(ALICE);
+    let other = deploy_contract(
+        &mut db,
+        get_bytecode("self_caller"),
+        Some(ALICE.abi_encode()),
+    )
+    .unwrap();
+
+    let mint_amount = U256::from(42e18);
+    let selector_mint = get_selector_from_sig("mint(uint256)");
+    let calldata_mint = get_calldata(selector_mint, mint_amount.abi_encode());
+    run_tx(&mut db, &other, calldata_mint, &ALICE).expect("Error executing tx");
+
+    // `contract` reaches the other instance through `SelfCaller::at`, and itself the same way
+    let selector_total_supply_at = get_selector_from_sig("total_supply_at(address)");
+    for (target, expected) in [(other, mint_amount), (contract, U256::ZERO)] {
+        let calldata = get_calldata(selector_total_supply_at, target.abi_encode());
+        let res = run_tx(&mut db, &contract, calldata, &ALICE).expect("Error executing tx");
+        assert_eq!(U256::abi_decode(&res.output, true).unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_unit_returning_self_call() {
+    let (mut db, contract) = self_caller_setup(ALICE);
+
+    let mint_amount = U256::from(42e18);
+    let selector_mint = get_selector_from_sig("mint(uint256)");
+    let calldata_mint = get_calldata(selector_mint, mint_amount.abi_encode());
+    run_tx(&mut db, &contract, calldata_mint, &ALICE).expect("Error executing tx");
+
+    // `burn` doesn't return anything
+    let selector_burn = get_selector_from_sig("burn(uint256)");
+    let calldata_burn = get_calldata(selector_burn, U256::from(1e18).abi_encode());
+    let burn_result =
+        run_tx(&mut db, &contract, calldata_burn, &ALICE).expect("Error executing tx");
+    assert!(burn_result.is_empty_output());
+
+    // So its empty return data must decode into `()` on the caller side
+    let selector_self_burn = get_selector_from_sig("self_burn(uint256)");
+    let calldata_self_burn = get_calldata(selector_self_burn, U256::from(1e18).abi_encode());
+    let self_burn_result =
+        run_tx(&mut db, &contract, calldata_self_burn, &ALICE).expect("Error executing tx");
+    assert!(
+        bool::abi_decode(&self_burn_result.output, true).unwrap(),
+        "Unit-returning call was not successful"
+    );
+
+    let selector_total_supply = get_selector_from_sig("total_supply()");
+    let res = run_tx(&mut db, &contract, selector_total_supply.to_vec(), &ALICE)
+        .expect("Error executing tx");
+    assert_eq!(
+        U256::abi_decode(&res.output, true).unwrap(),
+        U256::from(40e18),
+        "Burnt supply not removed"
+    );
+}
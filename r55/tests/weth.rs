@@ -0,0 +1,111 @@
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolValue;
+use r55::{
+    exec::{deploy_contract, run_tx, run_tx_with_value},
+    get_bytecode,
+    test_utils::{
+        add_balance_to_db, get_calldata, get_selector_from_sig, initialize_logger, keccak256,
+        ALICE, BOB,
+    },
+};
+use revm::InMemoryDB;
+
+fn weth_setup() -> (InMemoryDB, Address) {
+    initialize_logger();
+    let mut db = InMemoryDB::default();
+
+    // Fund user accounts with some ETH
+    for user in [ALICE, BOB] {
+        add_balance_to_db(&mut db, user, 1e18 as u64);
+    }
+
+    // Deploy contract
+    let bytecode = get_bytecode("weth");
+    let weth = deploy_contract(&mut db, bytecode, None).unwrap();
+
+    (db, weth)
+}
+
+fn balance_of(db: &mut InMemoryDB, weth: Address, owner: Address) -> U256 {
+    let selector_balance = get_selector_from_sig("balance_of(address)");
+    let calldata_balance = get_calldata(selector_balance, owner.abi_encode());
+
+    let balance_result = run_tx(db, &weth, calldata_balance, &owner)
+        .expect("Error executing tx")
+        .output;
+    U256::from_be_slice(balance_result.as_slice())
+}
+
+#[test]
+fn test_weth_receive() {
+    let (mut db, weth) = weth_setup();
+    let value = U256::from(25e16);
+
+    // Send ETH with empty calldata, so that `receive` credits the sender
+    let receive_result =
+        run_tx_with_value(&mut db, &weth, vec![], &ALICE, value).expect("Error executing tx");
+    assert!(receive_result.status, "Receive transaction failed");
+    assert_eq!(receive_result.logs.len(), 1, "Deposit event not emitted");
+
+    // Verify mapped balances
+    assert_eq!(balance_of(&mut db, weth, ALICE), value, "Incorrect balance");
+    assert_eq!(
+        balance_of(&mut db, weth, BOB),
+        U256::ZERO,
+        "Incorrect balance"
+    );
+
+    // Verify the contract holds the received ETH
+    let weth_account = db.load_account(weth).expect("Unable to load account");
+    assert_eq!(weth_account.info.balance, value, "Incorrect ETH balance");
+}
+
+#[test]
+fn test_weth_single_indexed_field_topics() {
+    let (mut db, weth) = weth_setup();
+    let value = U256::from(1e16);
+
+    let calldata_deposit = get_calldata(get_selector_from_sig("deposit()"), vec![]);
+    let deposit_result = run_tx_with_value(&mut db, &weth, calldata_deposit, &ALICE, value)
+        .expect("Error executing tx");
+    assert_eq!(deposit_result.logs.len(), 1, "Deposit event not emitted");
+
+    // `Deposit(address indexed to, uint256 amount)`: the signature plus the single indexed field,
+    // while the data only holds the non-indexed one
+    let log = &deposit_result.logs[0];
+    assert_eq!(log.address, weth);
+    assert_eq!(
+        log.topics(),
+        [keccak256("Deposit(address,uint256)"), ALICE.into_word()],
+        "Incorrect topics"
+    );
+    assert_eq!(log.data.data, value.abi_encode(), "Incorrect data");
+}
+
+#[test]
+fn test_weth_emit_macro_matches_log_emit() {
+    let (mut db, weth) = weth_setup();
+    let value = U256::from(1e16);
+
+    // `deposit` emits through `log::emit(Deposit::new(..))`
+    let calldata_deposit = get_calldata(get_selector_from_sig("deposit()"), vec![]);
+    let deposit_result = run_tx_with_value(&mut db, &weth, calldata_deposit, &ALICE, value)
+        .expect("Error executing tx");
+
+    // `deposit_to` emits through `emit!(Deposit, ..)`
+    let calldata_deposit_to = get_calldata(
+        get_selector_from_sig("deposit_to(address)"),
+        ALICE.abi_encode(),
+    );
+    let deposit_to_result = run_tx_with_value(&mut db, &weth, calldata_deposit_to, &ALICE, value)
+        .expect("Error executing tx");
+
+    assert_eq!(deposit_result.logs.len(), 1, "Deposit event not emitted");
+    assert_eq!(deposit_to_result.logs.len(), 1, "Deposit event not emitted");
+
+    // Both paths must produce identical topics and data
+    let (log, macro_log) = (&deposit_result.logs[0], &deposit_to_result.logs[0]);
+    assert_eq!(log.topics(), macro_log.topics(), "Topics mismatch");
+    assert_eq!(log.data.data, macro_log.data.data, "Data mismatch");
+    assert_eq!(balance_of(&mut db, weth, ALICE), value * U256::from(2));
+}